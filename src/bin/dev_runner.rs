@@ -1,82 +1,226 @@
 // src/bin/dev_runner.rs
 
 use mev_scalpel::{
-    config::Config,
-    execution::optimizer::{self, ArbitragePath},
+    config::{default_base_tokens, Config},
+    decoders::swap_instructions::{self, TransactionView},
+    decoders::PoolOperations,
+    execution::costs::CostModel,
+    execution::optimizer,
     graph_engine, // On importe le module graph_engine
-    state::MarketGraph,
+    graph_export::{self, GraphView},
+    snapshot,
     strategies,
-    decoders::PoolOperations
+    token_registry::TokenRegistry,
 };
 use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
 use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::UiTransactionEncoding;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::str::FromStr;
-use anyhow::Result;
 
-const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
 const SOL_DECIMALS: u32 = 9;
 
 #[tokio::main]
 async fn main() {
     println!("--- DEVELOPMENT RUNNER (STABILIZED) ---");
 
-    let config = Config::load().expect("Failed to load config");
-    let rpc_client = RpcClient::new(config.solana_rpc_url);
+    // Options : `--load <fichier>` rejoue un snapshot hors-ligne au lieu d'interroger le RPC,
+    // `--dump <fichier>` sauvegarde le graphe construit (`.json` => JSON, sinon binaire),
+    // `--export <fichier>` rend le graphe (et le cycle trouvé) en DOT, ou en JSON si `.json`,
+    // `--tx <signature>` décode les swaps d'une transaction exécutée et cherche leur backrun.
+    let args: Vec<String> = std::env::args().collect();
+    let load_path = arg_value(&args, "--load");
+    let dump_path = arg_value(&args, "--dump");
+    let export_path = arg_value(&args, "--export");
+    let tx_signature = arg_value(&args, "--tx");
 
-    // 1. On appelle le graph_engine pour construire notre graphe de test.
-    // Cette fonction contient maintenant toute la logique de fetch, decode, et hydrate.
-    let graph = graph_engine::build_hydrated_test_graph(&rpc_client);
+    // 1. On construit le graphe : depuis un snapshot, ou via le graph_engine.
+    // Ce dernier contient toute la logique de fetch, decode, et hydrate.
+    let graph = match &load_path {
+        Some(path) => {
+            println!("Loading graph snapshot from {}...", path.display());
+            snapshot::load_graph(path).expect("Failed to load graph snapshot")
+        }
+        None => {
+            let config = Config::load().expect("Failed to load config");
+            let rpc_client = RpcClient::new(config.solana_rpc_url);
+            graph_engine::build_hydrated_test_graph(&rpc_client)
+        }
+    };
+
+    if let Some(path) = &dump_path {
+        snapshot::save_graph(&graph, path).expect("Failed to save graph snapshot");
+        println!("Graph snapshot saved to {}", path.display());
+    }
+
+    // On ne garde que la partie du graphe utile aux stratégies : pas de feuilles,
+    // et uniquement les composantes connexes qui contiennent nos tokens de base.
+    let base_mints = match Config::load() {
+        Ok(config) => config.base_token_mints().expect("Invalid BASE_TOKENS"),
+        Err(_) => default_base_tokens().iter().map(|mint| Pubkey::from_str(mint).unwrap()).collect(),
+    };
+    let components = graph_engine::strongly_connected_components(&graph);
+    println!("Strongly connected components: {} (largest: {} tokens)",
+        components.len(), components.iter().map(|c| c.len()).max().unwrap_or(0));
+    let graph = graph_engine::restrict_to_base_components(&graph_engine::prune_leaves(&graph), &base_mints);
 
     println!("\n--- MINI-GRAPH BUILT SUCCESSFULLY ---");
-    println!("Total pools hydrated: {}", graph.nodes.iter().map(|e| e.len()).sum::<usize>() / 2);
+    println!("Total pools hydrated: {}", graph.pools.len());
     println!("Total tokens in graph: {}", graph.token_map.len());
     println!("-------------------------------------\n");
 
-    // 2. Lancement de la stratégie
-    let wsol_mint = Pubkey::from_str(SOL_MINT).unwrap();
-    if let Some(start_node_idx) = graph.token_map.get(&wsol_mint) {
-        println!("Running SPFA starting from WSOL...");
-        match strategies::spfa_arb::find_negative_cycle(&graph, *start_node_idx) {
-            Some(cycle_indices) => {
-                println!("\n--- !!! OPPORTUNITY FOUND !!! ---");
-                if let Ok(path) = build_path_for_optimizer(&graph, &cycle_indices) {
-                    let max_trade_amount = 100 * 10u64.pow(SOL_DECIMALS);
-                    if let Ok((optimal_amount, max_profit)) = optimizer::find_optimal_amount(&path, max_trade_amount) {
-                        println!("\n--- OPTIMIZATION COMPLETE ---");
-                        println!("Optimal trade amount: {} SOL", optimal_amount as f64 / 10f64.powi(SOL_DECIMALS as i32));
-                        println!("Predicted profit:     {} SOL", max_profit as f64 / 10f64.powi(SOL_DECIMALS as i32));
-                        println!("-----------------------------");
+    // 2. Lancement de la stratégie : SPFA depuis chaque token de base, en parallèle,
+    // sur le même instantané du graphe.
+    let graph = Arc::new(graph);
+    let mut found_cycle = None;
+    // Les arêtes sont évaluées à une taille de référence (en USD) propre à chaque token.
+    let registry = TokenRegistry::with_known_tokens();
+    let sizing = strategies::sizing::EdgeSizing::PerToken {
+        sizes: strategies::sizing::reference_sizes(&graph, &registry, strategies::sizing::DEFAULT_REFERENCE_USD),
+        fallback: strategies::spfa_arb::TEST_SWAP_AMOUNT,
+    };
+    // Les profits affichés sont nets des coûts d'exécution, convertis depuis le SOL.
+    let cost_model = CostModel::default();
+    let sol_equivalents = strategies::sizing::sol_equivalents(&graph);
+    let workers = strategies::spfa_arb::default_search_workers();
+    println!("Running SPFA from {} base token(s) on {} worker(s)...", base_mints.len(), workers);
+    let cycles = strategies::spfa_arb::find_negative_cycles(&graph, &base_mints, &sizing, workers);
+    if cycles.is_empty() {
+        println!("--- No opportunity found. ---");
+    }
+    for cycle in cycles {
+        let start_mint = graph.tokens[cycle.tokens[0]];
+        println!("\n--- !!! OPPORTUNITY FOUND ({}) !!! ---", registry.symbol(&start_mint));
+        println!("Cycle tokens: {:?} | pools: {:?}", cycle.tokens, cycle.pools);
+        if let Ok(path) = cycle.to_arbitrage_path(&graph) {
+            let max_trade_amount = registry
+                .amount_for_usd(&start_mint, 100.0 * strategies::sizing::DEFAULT_REFERENCE_USD)
+                .unwrap_or(100 * 10u64.pow(SOL_DECIMALS));
+            let Some(costs) = cost_model.path_costs(&path, sol_equivalents[cycle.tokens[0]]) else {
+                println!("No SOL price for the start token: execution costs unknown.");
+                found_cycle.get_or_insert(cycle);
+                continue;
+            };
+            if let Ok(report) = optimizer::optimize_path(&path, max_trade_amount, &costs) {
+                println!("\n--- OPTIMIZATION COMPLETE ---");
+                match report.outcome {
+                    optimizer::OptimizationOutcome::Profitable { amount_in, profit, gross_profit } => {
+                        println!("Optimal trade amount: {} (base units)", amount_in);
+                        println!("Predicted profit:     {} net, {} gross (base units)", profit, gross_profit);
+                    }
+                    optimizer::OptimizationOutcome::NoProfitableSize { best_amount, best_profit } => {
+                        println!("No profitable size (best: {} -> {})", best_amount, best_profit);
                     }
                 }
-            },
-            None => println!("--- No opportunity found. ---"),
+                println!("Curve: {} sample(s), {} tick crossing(s), feasible up to {}",
+                    report.samples.len(), report.breakpoints.len(), report.max_feasible_amount);
+                println!("-----------------------------");
+            }
         }
+        found_cycle.get_or_insert(cycle);
+    }
+
+    // 2 bis. Énumération exhaustive des cycles courts depuis les tokens de base.
+    let ranked = strategies::bounded_cycles::find_profitable_cycles(
+        &graph, &base_mints, strategies::bounded_cycles::MAX_HOPS, strategies::bounded_cycles::PROBE_AMOUNT,
+    );
+    println!("\nBounded cycle enumeration: {} profitable cycle(s)", ranked.len());
+    for ranked_cycle in ranked.iter().take(5) {
+        println!("  {:+.4}% | tokens: {:?} | pools: {:?}",
+            ranked_cycle.estimated_return * 100.0, ranked_cycle.cycle.tokens, ranked_cycle.cycle.pools);
     }
-}
 
-/// Construit le chemin pour l'optimiseur.
-fn build_path_for_optimizer<'a>(
-    graph: &'a MarketGraph,
-    cycle_indices: &[usize],
-) -> Result<Vec<ArbitragePath<'a>>> {
-    let mut path = Vec::new();
-    for i in 0..cycle_indices.len() - 1 {
-        let u_idx = cycle_indices[i];
-        let v_idx = cycle_indices[i + 1];
-
-        let edge = graph.nodes[u_idx]
-            .iter()
-            .find(|e| e.destination == v_idx)
-            .ok_or_else(|| anyhow::anyhow!("Could not find edge in graph"))?;
-
-        let (input_mint, output_mint) = edge.pool.get_mints();
-        let u_mint = graph.token_map.iter().find(|&(_, &v)| v == u_idx).unwrap().0;
-
-        path.push(ArbitragePath {
-            pool: &edge.pool,
-            input_mint: *u_mint,
-            output_mint: if input_mint == *u_mint { output_mint } else { input_mint },
-        });
+    // 2 ter. Arbitrage spatial : même paire, pools différents.
+    let spatial = strategies::spatial_arb::find_spatial_opportunities(
+        &graph, &base_mints, 100 * 10u64.pow(SOL_DECIMALS), &cost_model,
+    );
+    println!("\nSpatial arbitrage: {} opportunity(ies)", spatial.len());
+    for opportunity in spatial.iter().take(5) {
+        println!("  profit: {} | amount in: {} | pools: {:?}",
+            opportunity.expected_profit, opportunity.amount_in, opportunity.cycle.pools);
     }
-    Ok(path)
+
+    // 2 quater. Backrun : on simule un gros swap sur le premier pool et on cherche l'arbitrage qui suit.
+    if let Some(pool) = graph.pools.first() {
+        let (mint_a, _) = pool.get_mints();
+        let swap = strategies::backrunner::ObservedSwap {
+            pool_id: pool.get_id(),
+            input_mint: mint_a,
+            amount_in: 10 * 10u64.pow(SOL_DECIMALS),
+        };
+        let mut backrunner = strategies::backrunner::Backrunner::new(
+            &graph, &base_mints, strategies::bounded_cycles::MAX_HOPS, 100 * 10u64.pow(SOL_DECIMALS),
+        )
+        .with_cost_model(cost_model.clone());
+        match backrunner.find_backruns(&graph, 0, 0, None, std::slice::from_ref(&swap)) {
+            Ok(candidates) => {
+                println!("\nBackrun of a simulated swap on {}: {} candidate(s)", swap.pool_id, candidates.len());
+                for candidate in candidates.iter().take(5) {
+                    println!("  profit: {} | amount in: {} | pools: {:?}",
+                        candidate.opportunity.expected_profit, candidate.opportunity.amount_in, candidate.opportunity.pool_ids());
+                }
+            }
+            Err(e) => println!("\nBackrun simulation failed: {}", e),
+        }
+    }
+
+    // 2 quinquies. Backrun d'une vraie transaction : on décode ses swaps et on les rejoue.
+    if let Some(signature) = &tx_signature {
+        let signature = Signature::from_str(&signature.to_string_lossy()).expect("Invalid transaction signature");
+        let config = Config::load().expect("Failed to load config");
+        let rpc_client = RpcClient::new(config.solana_rpc_url);
+        let tx_config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: None,
+            max_supported_transaction_version: Some(0),
+        };
+        let encoded = rpc_client.get_transaction_with_config(&signature, tx_config).expect("Failed to fetch transaction");
+        let view = TransactionView::from_encoded(&encoded).expect("Failed to decode transaction");
+
+        println!("\nTransaction {}:", signature);
+        for decoded in swap_instructions::decode_swaps(&view) {
+            let resolved = decoded.resolve(&graph, &view);
+            println!("  #{}{} {} on {} | amount: {} | resolved: {:?}",
+                decoded.instruction_index,
+                decoded.inner_index.map(|i| format!(".{}", i)).unwrap_or_default(),
+                decoded.swap.dex, decoded.swap.pool_id, decoded.swap.amount, resolved);
+        }
+
+        let observed = swap_instructions::observed_swaps(&view, &graph);
+        let mut backrunner = strategies::backrunner::Backrunner::new(
+            &graph, &base_mints, strategies::bounded_cycles::MAX_HOPS, 100 * 10u64.pow(SOL_DECIMALS),
+        )
+        .with_cost_model(cost_model.clone());
+        match backrunner.find_backruns(&graph, 0, 0, Some(signature), &observed) {
+            Ok(candidates) => println!("Backrun candidates: {}", candidates.len()),
+            Err(e) => println!("Backrun failed: {}", e),
+        }
+    }
+
+    // 3. Export optionnel du graphe, avec le cycle trouvé mis en évidence.
+    if let Some(path) = &export_path {
+        let registry = TokenRegistry::with_known_tokens();
+        let mut view = GraphView::full(&graph);
+        if let Some(cycle) = &found_cycle {
+            view = view.with_highlighted_cycle(&cycle.tokens);
+        }
+        let rendered = if path.extension().is_some_and(|ext| ext == "json") {
+            graph_export::to_json(&graph, &registry, &view).expect("Failed to export graph")
+        } else {
+            graph_export::to_dot(&graph, &registry, &view)
+        };
+        std::fs::write(path, rendered).expect("Failed to write graph export");
+        println!("Graph exported to {}", path.display());
+    }
+}
+
+/// Retourne la valeur qui suit `flag` dans les arguments de la ligne de commande.
+fn arg_value(args: &[String], flag: &str) -> Option<PathBuf> {
+    args.iter()
+        .position(|arg| arg == flag)
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from)
 }
\ No newline at end of file
@@ -2,6 +2,18 @@
 
 use serde::Deserialize;
 use anyhow::Result;
+use crate::execution::bundle_sender::DEFAULT_BLOCK_ENGINE_URL;
+use crate::execution::costs::{CostModel, TipPolicy, MIN_JITO_TIP_LAMPORTS};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+
+/// Les tokens de base par défaut (SOL, USDC, USDT, mSOL) : le point de départ et d'arrivée de nos cycles.
+const DEFAULT_BASE_TOKENS: &[&str] = &[
+    "So11111111111111111111111111111111111111112",
+    "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+    "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB",
+    "mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So",
+];
 
 // Une struct qui contient toute la configuration nécessaire pour notre bot.
 // `#[derive(Deserialize)]` permet à la librairie `envy` de peupler
@@ -10,6 +22,19 @@ use anyhow::Result;
 pub struct Config {
     // L'URL du noeud RPC Solana que nous allons interroger.
     pub solana_rpc_url: String,
+    // Les mints des tokens de base, séparés par des virgules (variable `BASE_TOKENS`).
+    #[serde(default = "default_base_tokens")]
+    pub base_tokens: Vec<String>,
+    // Les stratégies à lancer, séparées par des virgules (variable `STRATEGIES`).
+    #[serde(default = "default_strategies")]
+    pub strategies: Vec<String>,
+    // Le prix d'une compute unit, en micro-lamports (variable `PRIORITY_FEE_MICRO_LAMPORTS`).
+    pub priority_fee_micro_lamports: Option<u64>,
+    // La part du profit brut reversée en pourboire Jito, en points de base (variable `JITO_TIP_BPS`).
+    pub jito_tip_bps: Option<u64>,
+    // L'URL du block engine Jito auquel soumettre les bundles (variable `JITO_BLOCK_ENGINE_URL`).
+    #[serde(default = "default_block_engine_url")]
+    pub jito_block_engine_url: String,
     // Plus tard, nous ajouterons ici la clé privée du trader, etc.
     // pub trader_private_key: String,
 }
@@ -27,5 +52,37 @@ impl Config {
 
         Ok(config)
     }
+
+    /// Le modèle de coûts d'exécution : les valeurs par défaut, surchargées par la configuration.
+    pub fn cost_model(&self) -> CostModel {
+        let mut costs = CostModel::default();
+        if let Some(price) = self.priority_fee_micro_lamports {
+            costs.compute_unit_price_micro_lamports = price;
+        }
+        if let Some(bps) = self.jito_tip_bps {
+            costs.tip = TipPolicy::ProfitShare { bps, min_lamports: MIN_JITO_TIP_LAMPORTS };
+        }
+        costs
+    }
+
+    /// Les tokens de base configurés, convertis en Pubkey.
+    pub fn base_token_mints(&self) -> Result<Vec<Pubkey>> {
+        self.base_tokens.iter().map(|mint| Ok(Pubkey::from_str(mint.trim())?)).collect()
+    }
 }
 
+/// La liste des tokens de base utilisée quand `BASE_TOKENS` n'est pas définie.
+pub fn default_base_tokens() -> Vec<String> {
+    DEFAULT_BASE_TOKENS.iter().map(|mint| mint.to_string()).collect()
+}
+
+
+/// Le block engine utilisé quand `JITO_BLOCK_ENGINE_URL` n'est pas définie.
+pub fn default_block_engine_url() -> String {
+    DEFAULT_BLOCK_ENGINE_URL.to_string()
+}
+
+/// Les stratégies lancées quand `STRATEGIES` n'est pas définie.
+pub fn default_strategies() -> Vec<String> {
+    crate::strategies::runner::DEFAULT_STRATEGIES.iter().map(|name| name.to_string()).collect()
+}
@@ -1,22 +1,95 @@
 // src/data_pipeline/data_scraper.rs
 
-use crate::decoders::RaydiumAmmPool;
-use anyhow::Result;
+use crate::decoders::{
+    orca_whirlpool, raydium_amm, raydium_clmm, OrcaWhirlpoolPool, Pool, RaydiumAmmPool, RaydiumClmmPool,
+};
+use anyhow::{anyhow, Result};
 use solana_client::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
 
+/// Nombre de TickArrays chargés de chaque côté du prix courant.
+/// Au-delà, la liquidité active est supposée constante : les cotations de très grosses
+/// tailles sur un pool à liquidité concentrée sont donc optimistes.
+const TICK_ARRAYS_PER_SIDE: i32 = 2;
+
+/// Hydrate n'importe quel pool avec les données qui ne sont pas dans son propre compte.
+pub fn hydrate_pool(pool: &mut Pool, rpc_client: &RpcClient) -> Result<()> {
+    match pool {
+        Pool::RaydiumAmm(pool) => hydrate_single_pool(pool, rpc_client),
+        Pool::RaydiumClmm(pool) => hydrate_clmm_pool(pool, rpc_client),
+        Pool::OrcaWhirlpool(pool) => hydrate_whirlpool(pool, rpc_client),
+    }
+}
+
+/// Hydrate un pool Raydium AMM v4 : réserves des vaults et, la première fois,
+/// les comptes du marché OpenBook (qui ne changent pas).
+/// Un marché illisible n'empêche pas de coter le pool : il est signalé, et sera relu au prochain passage.
 pub fn hydrate_single_pool(pool: &mut RaydiumAmmPool, rpc_client: &RpcClient) -> Result<()> {
-    let vaults_to_fetch = [pool.base_vault, pool.quote_vault];
-    let accounts = rpc_client.get_multiple_accounts(&vaults_to_fetch)?;
+    let mut to_fetch = vec![pool.base_vault, pool.quote_vault];
+    let fetch_market = pool.market.is_none() && pool.market_id != Pubkey::default();
+    if fetch_market {
+        to_fetch.push(pool.market_id);
+    }
+    let accounts = rpc_client.get_multiple_accounts(&to_fetch)?;
 
-    if let Some(Some(account)) = accounts.get(0) {
-        if account.owner == spl_token::id() && account.data.len() >= 165 {
-            pool.mint_a_reserve = u64::from_le_bytes(account.data[64..72].try_into()?);
-        }
+    if let Some(Some(account)) = accounts.first()
+        && account.owner == spl_token::id() && account.data.len() >= 165
+    {
+        pool.mint_a_reserve = u64::from_le_bytes(account.data[64..72].try_into()?);
+    }
+    if let Some(Some(account)) = accounts.get(1)
+        && account.owner == spl_token::id() && account.data.len() >= 165
+    {
+        pool.mint_b_reserve = u64::from_le_bytes(account.data[64..72].try_into()?);
     }
-    if let Some(Some(account)) = accounts.get(1) {
-        if account.owner == spl_token::id() && account.data.len() >= 165 {
-            pool.mint_b_reserve = u64::from_le_bytes(account.data[64..72].try_into()?);
+    if fetch_market && let Some(Some(account)) = accounts.get(2) {
+        match raydium_amm::decode_openbook_market(&pool.market_id, &pool.market_program, &account.data) {
+            Ok(market) => pool.market = Some(market),
+            Err(e) => tracing::warn!("Could not decode OpenBook market {} of pool {}: {}", pool.market_id, pool.id, e),
         }
     }
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Hydrate un pool Raydium CLMM : frais (compte AmmConfig) et ticks autour du prix courant.
+pub fn hydrate_clmm_pool(pool: &mut RaydiumClmmPool, rpc_client: &RpcClient) -> Result<()> {
+    let current_start = raydium_clmm::tick_array_start_index(pool.current_tick, pool.tick_spacing);
+    let step = raydium_clmm::TICK_ARRAY_SIZE * pool.tick_spacing as i32;
+    let tick_arrays: Vec<Pubkey> = (-TICK_ARRAYS_PER_SIDE..=TICK_ARRAYS_PER_SIDE)
+        .map(|i| raydium_clmm::tick_array_address(&pool.id, current_start + i * step))
+        .collect();
+
+    let mut to_fetch = vec![pool.amm_config];
+    to_fetch.extend(&tick_arrays);
+    let accounts = rpc_client.get_multiple_accounts(&to_fetch)?;
+
+    let config = accounts.first().cloned().flatten().ok_or_else(|| anyhow!("AmmConfig account not found"))?;
+    pool.fee_rate = raydium_clmm::decode_amm_config_fee_rate(&config.data)?;
+
+    // Un TickArray absent signifie simplement qu'aucun tick n'y est initialisé.
+    let mut ticks = Vec::new();
+    for account in accounts.iter().skip(1).flatten() {
+        ticks.extend(raydium_clmm::decode_tick_array(&account.data)?);
+    }
+    ticks.sort_by_key(|tick| tick.tick_index);
+    pool.ticks = ticks;
+    Ok(())
+}
+
+/// Hydrate un Whirlpool : les frais sont déjà dans le compte, il ne manque que les ticks.
+pub fn hydrate_whirlpool(pool: &mut OrcaWhirlpoolPool, rpc_client: &RpcClient) -> Result<()> {
+    let current_start = orca_whirlpool::tick_array_start_index(pool.tick_current_index, pool.tick_spacing);
+    let step = orca_whirlpool::TICK_ARRAY_SIZE * pool.tick_spacing as i32;
+    let tick_arrays: Vec<Pubkey> = (-TICK_ARRAYS_PER_SIDE..=TICK_ARRAYS_PER_SIDE)
+        .map(|i| orca_whirlpool::tick_array_address(&pool.id, current_start + i * step))
+        .collect();
+    let accounts = rpc_client.get_multiple_accounts(&tick_arrays)?;
+
+    let mut ticks = Vec::new();
+    for account in accounts.iter().flatten() {
+        ticks.extend(orca_whirlpool::decode_tick_array(&account.data, pool.tick_spacing)?);
+    }
+    ticks.sort_by_key(|tick| tick.tick_index);
+    pool.ticks = ticks;
+    Ok(())
+}
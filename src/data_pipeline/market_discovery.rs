@@ -3,6 +3,7 @@
 use super::discovery::{self, GenericPoolInfo};
 use anyhow::Result;
 
+#[allow(dead_code)] // Pas encore utilisé : Raydium fournit déjà le program_id via son API.
 const RAYDIUM_AMM_V4_PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzELxQfM9H24wFSut1Mp8";
 const ORCA_WHIRLPOOL_PROGRAM_ID: &str = "whirLbMiicVdio4iTSEveBxE8hSdrjvrnPAcKGAJqgM";
 
@@ -0,0 +1,219 @@
+// src/decoders/clmm_math.rs
+
+// Les mathématiques de liquidité concentrée, communes à Raydium CLMM et Orca Whirlpool.
+// Les deux programmes utilisent la même représentation : un prix stocké sous forme de
+// racine carrée en virgule fixe Q64.64, une liquidité active `L`, et des ticks
+// (prix = 1.0001^tick) où la liquidité active change de `liquidity_net`.
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use spl_math::uint::U256;
+
+/// Dénominateur des frais (les deux DEX expriment leurs frais en millionièmes).
+pub const FEE_RATE_DENOMINATOR: u64 = 1_000_000;
+
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+pub const MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+pub const MAX_SQRT_PRICE_X64: u128 = 79_226_673_521_066_979_257_578_248_091;
+
+const Q64: f64 = 18_446_744_073_709_551_616.0;
+
+/// Un tick initialisé : quand le prix le franchit, la liquidité active change de `liquidity_net`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TickLiquidity {
+    pub tick_index: i32,
+    pub liquidity_net: i128,
+}
+
+/// L'état d'un pool à liquidité concentrée nécessaire pour simuler un swap.
+#[derive(Debug, Clone, Copy)]
+pub struct ClmmState<'a> {
+    pub sqrt_price_x64: u128,
+    pub liquidity: u128,
+    pub tick_current: i32,
+    /// Les ticks initialisés connus, triés par `tick_index` croissant.
+    /// Si la liste est vide, la liquidité active est supposée constante sur toute la plage de prix.
+    pub ticks: &'a [TickLiquidity],
+    /// Frais en millionièmes (2500 = 0.25 %).
+    pub fee_rate: u32,
+}
+
+/// Le résultat d'un swap simulé, avec l'état du pool après le swap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapResult {
+    pub amount_out: u64,
+    pub sqrt_price_x64: u128,
+    pub liquidity: u128,
+    pub tick_current: i32,
+    /// Les montants d'entrée cumulés (frais inclus) auxquels un tick a été franchi.
+    /// La courbe de sortie est lisse entre deux franchissements, mais pas au franchissement.
+    pub crossings: Vec<u64>,
+}
+
+/// Simule un swap à montant d'entrée exact.
+/// `zero_for_one` : on vend le token 0 (A) contre le token 1 (B), le prix baisse.
+pub fn swap_exact_in(state: &ClmmState, amount_in: u64, zero_for_one: bool) -> Result<SwapResult> {
+    if state.fee_rate as u64 >= FEE_RATE_DENOMINATOR {
+        return Err(anyhow!("Invalid fee rate {}", state.fee_rate));
+    }
+
+    let mut sqrt_price = state.sqrt_price_x64;
+    let mut liquidity = state.liquidity;
+    let mut tick_current = state.tick_current;
+    let mut remaining = amount_in as u128;
+    let mut amount_out: u128 = 0;
+    let mut crossings = Vec::new();
+    let fee_rate = state.fee_rate as u128;
+    let fee_den = FEE_RATE_DENOMINATOR as u128;
+
+    while remaining > 0 {
+        let next_tick = next_initialized_tick(state.ticks, tick_current, zero_for_one);
+        // Le prix d'un tick est calculé en flottant : on le borne par le prix courant
+        // pour ne jamais viser une cible "derrière" nous à cause d'un arrondi.
+        let target = match (next_tick, zero_for_one) {
+            (Some(tick), true) => sqrt_price_from_tick(tick.tick_index).min(sqrt_price),
+            (Some(tick), false) => sqrt_price_from_tick(tick.tick_index).max(sqrt_price),
+            (None, true) => MIN_SQRT_PRICE_X64.min(sqrt_price),
+            (None, false) => MAX_SQRT_PRICE_X64.max(sqrt_price),
+        };
+
+        if liquidity > 0 {
+            let remaining_less_fee = remaining * (fee_den - fee_rate) / fee_den;
+            let max_in = amount_in_to_target(sqrt_price, target, liquidity, zero_for_one)?;
+
+            if remaining_less_fee < max_in {
+                // Le swap se termine avant le prochain tick.
+                let new_price = next_sqrt_price(sqrt_price, liquidity, remaining_less_fee, zero_for_one)?;
+                amount_out += amount_out_between(sqrt_price, new_price, liquidity, zero_for_one)?;
+                sqrt_price = new_price;
+                tick_current = tick_from_sqrt_price(sqrt_price);
+                break;
+            }
+
+            // On atteint le prochain tick : on consomme juste ce qu'il faut (frais inclus).
+            let fee = (max_in * fee_rate).div_ceil(fee_den - fee_rate);
+            amount_out += amount_out_between(sqrt_price, target, liquidity, zero_for_one)?;
+            remaining = remaining.saturating_sub(max_in + fee);
+        }
+
+        let Some(tick) = next_tick else {
+            return Err(anyhow!("Swap exceeds the pool's available liquidity"));
+        };
+        sqrt_price = target;
+        crossings.push((amount_in as u128 - remaining) as u64);
+        liquidity = cross_tick(liquidity, tick.liquidity_net, zero_for_one)?;
+        tick_current = if zero_for_one { tick.tick_index - 1 } else { tick.tick_index };
+    }
+
+    Ok(SwapResult {
+        amount_out: u64::try_from(amount_out).map_err(|_| anyhow!("Swap output overflows u64"))?,
+        sqrt_price_x64: sqrt_price,
+        liquidity,
+        tick_current,
+        crossings,
+    })
+}
+
+/// Le prochain tick initialisé dans le sens du swap.
+/// Vers le bas : le plus grand tick <= tick courant. Vers le haut : le plus petit tick > tick courant.
+fn next_initialized_tick(ticks: &[TickLiquidity], tick_current: i32, zero_for_one: bool) -> Option<TickLiquidity> {
+    if zero_for_one {
+        ticks.iter().rev().find(|t| t.tick_index <= tick_current).copied()
+    } else {
+        ticks.iter().find(|t| t.tick_index > tick_current).copied()
+    }
+}
+
+/// Met à jour la liquidité active au franchissement d'un tick.
+fn cross_tick(liquidity: u128, liquidity_net: i128, zero_for_one: bool) -> Result<u128> {
+    // En descendant, on sort de la position par le bas : la liquidité nette s'inverse.
+    let delta = if zero_for_one { -liquidity_net } else { liquidity_net };
+    liquidity
+        .checked_add_signed(delta)
+        .ok_or_else(|| anyhow!("Liquidity underflow while crossing a tick"))
+}
+
+/// Montant d'entrée (hors frais) nécessaire pour amener le prix jusqu'à `target`.
+fn amount_in_to_target(sqrt_price: u128, target: u128, liquidity: u128, zero_for_one: bool) -> Result<u128> {
+    if zero_for_one {
+        // Δx = L * (√P - √T) * 2^64 / (√P * √T), arrondi au supérieur.
+        let numerator = mul(U256::from(liquidity) << 64, U256::from(sqrt_price - target))?;
+        let denominator = mul(U256::from(sqrt_price), U256::from(target))?;
+        to_u128(ceil_div(numerator, denominator))
+    } else {
+        // Δy = L * (√T - √P) / 2^64, arrondi au supérieur.
+        let numerator = mul(U256::from(liquidity), U256::from(target - sqrt_price))?;
+        to_u128(ceil_div(numerator, U256::from(1u8) << 64))
+    }
+}
+
+/// Montant de sortie quand le prix passe de `from` à `to`, arrondi à l'inférieur.
+fn amount_out_between(from: u128, to: u128, liquidity: u128, zero_for_one: bool) -> Result<u128> {
+    if zero_for_one {
+        // On reçoit du token 1 : Δy = L * (√P - √P') / 2^64.
+        to_u128(mul(U256::from(liquidity), U256::from(from - to))? >> 64)
+    } else {
+        // On reçoit du token 0 : Δx = L * (√P' - √P) * 2^64 / (√P * √P').
+        let numerator = mul(U256::from(liquidity) << 64, U256::from(to - from))?;
+        to_u128(numerator / mul(U256::from(from), U256::from(to))?)
+    }
+}
+
+/// Le nouveau prix après avoir injecté `amount` (hors frais) sans franchir de tick.
+fn next_sqrt_price(sqrt_price: u128, liquidity: u128, amount: u128, zero_for_one: bool) -> Result<u128> {
+    if zero_for_one {
+        // √P' = L * 2^64 * √P / (L * 2^64 + Δx * √P), arrondi au supérieur.
+        let liquidity_shifted = U256::from(liquidity) << 64;
+        let numerator = mul(liquidity_shifted, U256::from(sqrt_price))?;
+        let denominator = liquidity_shifted + mul(U256::from(amount), U256::from(sqrt_price))?;
+        to_u128(ceil_div(numerator, denominator))
+    } else {
+        // √P' = √P + Δy * 2^64 / L, arrondi à l'inférieur.
+        let delta = (U256::from(amount) << 64) / U256::from(liquidity);
+        to_u128(U256::from(sqrt_price) + delta)
+    }
+}
+
+/// √(1.0001^tick) en Q64.64. Calcul flottant : suffisant pour coter, pas pour reproduire l'on-chain au bit près.
+pub fn sqrt_price_from_tick(tick: i32) -> u128 {
+    let tick = tick.clamp(MIN_TICK, MAX_TICK);
+    let sqrt_price = (1.0001f64.ln() * tick as f64 / 2.0).exp() * Q64;
+    (sqrt_price as u128).clamp(MIN_SQRT_PRICE_X64, MAX_SQRT_PRICE_X64)
+}
+
+/// Le tick qui contient un prix donné (inverse de `sqrt_price_from_tick`).
+pub fn tick_from_sqrt_price(sqrt_price_x64: u128) -> i32 {
+    let ratio = sqrt_price_x64 as f64 / Q64;
+    let tick = (2.0 * ratio.ln() / 1.0001f64.ln()).floor() as i32;
+    // Correction de l'arrondi flottant aux frontières de tick.
+    if sqrt_price_from_tick(tick + 1) <= sqrt_price_x64 {
+        tick + 1
+    } else if sqrt_price_from_tick(tick) > sqrt_price_x64 {
+        tick - 1
+    } else {
+        tick
+    }
+}
+
+/// Le prix spot (token 1 par token 0, en unités de base) correspondant à un √P Q64.64.
+pub fn price_from_sqrt_price(sqrt_price_x64: u128) -> f64 {
+    let ratio = sqrt_price_x64 as f64 / Q64;
+    ratio * ratio
+}
+
+fn mul(a: U256, b: U256) -> Result<U256> {
+    a.checked_mul(b).ok_or_else(|| anyhow!("Concentrated liquidity math overflow"))
+}
+
+fn ceil_div(numerator: U256, denominator: U256) -> U256 {
+    let (quotient, remainder) = numerator.div_mod(denominator);
+    if remainder.is_zero() { quotient } else { quotient + 1 }
+}
+
+fn to_u128(value: U256) -> Result<u128> {
+    if value > U256::from(u128::MAX) {
+        return Err(anyhow!("Concentrated liquidity math overflow"));
+    }
+    Ok(value.as_u128())
+}
@@ -1,35 +1,169 @@
 // src/decoders/mod.rs
 
 use solana_sdk::pubkey::Pubkey;
-use anyhow::Result;
+use solana_sdk::pubkey;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
 
 // 1. Déclarer les modules
+pub mod clmm_math;
+pub mod orca_whirlpool;
 pub mod raydium_amm;
 pub mod raydium_clmm;
+pub mod swap_instructions;
 
 // 2. Rendre publiques les structs définies dans les enfants
+pub use orca_whirlpool::OrcaWhirlpoolPool;
 pub use raydium_amm::RaydiumAmmPool;
 pub use raydium_clmm::RaydiumClmmPool;
 
+// Les programmes des DEX que nous savons décoder.
+pub const RAYDIUM_AMM_V4_PROGRAM_ID: Pubkey = pubkey!("675kPX9MHTjS2zt1qfr1NYHuzELxQfM9H24wFSut1Mp8");
+pub const RAYDIUM_CLMM_PROGRAM_ID: Pubkey = pubkey!("CAMMCzo5YL8w4VFF8KVHrK22GGUsp5VTaW7grrKgrWqK");
+pub const RAYDIUM_CPMM_PROGRAM_ID: Pubkey = pubkey!("CPMMoo8L3F4NbTegBCKVNunggL7H1ZpdTHKxQB5qKP1C");
+pub const ORCA_WHIRLPOOL_PROGRAM_ID: Pubkey = pubkey!("whirLbMiicVdio4iTSEveBxE8hSdrjvrnPAcKGAJqgM");
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = pubkey!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+
+/// Le compte de tokens associé (ATA) d'un propriétaire pour un mint SPL.
+pub fn associated_token_address(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[owner.as_ref(), spl_token::id().as_ref(), mint.as_ref()], &ASSOCIATED_TOKEN_PROGRAM_ID).0
+}
+
 // 3. Définir l'enum qui n'utilise QUE les structs que nous avons.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Pool {
     RaydiumAmm(RaydiumAmmPool),
     RaydiumClmm(RaydiumClmmPool),
+    OrcaWhirlpool(OrcaWhirlpoolPool),
+}
+
+impl Pool {
+    /// Le DEX (et la version de programme) auquel appartient ce pool.
+    pub fn dex(&self) -> Dex {
+        match self {
+            Pool::RaydiumAmm(_) => Dex::RaydiumAmmV4,
+            Pool::RaydiumClmm(_) => Dex::RaydiumClmm,
+            Pool::OrcaWhirlpool(_) => Dex::OrcaWhirlpool,
+        }
+    }
+
+    /// Indique si le pool a été hydraté et contient de la liquidité.
+    pub fn has_liquidity(&self) -> bool {
+        match self {
+            Pool::RaydiumAmm(pool) => pool.mint_a_reserve > 0 && pool.mint_b_reserve > 0,
+            Pool::RaydiumClmm(pool) => pool.liquidity > 0 && pool.fee_rate > 0,
+            Pool::OrcaWhirlpool(pool) => pool.liquidity > 0,
+        }
+    }
+
+    /// Les paramètres x·y=k (réserve d'entrée, réserve de sortie, multiplicateur de frais)
+    /// si le pool est à produit constant ; `None` pour les pools à liquidité concentrée.
+    pub fn constant_product_params(&self, token_in_mint: &Pubkey) -> Option<(f64, f64, f64)> {
+        match self {
+            Pool::RaydiumAmm(pool) => pool.constant_product_params(token_in_mint),
+            Pool::RaydiumClmm(_) | Pool::OrcaWhirlpool(_) => None,
+        }
+    }
+
+    /// Les montants d'entrée (cumulés) auxquels un swap de `amount_in` franchit un tick.
+    /// La courbe de sortie d'un pool à produit constant est lisse : la liste est alors vide.
+    pub fn tick_crossings(&self, token_in_mint: &Pubkey, amount_in: u64) -> Result<Vec<u64>> {
+        match self {
+            Pool::RaydiumAmm(_) => Ok(Vec::new()),
+            Pool::RaydiumClmm(pool) => Ok(pool.simulate_swap(token_in_mint, amount_in)?.crossings),
+            Pool::OrcaWhirlpool(pool) => Ok(pool.simulate_swap(token_in_mint, amount_in)?.crossings),
+        }
+    }
+
+    /// Les vaults (token A, token B) du pool.
+    pub fn vaults(&self) -> (Pubkey, Pubkey) {
+        match self {
+            Pool::RaydiumAmm(pool) => (pool.base_vault, pool.quote_vault),
+            Pool::RaydiumClmm(pool) => (pool.vault_a, pool.vault_b),
+            Pool::OrcaWhirlpool(pool) => (pool.vault_a, pool.vault_b),
+        }
+    }
+
+    /// Applique un swap à l'état du pool (comme s'il avait été exécuté on-chain)
+    /// et retourne le montant de sortie.
+    pub fn apply_swap(&mut self, token_in_mint: &Pubkey, amount_in: u64) -> Result<u64> {
+        match self {
+            Pool::RaydiumAmm(pool) => pool.apply_swap(token_in_mint, amount_in),
+            Pool::RaydiumClmm(pool) => pool.apply_swap(token_in_mint, amount_in),
+            Pool::OrcaWhirlpool(pool) => pool.apply_swap(token_in_mint, amount_in),
+        }
+    }
+}
+
+/// Identifie le DEX d'un pool, indépendamment de son état.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Dex {
+    RaydiumAmmV4,
+    RaydiumClmm,
+    RaydiumCpmm,
+    OrcaWhirlpool,
+}
+
+impl std::fmt::Display for Dex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Dex::RaydiumAmmV4 => "Raydium AMM v4",
+            Dex::RaydiumClmm => "Raydium CLMM",
+            Dex::RaydiumCpmm => "Raydium CPMM",
+            Dex::OrcaWhirlpool => "Orca Whirlpool",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Décode un compte de pool en se basant sur le programme qui le possède.
+/// Les pools retournés ne sont pas encore hydratés (réserves, frais, ticks).
+pub fn decode_pool(owner: &Pubkey, id: &Pubkey, data: &[u8]) -> Result<Pool> {
+    if *owner == RAYDIUM_AMM_V4_PROGRAM_ID {
+        Ok(Pool::RaydiumAmm(raydium_amm::decode_raydium_amm(id, data)?))
+    } else if *owner == RAYDIUM_CLMM_PROGRAM_ID {
+        Ok(Pool::RaydiumClmm(raydium_clmm::decode_raydium_clmm(id, data)?))
+    } else if *owner == ORCA_WHIRLPOOL_PROGRAM_ID {
+        Ok(Pool::OrcaWhirlpool(orca_whirlpool::decode_orca_whirlpool(id, data)?))
+    } else {
+        Err(anyhow!("Unsupported pool program {}", owner))
+    }
+}
+
+/// Le discriminateur Anchor d'un type de compte : les 8 premiers octets de sha256("account:<Nom>").
+pub fn account_discriminator(account_name: &str) -> [u8; 8] {
+    let hash = solana_sdk::hash::hash(format!("account:{}", account_name).as_bytes());
+    hash.to_bytes()[..8].try_into().unwrap()
+}
+
+/// Le discriminateur Anchor d'une instruction : les 8 premiers octets de sha256("global:<nom>").
+pub fn instruction_discriminator(instruction_name: &str) -> [u8; 8] {
+    let hash = solana_sdk::hash::hash(format!("global:{}", instruction_name).as_bytes());
+    hash.to_bytes()[..8].try_into().unwrap()
 }
 
 // 4. Définir le Trait
 pub trait PoolOperations {
+    fn get_id(&self) -> Pubkey;
     fn get_mints(&self) -> (Pubkey, Pubkey);
     fn get_quote(&self, token_in_mint: &Pubkey, amount_in: u64) -> Result<u64>;
 }
 
 // 5. Implémenter le Trait pour l'Enum
 impl PoolOperations for Pool {
+    fn get_id(&self) -> Pubkey {
+        match self {
+            Pool::RaydiumAmm(pool) => pool.get_id(),
+            Pool::RaydiumClmm(pool) => pool.get_id(),
+            Pool::OrcaWhirlpool(pool) => pool.get_id(),
+        }
+    }
+
     fn get_mints(&self) -> (Pubkey, Pubkey) {
         match self {
             Pool::RaydiumAmm(pool) => pool.get_mints(),
             Pool::RaydiumClmm(pool) => pool.get_mints(),
+            Pool::OrcaWhirlpool(pool) => pool.get_mints(),
         }
     }
 
@@ -37,6 +171,7 @@ impl PoolOperations for Pool {
         match self {
             Pool::RaydiumAmm(pool) => pool.get_quote(token_in_mint, amount_in),
             Pool::RaydiumClmm(pool) => pool.get_quote(token_in_mint, amount_in),
+            Pool::OrcaWhirlpool(pool) => pool.get_quote(token_in_mint, amount_in),
         }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,162 @@
+// src/decoders/orca_whirlpool.rs
+
+use super::clmm_math::{self, ClmmState, SwapResult, TickLiquidity};
+use super::PoolOperations;
+use anyhow::{anyhow, Result};
+use bytemuck::{from_bytes, Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+
+/// Nombre de ticks par compte TickArray chez Orca.
+pub const TICK_ARRAY_SIZE: i32 = 88;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrcaWhirlpoolPool {
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub id: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub mint_a: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub mint_b: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub vault_a: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub vault_b: Pubkey,
+    pub tick_spacing: u16,
+    /// Frais en millionièmes (stockés directement dans le compte du pool).
+    pub fee_rate: u32,
+    pub liquidity: u128,
+    pub sqrt_price: u128,
+    pub tick_current_index: i32,
+    /// Les ticks initialisés autour du prix courant (hydratés depuis les TickArrays).
+    pub ticks: Vec<TickLiquidity>,
+}
+
+// --- STRUCTURE DE DÉCODAGE PRIVÉE ---
+// Le début du compte Whirlpool (après le discriminateur Anchor), jusqu'au token B.
+#[repr(C, packed)]
+#[derive(Clone, Copy, Zeroable, Pod, Debug)]
+struct WhirlpoolHeader {
+    discriminator: [u8; 8],
+    whirlpools_config: Pubkey,
+    whirlpool_bump: [u8; 1],
+    tick_spacing: u16,
+    fee_tier_index_seed: [u8; 2],
+    fee_rate: u16,
+    protocol_fee_rate: u16,
+    liquidity: u128,
+    sqrt_price: u128,
+    tick_current_index: i32,
+    protocol_fee_owed_a: u64,
+    protocol_fee_owed_b: u64,
+    token_mint_a: Pubkey,
+    token_vault_a: Pubkey,
+    fee_growth_global_a: u128,
+    token_mint_b: Pubkey,
+    token_vault_b: Pubkey,
+}
+
+/// Décode un compte Whirlpool.
+pub fn decode_orca_whirlpool(id: &Pubkey, data: &[u8]) -> Result<OrcaWhirlpoolPool> {
+    let header_size = std::mem::size_of::<WhirlpoolHeader>();
+    if data.len() < header_size {
+        return Err(anyhow!("Data too short for Whirlpool"));
+    }
+    let header: &WhirlpoolHeader = from_bytes(&data[..header_size]);
+    if header.discriminator != super::account_discriminator("Whirlpool") {
+        return Err(anyhow!("Account is not an Orca Whirlpool"));
+    }
+
+    Ok(OrcaWhirlpoolPool {
+        id: *id,
+        mint_a: header.token_mint_a,
+        mint_b: header.token_mint_b,
+        vault_a: header.token_vault_a,
+        vault_b: header.token_vault_b,
+        tick_spacing: header.tick_spacing,
+        fee_rate: header.fee_rate as u32,
+        liquidity: header.liquidity,
+        sqrt_price: header.sqrt_price,
+        tick_current_index: header.tick_current_index,
+        ticks: Vec::new(),
+    })
+}
+
+/// Décode les ticks initialisés d'un compte TickArray.
+/// L'index d'un tick n'est pas stocké : c'est `start_tick_index + i * tick_spacing`.
+pub fn decode_tick_array(data: &[u8], tick_spacing: u16) -> Result<Vec<TickLiquidity>> {
+    // discriminateur (8) + start_tick_index (4), puis 88 Tick de 113 octets.
+    const START_INDEX_OFFSET: usize = 8;
+    const TICKS_OFFSET: usize = 8 + 4;
+    const TICK_SIZE: usize = 113;
+    if data.len() < TICKS_OFFSET + TICK_ARRAY_SIZE as usize * TICK_SIZE {
+        return Err(anyhow!("Data too short for Whirlpool TickArray"));
+    }
+    let start_index = i32::from_le_bytes(data[START_INDEX_OFFSET..TICKS_OFFSET].try_into()?);
+    let mut ticks = Vec::new();
+    for i in 0..TICK_ARRAY_SIZE as usize {
+        let tick_data = &data[TICKS_OFFSET + i * TICK_SIZE..];
+        let initialized = tick_data[0] != 0;
+        if initialized {
+            let liquidity_net = i128::from_le_bytes(tick_data[1..17].try_into()?);
+            ticks.push(TickLiquidity { tick_index: start_index + i as i32 * tick_spacing as i32, liquidity_net });
+        }
+    }
+    Ok(ticks)
+}
+
+/// L'index du premier tick du TickArray qui contient `tick`.
+pub fn tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+    tick.div_euclid(ticks_in_array) * ticks_in_array
+}
+
+/// L'adresse (PDA) du TickArray qui commence à `start_index`.
+pub fn tick_array_address(whirlpool: &Pubkey, start_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"tick_array", whirlpool.as_ref(), start_index.to_string().as_bytes()],
+        &super::ORCA_WHIRLPOOL_PROGRAM_ID,
+    )
+    .0
+}
+
+impl OrcaWhirlpoolPool {
+    /// Simule un swap et retourne l'état du pool après celui-ci.
+    pub fn simulate_swap(&self, token_in_mint: &Pubkey, amount_in: u64) -> Result<SwapResult> {
+        if self.liquidity == 0 {
+            return Err(anyhow!("Pool has no liquidity data yet."));
+        }
+        let a_to_b = if *token_in_mint == self.mint_a {
+            true
+        } else if *token_in_mint == self.mint_b {
+            false
+        } else {
+            return Err(anyhow!("Input token does not belong to this pool."));
+        };
+        let state = ClmmState {
+            sqrt_price_x64: self.sqrt_price,
+            liquidity: self.liquidity,
+            tick_current: self.tick_current_index,
+            ticks: &self.ticks,
+            fee_rate: self.fee_rate,
+        };
+        clmm_math::swap_exact_in(&state, amount_in, a_to_b)
+    }
+
+    /// Applique un swap à l'état du pool (prix, liquidité active, tick) et retourne le montant de sortie.
+    pub fn apply_swap(&mut self, token_in_mint: &Pubkey, amount_in: u64) -> Result<u64> {
+        let result = self.simulate_swap(token_in_mint, amount_in)?;
+        self.sqrt_price = result.sqrt_price_x64;
+        self.liquidity = result.liquidity;
+        self.tick_current_index = result.tick_current;
+        Ok(result.amount_out)
+    }
+}
+
+impl PoolOperations for OrcaWhirlpoolPool {
+    fn get_id(&self) -> Pubkey { self.id }
+    fn get_mints(&self) -> (Pubkey, Pubkey) { (self.mint_a, self.mint_b) }
+    fn get_quote(&self, token_in_mint: &Pubkey, amount_in: u64) -> Result<u64> {
+        Ok(self.simulate_swap(token_in_mint, amount_in)?.amount_out)
+    }
+}
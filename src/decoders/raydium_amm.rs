@@ -4,20 +4,64 @@
 use super::PoolOperations;
 use anyhow::{anyhow, Result};
 use bytemuck::{from_bytes, Pod, Zeroable};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey;
 use solana_sdk::pubkey::Pubkey;
 
+/// L'autorité (PDA) qui signe pour tous les pools Raydium AMM v4.
+pub const AMM_AUTHORITY: Pubkey = pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1");
+
+/// Les frais de swap Raydium AMM v4 : 25 / 10 000 (0.25 %).
+pub const FEE_NUMERATOR: u64 = 25;
+pub const FEE_DENOMINATOR: u64 = 10_000;
+
 // --- DÉFINITION DE LA STRUCT PUBLIQUE ---
 // C'est la struct que le reste de notre application utilisera.
 // Elle est maintenant définie ici, dans son propre module.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct RaydiumAmmPool {
+    #[serde(with = "crate::snapshot::pubkey")]
     pub id: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
     pub mint_a: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
     pub mint_b: Pubkey,
     pub mint_a_reserve: u64,
     pub mint_b_reserve: u64,
+    #[serde(with = "crate::snapshot::pubkey")]
     pub base_vault: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
     pub quote_vault: Pubkey,
+    // Les comptes du carnet d'ordres OpenBook associé, exigés par l'instruction de swap.
+    #[serde(default, with = "crate::snapshot::pubkey")]
+    pub open_orders: Pubkey,
+    #[serde(default, with = "crate::snapshot::pubkey")]
+    pub target_orders: Pubkey,
+    #[serde(default, with = "crate::snapshot::pubkey")]
+    pub market_id: Pubkey,
+    #[serde(default, with = "crate::snapshot::pubkey")]
+    pub market_program: Pubkey,
+    /// Les comptes du marché, lus dans le compte du marché à l'hydratation.
+    #[serde(default)]
+    pub market: Option<OpenBookMarketKeys>,
+}
+
+/// Les comptes d'un marché OpenBook (Serum v3) utilisés par un swap Raydium AMM v4.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpenBookMarketKeys {
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub bids: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub asks: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub event_queue: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub base_vault: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub quote_vault: Pubkey,
+    /// Le PDA qui signe pour les vaults du marché : `[market, nonce]` sous le programme du marché.
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub vault_signer: Pubkey,
 }
 
 
@@ -75,11 +119,79 @@ pub fn decode_raydium_amm(id: &Pubkey, data: &[u8]) -> Result<RaydiumAmmPool> {
         mint_b_reserve: 0,
         base_vault: amm_info.coin_vault,
         quote_vault: amm_info.pc_vault,
+        open_orders: amm_info.open_orders,
+        target_orders: amm_info.target_orders,
+        market_id: amm_info.market,
+        market_program: amm_info.market_program,
+        market: None,
+    })
+}
+
+// Le compte d'un marché OpenBook : 5 octets "serum", la struct, puis 7 octets "padding".
+const MARKET_ACCOUNT_SIZE: usize = 388;
+const MARKET_VAULT_SIGNER_NONCE: usize = 45;
+const MARKET_BASE_VAULT: usize = 117;
+const MARKET_QUOTE_VAULT: usize = 165;
+const MARKET_EVENT_QUEUE: usize = 253;
+const MARKET_BIDS: usize = 285;
+const MARKET_ASKS: usize = 317;
+
+/// Décode les comptes d'un marché OpenBook et dérive son vault signer.
+pub fn decode_openbook_market(market_id: &Pubkey, market_program: &Pubkey, data: &[u8]) -> Result<OpenBookMarketKeys> {
+    if data.len() < MARKET_ACCOUNT_SIZE || &data[..5] != b"serum" {
+        return Err(anyhow!("Not an OpenBook market account"));
+    }
+    let key = |offset: usize| Pubkey::try_from(&data[offset..offset + 32]).unwrap();
+    let nonce = u64::from_le_bytes(data[MARKET_VAULT_SIGNER_NONCE..MARKET_VAULT_SIGNER_NONCE + 8].try_into()?);
+    let vault_signer = Pubkey::create_program_address(&[market_id.as_ref(), &nonce.to_le_bytes()], market_program)
+        .map_err(|e| anyhow!("Invalid vault signer nonce for market {}: {}", market_id, e))?;
+
+    Ok(OpenBookMarketKeys {
+        bids: key(MARKET_BIDS),
+        asks: key(MARKET_ASKS),
+        event_queue: key(MARKET_EVENT_QUEUE),
+        base_vault: key(MARKET_BASE_VAULT),
+        quote_vault: key(MARKET_QUOTE_VAULT),
+        vault_signer,
     })
 }
 
+impl RaydiumAmmPool {
+    /// Les paramètres de la courbe x·y=k pour un token d'entrée :
+    /// (réserve d'entrée, réserve de sortie, part de l'entrée qui reste après les frais).
+    pub fn constant_product_params(&self, token_in_mint: &Pubkey) -> Option<(f64, f64, f64)> {
+        let (in_reserve, out_reserve) = if *token_in_mint == self.mint_a {
+            (self.mint_a_reserve, self.mint_b_reserve)
+        } else if *token_in_mint == self.mint_b {
+            (self.mint_b_reserve, self.mint_a_reserve)
+        } else {
+            return None;
+        };
+        let fee_multiplier = (FEE_DENOMINATOR - FEE_NUMERATOR) as f64 / FEE_DENOMINATOR as f64;
+        Some((in_reserve as f64, out_reserve as f64, fee_multiplier))
+    }
+
+    /// Applique un swap aux réserves du pool et retourne le montant de sortie.
+    /// Les frais restent dans le pool : la réserve d'entrée augmente du montant complet.
+    pub fn apply_swap(&mut self, token_in_mint: &Pubkey, amount_in: u64) -> Result<u64> {
+        let amount_out = self.get_quote(token_in_mint, amount_in)?;
+        if *token_in_mint == self.mint_a {
+            self.mint_a_reserve = self.mint_a_reserve.saturating_add(amount_in);
+            self.mint_b_reserve -= amount_out;
+        } else {
+            self.mint_b_reserve = self.mint_b_reserve.saturating_add(amount_in);
+            self.mint_a_reserve -= amount_out;
+        }
+        Ok(amount_out)
+    }
+}
+
 // L'implémentation du trait pour notre struct publique.
 impl PoolOperations for RaydiumAmmPool {
+    fn get_id(&self) -> Pubkey {
+        self.id
+    }
+
     fn get_mints(&self) -> (Pubkey, Pubkey) {
         (self.mint_a, self.mint_b)
     }
@@ -95,11 +207,9 @@ impl PoolOperations for RaydiumAmmPool {
         } else {
             return Err(anyhow!("Input token does not belong to this pool."));
         };
-        let fee_numerator = 25;
-        let fee_denominator = 10000;
-        let amount_in_with_fee = amount_in * (fee_denominator - fee_numerator);
-        let numerator = (amount_in_with_fee as u128) * (out_reserve as u128);
-        let denominator = ((in_reserve as u128) * (fee_denominator as u128)) + (amount_in_with_fee as u128);
+        let amount_in_with_fee = (amount_in as u128) * ((FEE_DENOMINATOR - FEE_NUMERATOR) as u128);
+        let numerator = amount_in_with_fee * (out_reserve as u128);
+        let denominator = ((in_reserve as u128) * (FEE_DENOMINATOR as u128)) + amount_in_with_fee;
         let amount_out = (numerator / denominator) as u64;
         Ok(amount_out)
     }
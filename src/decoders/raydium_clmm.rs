@@ -1,22 +1,179 @@
 // src/decoders/raydium_clmm.rs
 
 // On importe SEULEMENT ce dont on a besoin du module parent.
+use super::clmm_math::{self, ClmmState, SwapResult, TickLiquidity};
 use super::PoolOperations;
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use bytemuck::{from_bytes, Pod, Zeroable};
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 
+/// Nombre de ticks par compte TickArray chez Raydium.
+pub const TICK_ARRAY_SIZE: i32 = 60;
+
 // La VRAIE définition de la struct, rendue publique.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RaydiumClmmPool {
+    #[serde(with = "crate::snapshot::pubkey")]
     pub id: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
     pub mint_a: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
     pub mint_b: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub vault_a: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub vault_b: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub amm_config: Pubkey,
+    #[serde(with = "crate::snapshot::pubkey")]
+    pub observation_key: Pubkey,
+    pub tick_spacing: u16,
+    pub liquidity: u128,
     pub current_sqrt_price: u128,
     pub current_tick: i32,
+    /// Frais en millionièmes. Ils sont stockés dans le compte AmmConfig, pas dans le pool :
+    /// 0 tant que le pool n'a pas été hydraté.
+    pub fee_rate: u32,
+    /// Les ticks initialisés autour du prix courant (hydratés depuis les TickArrays).
+    pub ticks: Vec<TickLiquidity>,
+}
+
+// --- STRUCTURE DE DÉCODAGE PRIVÉE ---
+// Le début du compte PoolState (après le discriminateur Anchor), jusqu'au tick courant.
+#[repr(C, packed)]
+#[derive(Clone, Copy, Zeroable, Pod, Debug)]
+struct PoolStateHeader {
+    discriminator: [u8; 8],
+    bump: [u8; 1],
+    amm_config: Pubkey,
+    owner: Pubkey,
+    token_mint_0: Pubkey,
+    token_mint_1: Pubkey,
+    token_vault_0: Pubkey,
+    token_vault_1: Pubkey,
+    observation_key: Pubkey,
+    mint_decimals_0: u8,
+    mint_decimals_1: u8,
+    tick_spacing: u16,
+    liquidity: u128,
+    sqrt_price_x64: u128,
+    tick_current: i32,
+}
+
+/// Décode un compte PoolState Raydium CLMM.
+pub fn decode_raydium_clmm(id: &Pubkey, data: &[u8]) -> Result<RaydiumClmmPool> {
+    let header_size = std::mem::size_of::<PoolStateHeader>();
+    if data.len() < header_size {
+        return Err(anyhow!("Data too short for CLMM PoolState"));
+    }
+    let header: &PoolStateHeader = from_bytes(&data[..header_size]);
+    if header.discriminator != super::account_discriminator("PoolState") {
+        return Err(anyhow!("Account is not a Raydium CLMM PoolState"));
+    }
+
+    Ok(RaydiumClmmPool {
+        id: *id,
+        mint_a: header.token_mint_0,
+        mint_b: header.token_mint_1,
+        vault_a: header.token_vault_0,
+        vault_b: header.token_vault_1,
+        amm_config: header.amm_config,
+        observation_key: header.observation_key,
+        tick_spacing: header.tick_spacing,
+        liquidity: header.liquidity,
+        current_sqrt_price: header.sqrt_price_x64,
+        current_tick: header.tick_current,
+        fee_rate: 0,
+        ticks: Vec::new(),
+    })
+}
+
+/// Lit le `trade_fee_rate` d'un compte AmmConfig.
+pub fn decode_amm_config_fee_rate(data: &[u8]) -> Result<u32> {
+    // discriminateur (8) + bump (1) + index (2) + owner (32) + protocol_fee_rate (4)
+    const TRADE_FEE_RATE_OFFSET: usize = 8 + 1 + 2 + 32 + 4;
+    let bytes = data
+        .get(TRADE_FEE_RATE_OFFSET..TRADE_FEE_RATE_OFFSET + 4)
+        .ok_or_else(|| anyhow!("Data too short for AmmConfig"))?;
+    Ok(u32::from_le_bytes(bytes.try_into()?))
 }
 
-// L'implémentation du trait (avec des placeholders pour l'instant)
+/// Décode les ticks initialisés d'un compte TickArrayState.
+pub fn decode_tick_array(data: &[u8]) -> Result<Vec<TickLiquidity>> {
+    // discriminateur (8) + pool_id (32) + start_tick_index (4), puis 60 TickState de 168 octets.
+    const TICKS_OFFSET: usize = 8 + 32 + 4;
+    const TICK_STATE_SIZE: usize = 168;
+    if data.len() < TICKS_OFFSET + TICK_ARRAY_SIZE as usize * TICK_STATE_SIZE {
+        return Err(anyhow!("Data too short for CLMM TickArrayState"));
+    }
+
+    let mut ticks = Vec::new();
+    for i in 0..TICK_ARRAY_SIZE as usize {
+        let tick_data = &data[TICKS_OFFSET + i * TICK_STATE_SIZE..];
+        let tick_index = i32::from_le_bytes(tick_data[0..4].try_into()?);
+        let liquidity_net = i128::from_le_bytes(tick_data[4..20].try_into()?);
+        let liquidity_gross = u128::from_le_bytes(tick_data[20..36].try_into()?);
+        if liquidity_gross > 0 {
+            ticks.push(TickLiquidity { tick_index, liquidity_net });
+        }
+    }
+    Ok(ticks)
+}
+
+/// L'index du premier tick du TickArray qui contient `tick`.
+pub fn tick_array_start_index(tick: i32, tick_spacing: u16) -> i32 {
+    let ticks_in_array = TICK_ARRAY_SIZE * tick_spacing as i32;
+    tick.div_euclid(ticks_in_array) * ticks_in_array
+}
+
+/// L'adresse (PDA) du TickArray qui commence à `start_index`.
+pub fn tick_array_address(pool_id: &Pubkey, start_index: i32) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"tick_array", pool_id.as_ref(), &start_index.to_be_bytes()],
+        &super::RAYDIUM_CLMM_PROGRAM_ID,
+    )
+    .0
+}
+
+impl RaydiumClmmPool {
+    /// Simule un swap et retourne l'état du pool après celui-ci.
+    pub fn simulate_swap(&self, token_in_mint: &Pubkey, amount_in: u64) -> Result<SwapResult> {
+        if self.liquidity == 0 || self.fee_rate == 0 {
+            return Err(anyhow!("Pool has no liquidity data yet."));
+        }
+        let zero_for_one = if *token_in_mint == self.mint_a {
+            true
+        } else if *token_in_mint == self.mint_b {
+            false
+        } else {
+            return Err(anyhow!("Input token does not belong to this pool."));
+        };
+        let state = ClmmState {
+            sqrt_price_x64: self.current_sqrt_price,
+            liquidity: self.liquidity,
+            tick_current: self.current_tick,
+            ticks: &self.ticks,
+            fee_rate: self.fee_rate,
+        };
+        clmm_math::swap_exact_in(&state, amount_in, zero_for_one)
+    }
+
+    /// Applique un swap à l'état du pool (prix, liquidité active, tick) et retourne le montant de sortie.
+    pub fn apply_swap(&mut self, token_in_mint: &Pubkey, amount_in: u64) -> Result<u64> {
+        let result = self.simulate_swap(token_in_mint, amount_in)?;
+        self.current_sqrt_price = result.sqrt_price_x64;
+        self.liquidity = result.liquidity;
+        self.current_tick = result.tick_current;
+        Ok(result.amount_out)
+    }
+}
+
+// L'implémentation du trait pour notre struct publique.
 impl PoolOperations for RaydiumClmmPool {
+    fn get_id(&self) -> Pubkey { self.id }
     fn get_mints(&self) -> (Pubkey, Pubkey) { (self.mint_a, self.mint_b) }
-    fn get_quote(&self, _token_in_mint: &Pubkey, _amount_in: u64) -> Result<u64> { Ok(0) } // Placeholder
-}
\ No newline at end of file
+    fn get_quote(&self, token_in_mint: &Pubkey, amount_in: u64) -> Result<u64> {
+        Ok(self.simulate_swap(token_in_mint, amount_in)?.amount_out)
+    }
+}
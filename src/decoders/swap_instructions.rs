@@ -0,0 +1,358 @@
+// src/decoders/swap_instructions.rs
+
+// Décodage des instructions de swap (Raydium AMM v4, CLMM et Orca Whirlpool)
+// contenues dans une transaction, y compris les instructions internes émises par
+// les agrégateurs. On s'en sert pour réagir aux swaps des autres (backrun) et pour
+// réconcilier nos propres transactions.
+// Les swaps Raydium CPMM ne sont pas décodés : le graphe ne contient aucun pool CPMM
+// auquel les rattacher.
+
+use super::{
+    associated_token_address, instruction_discriminator, Dex, PoolOperations, ORCA_WHIRLPOOL_PROGRAM_ID,
+    RAYDIUM_AMM_V4_PROGRAM_ID, RAYDIUM_CLMM_PROGRAM_ID,
+};
+use crate::state::MarketGraph;
+use anyhow::{anyhow, Result};
+use solana_sdk::instruction::CompiledInstruction;
+use solana_sdk::message::v0::LoadedAddresses;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, UiInnerInstructions, UiInstruction, UiLoadedAddresses,
+    UiTransactionTokenBalance,
+};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Les soldes d'un compte de tokens avant et après la transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenBalanceChange {
+    pub mint: Pubkey,
+    pub pre: u64,
+    pub post: u64,
+}
+
+/// Une transaction mise à plat : les comptes résolus et les instructions à décoder.
+#[derive(Debug, Clone, Default)]
+pub struct TransactionView {
+    pub signature: Option<Signature>,
+    /// Les clés statiques du message, puis les adresses chargées via les ALT (écriture, puis lecture seule).
+    pub account_keys: Vec<Pubkey>,
+    pub instructions: Vec<CompiledInstruction>,
+    /// Les instructions internes, par index de l'instruction externe qui les a émises.
+    pub inner_instructions: HashMap<usize, Vec<CompiledInstruction>>,
+    /// Les soldes des comptes de tokens (vide pour une transaction en attente).
+    pub token_balances: HashMap<Pubkey, TokenBalanceChange>,
+}
+
+impl TransactionView {
+    /// Une transaction sans métadonnées (ex : en attente). Les comptes chargés via
+    /// des ALT doivent être fournis, sinon les instructions qui les utilisent sont ignorées.
+    pub fn from_transaction(transaction: &VersionedTransaction, loaded_addresses: Option<&LoadedAddresses>) -> Self {
+        let mut account_keys = transaction.message.static_account_keys().to_vec();
+        if let Some(loaded) = loaded_addresses {
+            account_keys.extend(&loaded.writable);
+            account_keys.extend(&loaded.readonly);
+        }
+        Self {
+            signature: transaction.signatures.first().copied(),
+            account_keys,
+            instructions: transaction.message.instructions().to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// Une transaction exécutée, telle que retournée par `getTransaction` en encodage base64.
+    pub fn from_encoded(encoded: &EncodedConfirmedTransactionWithStatusMeta) -> Result<Self> {
+        let transaction = encoded
+            .transaction
+            .transaction
+            .decode()
+            .ok_or_else(|| anyhow!("Transaction must be fetched with a binary encoding"))?;
+        let Some(meta) = &encoded.transaction.meta else {
+            return Ok(Self::from_transaction(&transaction, None));
+        };
+
+        let loaded: Option<&UiLoadedAddresses> = Option::from(meta.loaded_addresses.as_ref());
+        let loaded_addresses = match loaded {
+            Some(loaded) => Some(LoadedAddresses {
+                writable: parse_keys(&loaded.writable)?,
+                readonly: parse_keys(&loaded.readonly)?,
+            }),
+            None => None,
+        };
+        let mut view = Self::from_transaction(&transaction, loaded_addresses.as_ref());
+
+        let inner_sets: Option<&Vec<UiInnerInstructions>> = Option::from(meta.inner_instructions.as_ref());
+        for inner in inner_sets.into_iter().flatten() {
+            let mut instructions = Vec::with_capacity(inner.instructions.len());
+            for instruction in &inner.instructions {
+                // En encodage binaire, les instructions internes ne sont jamais "parsed".
+                if let UiInstruction::Compiled(compiled) = instruction {
+                    instructions.push(CompiledInstruction {
+                        program_id_index: compiled.program_id_index,
+                        accounts: compiled.accounts.clone(),
+                        data: bs58::decode(&compiled.data).into_vec()?,
+                    });
+                }
+            }
+            view.inner_instructions.insert(inner.index as usize, instructions);
+        }
+
+        let pre: Option<&Vec<UiTransactionTokenBalance>> = Option::from(meta.pre_token_balances.as_ref());
+        let post: Option<&Vec<UiTransactionTokenBalance>> = Option::from(meta.post_token_balances.as_ref());
+        for (balances, is_post) in [(pre, false), (post, true)] {
+            for balance in balances.into_iter().flatten() {
+                let Some(&account) = view.account_keys.get(balance.account_index as usize) else { continue };
+                let mint = Pubkey::from_str(&balance.mint)?;
+                let amount: u64 = balance.ui_token_amount.amount.parse()?;
+                // Un compte créé (ou fermé) dans la transaction n'a qu'un des deux soldes.
+                let entry = view.token_balances.entry(account).or_insert(TokenBalanceChange { mint, pre: 0, post: 0 });
+                if is_post { entry.post = amount } else { entry.pre = amount }
+            }
+        }
+
+        Ok(view)
+    }
+
+    fn instruction_accounts(&self, instruction: &CompiledInstruction) -> Option<Vec<Pubkey>> {
+        instruction.accounts.iter().map(|&index| self.account_keys.get(index as usize).copied()).collect()
+    }
+}
+
+fn parse_keys(keys: &[String]) -> Result<Vec<Pubkey>> {
+    keys.iter().map(|key| Ok(Pubkey::from_str(key)?)).collect()
+}
+
+/// Comment déterminer le token d'entrée d'un swap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapDirection {
+    /// Le vault du pool qui reçoit l'entrée (CLMM).
+    InputVault(Pubkey),
+    /// Le sens est un argument de l'instruction (Whirlpool).
+    AToB(bool),
+    /// Le compte de tokens de l'utilisateur qui paie l'entrée, et son propriétaire (AMM v4).
+    /// Le mint du compte se lit dans les soldes ; sans soldes (transaction en attente),
+    /// seul un ATA du propriétaire peut être rattaché à l'un des mints du pool.
+    UserSource { account: Pubkey, owner: Pubkey },
+}
+
+/// Une instruction de swap décodée, indépendamment de l'état du graphe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapInstruction {
+    pub dex: Dex,
+    pub pool_id: Pubkey,
+    pub direction: SwapDirection,
+    /// Le montant fixé par l'utilisateur : l'entrée si `exact_in`, sinon la sortie.
+    pub amount: u64,
+    /// La sortie minimale si `exact_in`, sinon l'entrée maximale.
+    pub other_amount_threshold: u64,
+    pub exact_in: bool,
+}
+
+/// Un swap observé sur un pool du graphe, tel que le backrunner le rejoue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObservedSwap {
+    pub pool_id: Pubkey,
+    pub input_mint: Pubkey,
+    pub amount_in: u64,
+}
+
+/// Une instruction de swap et sa position dans la transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodedSwap {
+    pub instruction_index: usize,
+    /// La position parmi les instructions internes, si le swap a été émis par CPI.
+    pub inner_index: Option<usize>,
+    pub swap: SwapInstruction,
+}
+
+/// Un swap rattaché à un pool du graphe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedSwap {
+    pub pool_index: usize,
+    pub pool_id: Pubkey,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    pub amount_in: u64,
+    /// La sortie réelle, lue dans les soldes des vaults si la transaction a été exécutée.
+    pub amount_out: Option<u64>,
+}
+
+impl ResolvedSwap {
+    pub fn to_observed_swap(&self) -> ObservedSwap {
+        ObservedSwap { pool_id: self.pool_id, input_mint: self.input_mint, amount_in: self.amount_in }
+    }
+}
+
+/// Décode une instruction si elle appartient à un programme de swap connu.
+pub fn decode_swap_instruction(program_id: &Pubkey, accounts: &[Pubkey], data: &[u8]) -> Option<SwapInstruction> {
+    if *program_id == RAYDIUM_AMM_V4_PROGRAM_ID {
+        decode_raydium_amm_swap(accounts, data)
+    } else if *program_id == RAYDIUM_CLMM_PROGRAM_ID {
+        decode_raydium_clmm_swap(accounts, data)
+    } else if *program_id == ORCA_WHIRLPOOL_PROGRAM_ID {
+        decode_whirlpool_swap(accounts, data)
+    } else {
+        None
+    }
+}
+
+/// Tous les swaps de la transaction, dans l'ordre d'exécution
+/// (chaque instruction externe, suivie de ses instructions internes).
+pub fn decode_swaps(view: &TransactionView) -> Vec<DecodedSwap> {
+    let decode = |instruction: &CompiledInstruction| {
+        let program_id = view.account_keys.get(instruction.program_id_index as usize)?;
+        let accounts = view.instruction_accounts(instruction)?;
+        decode_swap_instruction(program_id, &accounts, &instruction.data)
+    };
+
+    let mut swaps = Vec::new();
+    for (instruction_index, instruction) in view.instructions.iter().enumerate() {
+        if let Some(swap) = decode(instruction) {
+            swaps.push(DecodedSwap { instruction_index, inner_index: None, swap });
+        }
+        for (inner_index, inner) in view.inner_instructions.get(&instruction_index).into_iter().flatten().enumerate() {
+            if let Some(swap) = decode(inner) {
+                swaps.push(DecodedSwap { instruction_index, inner_index: Some(inner_index), swap });
+            }
+        }
+    }
+    swaps
+}
+
+impl DecodedSwap {
+    /// Rattache le swap à un pool du graphe et détermine son sens et ses montants.
+    /// Retourne `None` si le pool est inconnu ou si le sens ne peut pas être déterminé
+    /// (ex : swap AMM v4 en attente payé depuis un compte qui n'est pas un ATA, comme un compte WSOL temporaire).
+    /// Si le même pool est traversé plusieurs fois dans la transaction, les soldes des vaults
+    /// cumulent tous les passages : seuls les montants des instructions sont alors fiables.
+    pub fn resolve(&self, graph: &MarketGraph, view: &TransactionView) -> Option<ResolvedSwap> {
+        let swap = &self.swap;
+        let &pool_index = graph.pool_map.get(&swap.pool_id)?;
+        let pool = &graph.pools[pool_index];
+        let (mint_a, mint_b) = pool.get_mints();
+        let (vault_a, vault_b) = pool.vaults();
+
+        let input_mint = match swap.direction {
+            SwapDirection::InputVault(vault) if vault == vault_a => mint_a,
+            SwapDirection::InputVault(vault) if vault == vault_b => mint_b,
+            SwapDirection::InputVault(_) => return None,
+            SwapDirection::AToB(a_to_b) => if a_to_b { mint_a } else { mint_b },
+            SwapDirection::UserSource { account, owner } => match view.token_balances.get(&account) {
+                Some(balance) => balance.mint,
+                // Pas de soldes (transaction en attente) : on reconnaît l'ATA du propriétaire.
+                None if account == associated_token_address(&owner, &mint_a) => mint_a,
+                None if account == associated_token_address(&owner, &mint_b) => mint_b,
+                None => return None,
+            },
+        };
+        let (output_mint, vault_in, vault_out) = if input_mint == mint_a {
+            (mint_b, vault_a, vault_b)
+        } else if input_mint == mint_b {
+            (mint_a, vault_b, vault_a)
+        } else {
+            return None;
+        };
+
+        let received = view.token_balances.get(&vault_in).map(|b| b.post.saturating_sub(b.pre));
+        let paid = view.token_balances.get(&vault_out).map(|b| b.pre.saturating_sub(b.post));
+        let (amount_in, amount_out) = if swap.exact_in {
+            (swap.amount, paid)
+        } else {
+            // Swap à sortie exacte : l'entrée réelle n'est connue qu'après exécution.
+            (received?, Some(swap.amount))
+        };
+
+        Some(ResolvedSwap { pool_index, pool_id: swap.pool_id, input_mint, output_mint, amount_in, amount_out })
+    }
+}
+
+/// Les swaps de la transaction sur des pools du graphe, prêts pour le backrunner.
+/// Un swap sur un pool connu dont le sens ne peut pas être déterminé est écarté et signalé :
+/// le backrun d'une transaction en attente peut donc ne voir qu'une partie de ses swaps.
+pub fn observed_swaps(view: &TransactionView, graph: &MarketGraph) -> Vec<ObservedSwap> {
+    let mut observed = Vec::new();
+    for decoded in decode_swaps(view) {
+        match decoded.resolve(graph, view) {
+            Some(resolved) => observed.push(resolved.to_observed_swap()),
+            None if graph.pool_map.contains_key(&decoded.swap.pool_id) => tracing::debug!(
+                "Skipping unresolved {:?} swap on pool {} in {:?}",
+                decoded.swap.dex,
+                decoded.swap.pool_id,
+                view.signature
+            ),
+            None => {}
+        }
+    }
+    observed
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(offset..offset + 8)?.try_into().ok()?))
+}
+
+/// Raydium AMM v4 : swap_base_in (9) / swap_base_out (11), avec 17 ou 18 comptes,
+/// et leurs versions sans carnet d'ordres (16 / 17, 8 comptes).
+/// Les trois derniers comptes sont toujours : source, destination, propriétaire.
+fn decode_raydium_amm_swap(accounts: &[Pubkey], data: &[u8]) -> Option<SwapInstruction> {
+    let exact_in = match (data.first()?, accounts.len()) {
+        (9, 17 | 18) | (16, 8) => true,
+        (11, 17 | 18) | (17, 8) => false,
+        _ => return None,
+    };
+    let (first, second) = (read_u64(data, 1)?, read_u64(data, 9)?);
+    // swap_base_in : (amount_in, minimum_amount_out) ; swap_base_out : (max_amount_in, amount_out).
+    let (amount, other_amount_threshold) = if exact_in { (first, second) } else { (second, first) };
+    Some(SwapInstruction {
+        dex: Dex::RaydiumAmmV4,
+        pool_id: accounts[1],
+        direction: SwapDirection::UserSource { account: accounts[accounts.len() - 3], owner: accounts[accounts.len() - 1] },
+        amount,
+        other_amount_threshold,
+        exact_in,
+    })
+}
+
+/// Raydium CLMM : swap / swap_v2 (amount, other_amount_threshold, sqrt_price_limit_x64, is_base_input).
+/// Comptes : payer, amm_config, pool_state, input_token_account, output_token_account, input_vault, ...
+fn decode_raydium_clmm_swap(accounts: &[Pubkey], data: &[u8]) -> Option<SwapInstruction> {
+    let discriminator = data.get(..8)?;
+    if discriminator != instruction_discriminator("swap") && discriminator != instruction_discriminator("swap_v2") {
+        return None;
+    }
+    if accounts.len() < 7 {
+        return None;
+    }
+    Some(SwapInstruction {
+        dex: Dex::RaydiumClmm,
+        pool_id: accounts[2],
+        direction: SwapDirection::InputVault(accounts[5]),
+        amount: read_u64(data, 8)?,
+        other_amount_threshold: read_u64(data, 16)?,
+        exact_in: *data.get(40)? != 0,
+    })
+}
+
+/// Orca Whirlpool : swap / swap_v2
+/// (amount, other_amount_threshold, sqrt_price_limit, amount_specified_is_input, a_to_b).
+/// Le whirlpool est le 3e compte de `swap`, le 5e de `swap_v2`.
+fn decode_whirlpool_swap(accounts: &[Pubkey], data: &[u8]) -> Option<SwapInstruction> {
+    let discriminator = data.get(..8)?;
+    let pool_position = if discriminator == instruction_discriminator("swap") {
+        2
+    } else if discriminator == instruction_discriminator("swap_v2") {
+        4
+    } else {
+        return None;
+    };
+    Some(SwapInstruction {
+        dex: Dex::OrcaWhirlpool,
+        pool_id: *accounts.get(pool_position)?,
+        direction: SwapDirection::AToB(*data.get(41)? != 0),
+        amount: read_u64(data, 8)?,
+        other_amount_threshold: read_u64(data, 16)?,
+        exact_in: *data.get(40)? != 0,
+    })
+}
@@ -0,0 +1,443 @@
+// src/execution/bundle_builder.rs
+
+// Construction des instructions de swap, et assemblage des transactions d'arbitrage qui les enchaînent.
+
+use crate::decoders::raydium_amm::AMM_AUTHORITY;
+use crate::decoders::{
+    instruction_discriminator, orca_whirlpool, raydium_clmm, OrcaWhirlpoolPool, Pool, RaydiumAmmPool,
+    RaydiumClmmPool, ORCA_WHIRLPOOL_PROGRAM_ID, RAYDIUM_AMM_V4_PROGRAM_ID, RAYDIUM_CLMM_PROGRAM_ID,
+    RAYDIUM_CPMM_PROGRAM_ID,
+};
+pub use crate::decoders::{associated_token_address, ASSOCIATED_TOKEN_PROGRAM_ID};
+use crate::execution::bundle_sender::tip_instruction;
+use crate::execution::costs::{CostModel, SOL_MINT};
+use crate::execution::lookup_tables::LookupTableManager;
+use crate::strategies::opportunity::OwnedOpportunity;
+use anyhow::{anyhow, Result};
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_system_interface::{instruction as system_instruction, program as system_program};
+
+/// Le tag de l'instruction `swap_base_in` du programme Raydium AMM v4.
+pub const RAYDIUM_AMM_SWAP_BASE_IN: u8 = 9;
+
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = pubkey!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+pub const MEMO_PROGRAM_ID: Pubkey = pubkey!("MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr");
+
+/// Le tag de l'instruction `CreateIdempotent` du programme des comptes associés.
+const CREATE_ASSOCIATED_TOKEN_ACCOUNT_IDEMPOTENT: u8 = 1;
+
+/// Le nombre maximal de TickArrays passés à un swap concentré.
+pub const MAX_SWAP_TICK_ARRAYS: usize = 3;
+
+// Les bornes de √prix d'Orca : sans limite explicite, le swap peut aller jusqu'au bout de la courbe.
+const WHIRLPOOL_MIN_SQRT_PRICE_X64: u128 = 4_295_048_016;
+const WHIRLPOOL_MAX_SQRT_PRICE_X64: u128 = 79_226_673_515_401_279_992_447_579_055;
+
+/// Les comptes de l'utilisateur impliqués dans un swap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserSwapAccounts {
+    /// Le compte de tokens débité (token d'entrée).
+    pub source: Pubkey,
+    /// Le compte de tokens crédité (token de sortie).
+    pub destination: Pubkey,
+    /// Le propriétaire du compte source, signataire de la transaction.
+    pub owner: Pubkey,
+}
+
+/// Construit un `swap_base_in` Raydium AMM v4 : `amount_in` exact, au moins `minimum_amount_out` en sortie.
+/// Le sens du swap est déduit par le programme du mint du compte source.
+/// Avec `include_target_orders`, l'instruction a la forme historique à 18 comptes ;
+/// sinon la forme à 17 comptes, sans `target_orders`, acceptée par le programme actuel.
+/// Le pool doit avoir été hydraté avec les comptes de son marché OpenBook.
+pub fn raydium_amm_swap_base_in(
+    pool: &RaydiumAmmPool,
+    user: &UserSwapAccounts,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    include_target_orders: bool,
+) -> Result<Instruction> {
+    let market = pool.market.ok_or_else(|| anyhow!("Pool {} has no OpenBook market keys", pool.id))?;
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new(pool.id, false),
+        AccountMeta::new_readonly(AMM_AUTHORITY, false),
+        AccountMeta::new(pool.open_orders, false),
+    ];
+    if include_target_orders {
+        accounts.push(AccountMeta::new(pool.target_orders, false));
+    }
+    accounts.extend([
+        AccountMeta::new(pool.base_vault, false),
+        AccountMeta::new(pool.quote_vault, false),
+        AccountMeta::new_readonly(pool.market_program, false),
+        AccountMeta::new(pool.market_id, false),
+        AccountMeta::new(market.bids, false),
+        AccountMeta::new(market.asks, false),
+        AccountMeta::new(market.event_queue, false),
+        AccountMeta::new(market.base_vault, false),
+        AccountMeta::new(market.quote_vault, false),
+        AccountMeta::new_readonly(market.vault_signer, false),
+        AccountMeta::new(user.source, false),
+        AccountMeta::new(user.destination, false),
+        AccountMeta::new_readonly(user.owner, true),
+    ]);
+
+    let mut data = Vec::with_capacity(17);
+    data.push(RAYDIUM_AMM_SWAP_BASE_IN);
+    data.extend(amount_in.to_le_bytes());
+    data.extend(minimum_amount_out.to_le_bytes());
+
+    Ok(Instruction { program_id: RAYDIUM_AMM_V4_PROGRAM_ID, accounts, data })
+}
+
+/// Les instructions d'un swap, et les comptes du pool qu'elles référencent.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapInstructions {
+    pub instructions: Vec<Instruction>,
+    /// Les comptes requis par le swap hors comptes de l'utilisateur (programmes, pool, vaults,
+    /// TickArrays...), sans doublon : ce sont eux qui peuvent aller dans une table d'adresses.
+    pub accounts: Vec<Pubkey>,
+}
+
+impl SwapInstructions {
+    fn new(instructions: Vec<Instruction>, user: &UserSwapAccounts) -> Self {
+        let mut accounts = Vec::new();
+        for instruction in &instructions {
+            for key in std::iter::once(&instruction.program_id).chain(instruction.accounts.iter().map(|meta| &meta.pubkey)) {
+                let is_user = *key == user.source || *key == user.destination || *key == user.owner;
+                if !is_user && !accounts.contains(key) {
+                    accounts.push(*key);
+                }
+            }
+        }
+        Self { instructions, accounts }
+    }
+}
+
+/// Construit les instructions d'un swap "montant d'entrée exact" sur un pool.
+pub trait SwapInstructionBuilder {
+    /// `a_to_b` : on vend le token A du pool (le premier de `get_mints`) contre le token B.
+    /// `user.source` doit être le compte de tokens du mint vendu, `user.destination` celui du mint acheté.
+    fn build_swap(&self, a_to_b: bool, amount_in: u64, min_out: u64, user: &UserSwapAccounts) -> Result<SwapInstructions>;
+}
+
+impl SwapInstructionBuilder for Pool {
+    fn build_swap(&self, a_to_b: bool, amount_in: u64, min_out: u64, user: &UserSwapAccounts) -> Result<SwapInstructions> {
+        match self {
+            Pool::RaydiumAmm(pool) => pool.build_swap(a_to_b, amount_in, min_out, user),
+            Pool::RaydiumClmm(pool) => pool.build_swap(a_to_b, amount_in, min_out, user),
+            Pool::OrcaWhirlpool(pool) => pool.build_swap(a_to_b, amount_in, min_out, user),
+        }
+    }
+}
+
+impl SwapInstructionBuilder for RaydiumAmmPool {
+    /// Le programme déduit le sens du swap du compte source : `a_to_b` n'apparaît pas dans l'instruction.
+    fn build_swap(&self, _a_to_b: bool, amount_in: u64, min_out: u64, user: &UserSwapAccounts) -> Result<SwapInstructions> {
+        let instruction = raydium_amm_swap_base_in(self, user, amount_in, min_out, false)?;
+        Ok(SwapInstructions::new(vec![instruction], user))
+    }
+}
+
+impl SwapInstructionBuilder for RaydiumClmmPool {
+    /// `swap_v2`, suivi en comptes restants de l'extension du bitmap et des TickArrays à traverser.
+    fn build_swap(&self, a_to_b: bool, amount_in: u64, min_out: u64, user: &UserSwapAccounts) -> Result<SwapInstructions> {
+        let (input_vault, output_vault, input_mint, output_mint) = if a_to_b {
+            (self.vault_a, self.vault_b, self.mint_a, self.mint_b)
+        } else {
+            (self.vault_b, self.vault_a, self.mint_b, self.mint_a)
+        };
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(user.owner, true),
+            AccountMeta::new_readonly(self.amm_config, false),
+            AccountMeta::new(self.id, false),
+            AccountMeta::new(user.source, false),
+            AccountMeta::new(user.destination, false),
+            AccountMeta::new(input_vault, false),
+            AccountMeta::new(output_vault, false),
+            AccountMeta::new(self.observation_key, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(TOKEN_2022_PROGRAM_ID, false),
+            AccountMeta::new_readonly(MEMO_PROGRAM_ID, false),
+            AccountMeta::new_readonly(input_mint, false),
+            AccountMeta::new_readonly(output_mint, false),
+            AccountMeta::new(raydium_clmm_bitmap_extension_address(&self.id), false),
+        ];
+        accounts.extend(raydium_clmm_swap_tick_arrays(self, a_to_b).into_iter().map(|key| AccountMeta::new(key, false)));
+
+        // amount, other_amount_threshold, sqrt_price_limit_x64 (0 : pas de limite), is_base_input.
+        let mut data = Vec::with_capacity(41);
+        data.extend(instruction_discriminator("swap_v2"));
+        data.extend(amount_in.to_le_bytes());
+        data.extend(min_out.to_le_bytes());
+        data.extend(0u128.to_le_bytes());
+        data.push(1);
+
+        let instruction = Instruction { program_id: RAYDIUM_CLMM_PROGRAM_ID, accounts, data };
+        Ok(SwapInstructions::new(vec![instruction], user))
+    }
+}
+
+impl SwapInstructionBuilder for OrcaWhirlpoolPool {
+    /// `swap` (tokens SPL classiques), avec les trois TickArrays consécutifs dans le sens du swap et l'oracle.
+    fn build_swap(&self, a_to_b: bool, amount_in: u64, min_out: u64, user: &UserSwapAccounts) -> Result<SwapInstructions> {
+        // Les comptes de l'utilisateur sont désignés par token (A, B), pas par sens.
+        let (owner_account_a, owner_account_b) =
+            if a_to_b { (user.source, user.destination) } else { (user.destination, user.source) };
+
+        let mut accounts = vec![
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(user.owner, true),
+            AccountMeta::new(self.id, false),
+            AccountMeta::new(owner_account_a, false),
+            AccountMeta::new(self.vault_a, false),
+            AccountMeta::new(owner_account_b, false),
+            AccountMeta::new(self.vault_b, false),
+        ];
+        accounts.extend(whirlpool_swap_tick_arrays(self, a_to_b).into_iter().map(|key| AccountMeta::new(key, false)));
+        accounts.push(AccountMeta::new(whirlpool_oracle_address(&self.id), false));
+
+        let sqrt_price_limit = if a_to_b { WHIRLPOOL_MIN_SQRT_PRICE_X64 } else { WHIRLPOOL_MAX_SQRT_PRICE_X64 };
+        // amount, other_amount_threshold, sqrt_price_limit, amount_specified_is_input, a_to_b.
+        let mut data = Vec::with_capacity(42);
+        data.extend(instruction_discriminator("swap"));
+        data.extend(amount_in.to_le_bytes());
+        data.extend(min_out.to_le_bytes());
+        data.extend(sqrt_price_limit.to_le_bytes());
+        data.push(1);
+        data.push(a_to_b as u8);
+
+        let instruction = Instruction { program_id: ORCA_WHIRLPOOL_PROGRAM_ID, accounts, data };
+        Ok(SwapInstructions::new(vec![instruction], user))
+    }
+}
+
+/// Les comptes d'un pool Raydium CPMM nécessaires à un swap.
+/// Nous ne cotons pas encore ces pools : il n'y a pas de variante `Pool` correspondante.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaydiumCpmmPoolKeys {
+    pub id: Pubkey,
+    pub amm_config: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub vault_a: Pubkey,
+    pub vault_b: Pubkey,
+    pub observation_key: Pubkey,
+}
+
+impl SwapInstructionBuilder for RaydiumCpmmPoolKeys {
+    /// `swap_base_input`, pour des mints SPL classiques.
+    fn build_swap(&self, a_to_b: bool, amount_in: u64, min_out: u64, user: &UserSwapAccounts) -> Result<SwapInstructions> {
+        let (input_vault, output_vault, input_mint, output_mint) = if a_to_b {
+            (self.vault_a, self.vault_b, self.mint_a, self.mint_b)
+        } else {
+            (self.vault_b, self.vault_a, self.mint_b, self.mint_a)
+        };
+
+        let accounts = vec![
+            AccountMeta::new_readonly(user.owner, true),
+            AccountMeta::new_readonly(raydium_cpmm_authority(), false),
+            AccountMeta::new_readonly(self.amm_config, false),
+            AccountMeta::new(self.id, false),
+            AccountMeta::new(user.source, false),
+            AccountMeta::new(user.destination, false),
+            AccountMeta::new(input_vault, false),
+            AccountMeta::new(output_vault, false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(input_mint, false),
+            AccountMeta::new_readonly(output_mint, false),
+            AccountMeta::new(self.observation_key, false),
+        ];
+
+        let mut data = Vec::with_capacity(24);
+        data.extend(instruction_discriminator("swap_base_input"));
+        data.extend(amount_in.to_le_bytes());
+        data.extend(min_out.to_le_bytes());
+
+        let instruction = Instruction { program_id: RAYDIUM_CPMM_PROGRAM_ID, accounts, data };
+        Ok(SwapInstructions::new(vec![instruction], user))
+    }
+}
+
+/// L'autorité (PDA) qui signe les transferts depuis les vaults des pools CPMM.
+pub fn raydium_cpmm_authority() -> Pubkey {
+    Pubkey::find_program_address(&[b"vault_and_lp_mint_auth_seed"], &RAYDIUM_CPMM_PROGRAM_ID).0
+}
+
+/// L'adresse (PDA) de l'extension du bitmap des TickArrays d'un pool Raydium CLMM.
+pub fn raydium_clmm_bitmap_extension_address(pool_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"pool_tick_array_bitmap_extension", pool_id.as_ref()], &RAYDIUM_CLMM_PROGRAM_ID).0
+}
+
+/// L'adresse (PDA) de l'oracle d'un Whirlpool.
+pub fn whirlpool_oracle_address(whirlpool: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"oracle", whirlpool.as_ref()], &ORCA_WHIRLPOOL_PROGRAM_ID).0
+}
+
+/// Les TickArrays qu'un swap Raydium CLMM peut traverser, du plus proche au plus lointain.
+/// Chez Raydium, seuls les TickArrays initialisés existent : on retient ceux qui contiennent
+/// des ticks hydratés dans le sens du swap, et à défaut celui du tick courant.
+pub fn raydium_clmm_swap_tick_arrays(pool: &RaydiumClmmPool, a_to_b: bool) -> Vec<Pubkey> {
+    let current = raydium_clmm::tick_array_start_index(pool.current_tick, pool.tick_spacing);
+    let mut starts: Vec<i32> = pool
+        .ticks
+        .iter()
+        .map(|tick| raydium_clmm::tick_array_start_index(tick.tick_index, pool.tick_spacing))
+        .filter(|&start| if a_to_b { start <= current } else { start >= current })
+        .collect();
+    starts.sort_by_key(|&start| (start - current).abs());
+    starts.dedup();
+    if starts.is_empty() {
+        starts.push(current);
+    }
+    starts.truncate(MAX_SWAP_TICK_ARRAYS);
+    starts.into_iter().map(|start| raydium_clmm::tick_array_address(&pool.id, start)).collect()
+}
+
+/// Les trois TickArrays consécutifs qu'un swap Orca traverse, à partir de celui du tick courant.
+/// Vers B→A, le programme part du tick suivant : un tick courant en bord de TickArray
+/// appartient déjà au suivant.
+pub fn whirlpool_swap_tick_arrays(pool: &OrcaWhirlpoolPool, a_to_b: bool) -> Vec<Pubkey> {
+    let spacing = pool.tick_spacing as i32;
+    let shift = if a_to_b { 0 } else { spacing };
+    let first = orca_whirlpool::tick_array_start_index(pool.tick_current_index + shift, pool.tick_spacing);
+    let step = orca_whirlpool::TICK_ARRAY_SIZE * spacing * if a_to_b { -1 } else { 1 };
+    (0..MAX_SWAP_TICK_ARRAYS as i32)
+        .map(|i| orca_whirlpool::tick_array_address(&pool.id, first + i * step))
+        .collect()
+}
+
+/// Crée l'ATA de `owner` pour `mint` s'il n'existe pas encore, sans échouer sinon.
+pub fn create_associated_token_account_idempotent(payer: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(associated_token_address(owner, mint), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: vec![CREATE_ASSOCIATED_TOKEN_ACCOUNT_IDEMPOTENT],
+    }
+}
+
+/// Toutes les instructions d'un arbitrage, dans l'ordre d'exécution :
+/// 1. budget de calcul (limite estimée par le modèle de coûts, et prix s'il y en a un) ;
+/// 2. création idempotente des ATAs des mints intermédiaires ;
+/// 3. si le cycle part du SOL, wrap de `amount_in` lamports dans l'ATA WSOL ;
+/// 4. un swap par saut, avec les montants chaînés et sorties minimales de l'opportunité ;
+/// 5. si le cycle part du SOL, fermeture de l'ATA WSOL (unwrap) ;
+/// 6. si un compte de tip est donné, le transfert du pourboire Jito (toujours la dernière instruction).
+///
+/// La garde de profit est la sortie minimale du dernier saut, relevée à la mise plus les coûts
+/// d'exécution convertis dans le token de départ (`token_per_sol`, ignoré pour un cycle en SOL) :
+/// si le cycle ne les couvre pas, toute la transaction est annulée. Une opportunité dont la
+/// cotation ne couvre déjà plus ses coûts est refusée.
+/// Le pourboire est versé dans la transaction elle-même : une transaction de plus dans le bundle
+/// coûterait une signature que le modèle de coûts ne compte pas.
+/// Le wallet paie les frais et possède tous les comptes de tokens.
+pub fn arbitrage_instructions(
+    opportunity: &OwnedOpportunity,
+    wallet: &Pubkey,
+    costs: &CostModel,
+    token_per_sol: Option<u64>,
+    tip_account: Option<&Pubkey>,
+) -> Result<Vec<Instruction>> {
+    let start_mint = opportunity.start_mint().ok_or_else(|| anyhow!("Opportunity has no legs"))?;
+    let min_final_out = profit_guard(opportunity, costs, token_per_sol)?;
+
+    let compute_units = costs.compute_units(&opportunity.arbitrage_path());
+    let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(compute_units.min(u32::MAX as u64) as u32)];
+    if costs.compute_unit_price_micro_lamports > 0 {
+        instructions.push(ComputeBudgetInstruction::set_compute_unit_price(costs.compute_unit_price_micro_lamports));
+    }
+
+    let mut intermediate_mints: Vec<Pubkey> = Vec::new();
+    for leg in &opportunity.legs {
+        if leg.output_mint != start_mint && !intermediate_mints.contains(&leg.output_mint) {
+            intermediate_mints.push(leg.output_mint);
+        }
+    }
+    for mint in &intermediate_mints {
+        instructions.push(create_associated_token_account_idempotent(wallet, wallet, mint));
+    }
+
+    let start_account = associated_token_address(wallet, &start_mint);
+    let wraps_sol = start_mint == SOL_MINT;
+    if wraps_sol {
+        instructions.push(create_associated_token_account_idempotent(wallet, wallet, &SOL_MINT));
+        instructions.push(system_instruction::transfer(wallet, &start_account, opportunity.amount_in));
+        instructions.push(spl_token::instruction::sync_native(&spl_token::id(), &start_account)?);
+    }
+
+    let last_leg = opportunity.legs.len() - 1;
+    for (i, leg) in opportunity.legs.iter().enumerate() {
+        let user = UserSwapAccounts {
+            source: associated_token_address(wallet, &leg.input_mint),
+            destination: associated_token_address(wallet, &leg.output_mint),
+            owner: *wallet,
+        };
+        let min_out = if i == last_leg { min_final_out } else { leg.min_out };
+        let swap = leg.pool.build_swap(leg.a_to_b, leg.amount_in, min_out, &user)?;
+        instructions.extend(swap.instructions);
+    }
+
+    if wraps_sol {
+        instructions.push(spl_token::instruction::close_account(&spl_token::id(), &start_account, wallet, wallet, &[])?);
+    }
+
+    if let Some(tip_account) = tip_account {
+        let tip_lamports = costs
+            .tip_lamports(&opportunity.arbitrage_path(), opportunity.expected_gross_profit(), token_per_sol)
+            .ok_or_else(|| anyhow!("Tip cannot be priced in SOL"))?;
+        if tip_lamports > 0 {
+            instructions.push(tip_instruction(wallet, tip_account, tip_lamports));
+        }
+    }
+    Ok(instructions)
+}
+
+/// La sortie minimale du dernier saut : sa propre sortie minimale, relevée à la mise plus les coûts.
+pub fn profit_guard(opportunity: &OwnedOpportunity, costs: &CostModel, token_per_sol: Option<u64>) -> Result<u64> {
+    let last = opportunity.legs.last().ok_or_else(|| anyhow!("Opportunity has no legs"))?;
+    let path_costs = costs
+        .path_costs(&opportunity.arbitrage_path(), token_per_sol)
+        .ok_or_else(|| anyhow!("Execution costs cannot be priced in the start token"))?;
+    let costs_in_token = path_costs.fixed.saturating_add(path_costs.tip(opportunity.expected_gross_profit()));
+    let break_even = opportunity.amount_in.saturating_add(costs_in_token);
+    if last.expected_out < break_even {
+        return Err(anyhow!(
+            "Opportunity expects {} back, below its input plus costs {}",
+            last.expected_out,
+            break_even
+        ));
+    }
+    Ok(last.min_out.max(break_even))
+}
+
+/// Assemble et signe la transaction `v0` d'un arbitrage, en passant par nos tables d'adresses.
+/// Échoue si la transaction ne tient pas dans un paquet.
+pub fn build_arbitrage_transaction(
+    opportunity: &OwnedOpportunity,
+    wallet: &Keypair,
+    costs: &CostModel,
+    token_per_sol: Option<u64>,
+    tip_account: Option<&Pubkey>,
+    lookup_tables: &LookupTableManager,
+    recent_blockhash: Hash,
+) -> Result<VersionedTransaction> {
+    let instructions = arbitrage_instructions(opportunity, &wallet.pubkey(), costs, token_per_sol, tip_account)?;
+    lookup_tables.build_transaction(wallet, &instructions, recent_blockhash)
+}
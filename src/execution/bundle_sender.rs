@@ -0,0 +1,183 @@
+// src/execution/bundle_sender.rs
+
+// Soumission de bundles au block engine Jito, via son API JSON-RPC.
+// Un bundle est une liste ordonnée de transactions exécutées atomiquement, dans le même slot ;
+// le validateur ne l'inclut que si l'une d'elles verse un pourboire à un compte de tip Jito.
+// Le pourboire est une instruction de la transaction d'arbitrage (voir `tip_instruction`).
+
+use anyhow::{anyhow, Result};
+use base64::Engine;
+use serde::Deserialize;
+use rand::seq::SliceRandom;
+use serde_json::{json, Value};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_system_interface::instruction as system_instruction;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_BLOCK_ENGINE_URL: &str = "https://mainnet.block-engine.jito.wtf";
+
+/// Le chemin de l'API des bundles sur le block engine.
+const BUNDLES_PATH: &str = "/api/v1/bundles";
+
+/// Le nombre maximal de transactions dans un bundle.
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+
+/// Les comptes de tip Jito sur mainnet. Le pourboire va à l'un d'eux, tiré au hasard
+/// pour ne pas contendre en écriture avec les autres searchers sur le même compte.
+pub const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
+    pubkey!("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    pubkey!("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    pubkey!("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    pubkey!("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    pubkey!("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    pubkey!("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    pubkey!("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    pubkey!("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
+
+/// Le transfert de `lamports` du payeur vers un compte de tip.
+pub fn tip_instruction(payer: &Pubkey, tip_account: &Pubkey, lamports: u64) -> Instruction {
+    system_instruction::transfer(payer, tip_account, lamports)
+}
+
+/// Le statut d'un bundle atterri, tel que retourné par `getBundleStatuses`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BundleStatus {
+    pub bundle_id: String,
+    /// Les signatures des transactions du bundle.
+    pub transactions: Vec<String>,
+    pub slot: u64,
+    pub confirmation_status: Option<String>,
+    /// `{"Ok": null}` si le bundle s'est exécuté sans erreur.
+    #[serde(default)]
+    pub err: Value,
+}
+
+impl BundleStatus {
+    pub fn is_ok(&self) -> bool {
+        match &self.err {
+            Value::Null => true,
+            Value::Object(err) => err.contains_key("Ok"),
+            _ => false,
+        }
+    }
+}
+
+/// L'issue d'un bundle soumis.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BundleOutcome {
+    /// Le bundle a été inclus dans ce slot.
+    Landed { slot: u64 },
+    /// Le bundle a été inclus mais son exécution a échoué.
+    Failed { slot: u64, err: Value },
+    /// Le bundle n'est pas apparu avant l'échéance : il ne sera plus inclus.
+    Expired,
+}
+
+/// Le client du block engine Jito.
+#[derive(Debug, Clone)]
+pub struct BundleSender {
+    client: reqwest::Client,
+    url: String,
+    tip_accounts: Vec<Pubkey>,
+}
+
+impl BundleSender {
+    /// `block_engine_url` est la racine du block engine, par exemple `DEFAULT_BLOCK_ENGINE_URL`.
+    pub fn new(block_engine_url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: format!("{}{}", block_engine_url.trim_end_matches('/'), BUNDLES_PATH),
+            tip_accounts: JITO_TIP_ACCOUNTS.to_vec(),
+        }
+    }
+
+    /// Remplace les comptes de tip (par exemple ceux retournés par `get_tip_accounts`).
+    pub fn with_tip_accounts(mut self, tip_accounts: Vec<Pubkey>) -> Self {
+        self.tip_accounts = tip_accounts;
+        self
+    }
+
+    pub fn tip_accounts(&self) -> &[Pubkey] {
+        &self.tip_accounts
+    }
+
+    /// Un compte de tip tiré au hasard.
+    pub fn random_tip_account(&self) -> Result<Pubkey> {
+        self.tip_accounts.choose(&mut rand::thread_rng()).copied().ok_or_else(|| anyhow!("No Jito tip account configured"))
+    }
+
+    /// Le transfert de `lamports` du payeur vers un compte de tip tiré au hasard,
+    /// à ajouter en fin de transaction d'arbitrage.
+    pub fn tip_instruction(&self, payer: &Pubkey, lamports: u64) -> Result<Instruction> {
+        Ok(tip_instruction(payer, &self.random_tip_account()?, lamports))
+    }
+
+    /// Interroge le block engine pour la liste courante des comptes de tip.
+    pub async fn get_tip_accounts(&self) -> Result<Vec<Pubkey>> {
+        let result = self.call("getTipAccounts", json!([])).await?;
+        let accounts: Vec<String> = serde_json::from_value(result)?;
+        accounts.iter().map(|account| account.parse().map_err(|e| anyhow!("Invalid tip account {}: {}", account, e))).collect()
+    }
+
+    /// Soumet un bundle et retourne son identifiant.
+    pub async fn send_bundle(&self, transactions: &[VersionedTransaction]) -> Result<String> {
+        if transactions.is_empty() || transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+            return Err(anyhow!("A bundle holds 1 to {} transactions, got {}", MAX_BUNDLE_TRANSACTIONS, transactions.len()));
+        }
+        let encoded = transactions
+            .iter()
+            .map(|transaction| Ok(base64::engine::general_purpose::STANDARD.encode(bincode::serialize(transaction)?)))
+            .collect::<Result<Vec<String>>>()?;
+        let result = self.call("sendBundle", json!([encoded, { "encoding": "base64" }])).await?;
+        result.as_str().map(str::to_string).ok_or_else(|| anyhow!("Unexpected sendBundle result: {}", result))
+    }
+
+    /// Le statut de chaque bundle, `None` s'il n'a pas (encore) atterri.
+    pub async fn get_bundle_statuses(&self, bundle_ids: &[String]) -> Result<Vec<Option<BundleStatus>>> {
+        let result = self.call("getBundleStatuses", json!([bundle_ids])).await?;
+        let value = result.get("value").cloned().ok_or_else(|| anyhow!("Unexpected getBundleStatuses result: {}", result))?;
+        let statuses: Vec<Option<BundleStatus>> = serde_json::from_value(value)?;
+        // Le block engine ne renvoie que les bundles qu'il connaît : on les range par identifiant.
+        Ok(bundle_ids
+            .iter()
+            .map(|id| statuses.iter().flatten().find(|status| status.bundle_id == *id).cloned())
+            .collect())
+    }
+
+    /// Interroge le statut du bundle toutes les `poll_interval` jusqu'à ce qu'il atterrisse, ou jusqu'à `timeout`.
+    pub async fn wait_for_landing(&self, bundle_id: &str, timeout: Duration, poll_interval: Duration) -> Result<BundleOutcome> {
+        let deadline = Instant::now() + timeout;
+        let ids = [bundle_id.to_string()];
+        loop {
+            if let Some(Some(status)) = self.get_bundle_statuses(&ids).await?.pop() {
+                return Ok(if status.is_ok() {
+                    BundleOutcome::Landed { slot: status.slot }
+                } else {
+                    BundleOutcome::Failed { slot: status.slot, err: status.err }
+                });
+            }
+            if Instant::now() + poll_interval > deadline {
+                return Ok(BundleOutcome::Expired);
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Un appel JSON-RPC au block engine ; retourne le champ `result`.
+    async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let response = self.client.post(&self.url).json(&request).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Block engine {} failed with status: {}", method, response.status()));
+        }
+        let mut body: Value = response.json().await?;
+        if let Some(error) = body.get("error") {
+            return Err(anyhow!("Block engine {} returned an error: {}", method, error));
+        }
+        body.get_mut("result").map(Value::take).ok_or_else(|| anyhow!("Block engine {} returned no result", method))
+    }
+}
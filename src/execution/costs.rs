@@ -0,0 +1,187 @@
+// src/execution/costs.rs
+
+// Les coûts d'exécution d'un arbitrage : frais de signature, frais de priorité (prix par
+// compute unit), pourboire Jito et rent des comptes de tokens à créer. Ils sont payés en SOL,
+// alors que le profit est mesuré dans le token de départ du cycle : on les convertit.
+
+use super::optimizer::ArbitragePath;
+use crate::decoders::Dex;
+use solana_sdk::pubkey;
+use solana_sdk::pubkey::Pubkey;
+
+/// Le mint du SOL "wrappé" : la monnaie dans laquelle les coûts sont payés.
+pub const SOL_MINT: Pubkey = pubkey!("So11111111111111111111111111111111111111112");
+
+pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+/// La rent d'un compte de tokens SPL (165 octets).
+pub const TOKEN_ACCOUNT_RENT_LAMPORTS: u64 = 2_039_280;
+/// Les compute units hors swaps : instructions de compute budget, transfert du pourboire...
+pub const BASE_COMPUTE_UNITS: u64 = 20_000;
+/// Le pourboire minimal accepté par les block engines Jito.
+pub const MIN_JITO_TIP_LAMPORTS: u64 = 1_000;
+
+const BPS_DENOMINATOR: u64 = 10_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// Estimation des compute units consommées par un swap, selon le DEX.
+pub fn compute_units_for(dex: Dex) -> u64 {
+    match dex {
+        Dex::RaydiumAmmV4 => 45_000,
+        Dex::RaydiumCpmm => 55_000,
+        // Les pools à liquidité concentrée paient en plus chaque tick array parcouru.
+        Dex::RaydiumClmm => 90_000,
+        Dex::OrcaWhirlpool => 80_000,
+    }
+}
+
+/// Le pourboire versé au validateur.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TipPolicy {
+    /// Un montant fixe, en lamports.
+    Fixed(u64),
+    /// Une part (en points de base) du profit brut, avec un plancher en lamports.
+    ProfitShare { bps: u64, min_lamports: u64 },
+}
+
+/// Le modèle de coûts d'une transaction d'arbitrage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostModel {
+    pub lamports_per_signature: u64,
+    pub signatures: u64,
+    /// Le prix d'une compute unit (frais de priorité), en micro-lamports.
+    pub compute_unit_price_micro_lamports: u64,
+    pub base_compute_units: u64,
+    pub tip: TipPolicy,
+    /// Le nombre de comptes de tokens créés par la transaction (0 si tout est pré-créé).
+    pub token_accounts_to_create: u64,
+}
+
+impl Default for CostModel {
+    /// Les coûts d'un bundle Jito d'une signature, sans compte à créer,
+    /// qui reverse la moitié du profit brut en pourboire.
+    fn default() -> Self {
+        Self {
+            lamports_per_signature: LAMPORTS_PER_SIGNATURE,
+            signatures: 1,
+            compute_unit_price_micro_lamports: 1_000,
+            base_compute_units: BASE_COMPUTE_UNITS,
+            tip: TipPolicy::ProfitShare { bps: 5_000, min_lamports: MIN_JITO_TIP_LAMPORTS },
+            token_accounts_to_create: 0,
+        }
+    }
+}
+
+impl CostModel {
+    /// Aucun coût : le profit net est égal au profit brut.
+    pub fn free() -> Self {
+        Self {
+            lamports_per_signature: 0,
+            signatures: 0,
+            compute_unit_price_micro_lamports: 0,
+            base_compute_units: 0,
+            tip: TipPolicy::Fixed(0),
+            token_accounts_to_create: 0,
+        }
+    }
+
+    pub fn is_free(&self) -> bool {
+        let tip_is_free = match self.tip {
+            TipPolicy::Fixed(lamports) => lamports == 0,
+            TipPolicy::ProfitShare { bps, min_lamports } => bps == 0 && min_lamports == 0,
+        };
+        tip_is_free
+            && self.signatures * self.lamports_per_signature == 0
+            && self.compute_unit_price_micro_lamports == 0
+            && self.token_accounts_to_create == 0
+    }
+
+    /// Les compute units estimées pour exécuter le chemin.
+    pub fn compute_units(&self, path: &[ArbitragePath]) -> u64 {
+        self.base_compute_units + path.iter().map(|step| compute_units_for(step.pool.dex())).sum::<u64>()
+    }
+
+    /// Les coûts qui ne dépendent pas du profit (tout sauf un pourboire proportionnel), en lamports.
+    pub fn fixed_cost_lamports(&self, path: &[ArbitragePath]) -> u64 {
+        let priority_fee = (self.compute_units(path) as u128 * self.compute_unit_price_micro_lamports as u128)
+            .div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64;
+        let fixed_tip = match self.tip {
+            TipPolicy::Fixed(lamports) => lamports,
+            TipPolicy::ProfitShare { .. } => 0,
+        };
+        self.signatures * self.lamports_per_signature
+            + priority_fee
+            + self.token_accounts_to_create * TOKEN_ACCOUNT_RENT_LAMPORTS
+            + fixed_tip
+    }
+
+    /// Les coûts du chemin, exprimés dans son token de départ.
+    /// `token_per_sol` est la quantité de ce token (unités de base) qui vaut 1 SOL ;
+    /// si le cycle part du SOL, elle est ignorée. Retourne `None` si les coûts ne peuvent
+    /// pas être convertis (token de départ sans prix en SOL).
+    pub fn path_costs(&self, path: &[ArbitragePath], token_per_sol: Option<u64>) -> Option<PathCosts> {
+        if self.is_free() {
+            return Some(PathCosts::default());
+        }
+        let start_mint = path.first()?.input_mint;
+        let token_per_sol = if start_mint == SOL_MINT { LAMPORTS_PER_SOL } else { token_per_sol? };
+        let to_token = |lamports: u64| -> u64 {
+            (lamports as u128 * token_per_sol as u128).div_ceil(LAMPORTS_PER_SOL as u128).min(u64::MAX as u128) as u64
+        };
+
+        let (tip_bps, min_tip_lamports) = match self.tip {
+            TipPolicy::Fixed(_) => (0, 0),
+            TipPolicy::ProfitShare { bps, min_lamports } => (bps.min(BPS_DENOMINATOR), min_lamports),
+        };
+        Some(PathCosts { fixed: to_token(self.fixed_cost_lamports(path)), tip_bps, min_tip: to_token(min_tip_lamports) })
+    }
+
+    /// Le pourboire à verser pour un profit brut donné (dans le token de départ du chemin), en lamports.
+    /// Retourne `None` si un pourboire proportionnel ne peut pas être converti en SOL.
+    pub fn tip_lamports(&self, path: &[ArbitragePath], gross_profit: i64, token_per_sol: Option<u64>) -> Option<u64> {
+        let (bps, min_lamports) = match self.tip {
+            TipPolicy::Fixed(lamports) => return Some(lamports),
+            TipPolicy::ProfitShare { bps, min_lamports } => (bps.min(BPS_DENOMINATOR), min_lamports),
+        };
+        if bps == 0 {
+            return Some(min_lamports);
+        }
+        let start_mint = path.first()?.input_mint;
+        let token_per_sol = if start_mint == SOL_MINT { LAMPORTS_PER_SOL } else { token_per_sol? };
+        if token_per_sol == 0 {
+            return None;
+        }
+        let share = gross_profit.max(0) as u128 * bps as u128 / BPS_DENOMINATOR as u128;
+        let share_lamports = (share * LAMPORTS_PER_SOL as u128 / token_per_sol as u128).min(u64::MAX as u128) as u64;
+        Some(share_lamports.max(min_lamports))
+    }
+}
+
+/// Les coûts d'un chemin dans son token de départ, prêts à être déduits du profit brut.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PathCosts {
+    /// Frais de signature, de priorité, rent et pourboire fixe.
+    pub fixed: u64,
+    /// La part du profit brut reversée en pourboire, en points de base.
+    pub tip_bps: u64,
+    /// Le plancher du pourboire proportionnel.
+    pub min_tip: u64,
+}
+
+impl PathCosts {
+    /// Le pourboire proportionnel pour un profit brut donné.
+    pub fn tip(&self, gross_profit: i64) -> u64 {
+        if self.tip_bps == 0 && self.min_tip == 0 {
+            return 0;
+        }
+        let share = gross_profit.max(0) as u128 * self.tip_bps as u128 / BPS_DENOMINATOR as u128;
+        (share as u64).max(self.min_tip)
+    }
+
+    /// Le profit net d'un profit brut.
+    /// Avec une part de pourboire d'au plus 100 %, le profit net est croissant en le profit brut :
+    /// le montant qui maximise l'un maximise l'autre.
+    pub fn net_profit(&self, gross_profit: i64) -> i64 {
+        gross_profit.saturating_sub(self.fixed as i64).saturating_sub(self.tip(gross_profit) as i64)
+    }
+}
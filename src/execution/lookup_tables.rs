@@ -0,0 +1,197 @@
+// src/execution/lookup_tables.rs
+
+// Gestion de nos tables d'adresses (ALT). Une transaction legacy liste chaque compte en entier
+// (32 octets) : deux swaps Raydium v4 suffisent à dépasser les 1232 octets d'un paquet.
+// Un message v0 remplace les comptes présents dans une table par un index d'un octet.
+
+use anyhow::{anyhow, Result};
+use solana_address_lookup_table_interface::instruction::{create_lookup_table, extend_lookup_table};
+use solana_address_lookup_table_interface::state::{AddressLookupTable, LOOKUP_TABLE_MAX_ADDRESSES};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{v0, AddressLookupTableAccount, VersionedMessage};
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashMap;
+
+/// Le nombre d'adresses ajoutées par instruction `extend` : 20 × 32 octets tiennent
+/// largement dans une transaction, avec les signatures et les comptes de l'instruction.
+pub const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+/// Les tables d'adresses connues, et l'index inverse adresse -> table.
+#[derive(Debug, Clone, Default)]
+pub struct LookupTableManager {
+    tables: Vec<AddressLookupTableAccount>,
+    index: HashMap<Pubkey, usize>,
+}
+
+impl LookupTableManager {
+    pub fn new(tables: Vec<AddressLookupTableAccount>) -> Self {
+        let mut manager = Self::default();
+        for table in tables {
+            manager.add_table(table);
+        }
+        manager
+    }
+
+    /// Charge (ou recharge) des tables depuis le RPC.
+    pub fn load(rpc_client: &RpcClient, keys: &[Pubkey]) -> Result<Self> {
+        let mut manager = Self::default();
+        let accounts = rpc_client.get_multiple_accounts(keys)?;
+        for (key, account) in keys.iter().zip(accounts) {
+            let account = account.ok_or_else(|| anyhow!("Lookup table {} not found", key))?;
+            let table = AddressLookupTable::deserialize(&account.data)
+                .map_err(|e| anyhow!("Account {} is not a lookup table: {}", key, e))?;
+            manager.add_table(AddressLookupTableAccount { key: *key, addresses: table.addresses.to_vec() });
+        }
+        Ok(manager)
+    }
+
+    /// Ajoute une table, ou remplace celle qui a la même adresse.
+    pub fn add_table(&mut self, table: AddressLookupTableAccount) {
+        match self.tables.iter().position(|known| known.key == table.key) {
+            Some(position) => self.tables[position] = table,
+            None => self.tables.push(table),
+        }
+        self.reindex();
+    }
+
+    pub fn tables(&self) -> &[AddressLookupTableAccount] {
+        &self.tables
+    }
+
+    pub fn contains(&self, address: &Pubkey) -> bool {
+        self.index.contains_key(address)
+    }
+
+    /// La table qui contient `address`, s'il y en a une.
+    pub fn table_for(&self, address: &Pubkey) -> Option<Pubkey> {
+        self.index.get(address).map(|&position| self.tables[position].key)
+    }
+
+    /// Les comptes des instructions qui pourraient passer par une table mais n'y sont pas.
+    /// Les signataires et les programmes appelés doivent rester dans le message : ils sont exclus.
+    pub fn missing_accounts(&self, instructions: &[Instruction]) -> Vec<Pubkey> {
+        let mut missing = Vec::new();
+        for address in lookup_candidates(instructions) {
+            if !self.contains(&address) {
+                missing.push(address);
+            }
+        }
+        missing
+    }
+
+    /// Les tables utiles aux instructions, de celle qui couvre le plus de comptes à celle qui
+    /// en couvre le moins ; une table qui n'apporte aucun compte n'est pas retenue.
+    pub fn tables_for(&self, instructions: &[Instruction]) -> Vec<AddressLookupTableAccount> {
+        let mut coverage: HashMap<usize, usize> = HashMap::new();
+        for address in lookup_candidates(instructions) {
+            if let Some(&position) = self.index.get(&address) {
+                *coverage.entry(position).or_default() += 1;
+            }
+        }
+        let mut useful: Vec<(usize, usize)> = coverage.into_iter().collect();
+        useful.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        useful.into_iter().map(|(position, _)| self.tables[position].clone()).collect()
+    }
+
+    /// Compile un message v0 qui passe par nos tables autant que possible.
+    pub fn compile_message(&self, payer: &Pubkey, instructions: &[Instruction], recent_blockhash: Hash) -> Result<v0::Message> {
+        Ok(v0::Message::try_compile(payer, instructions, &self.tables_for(instructions), recent_blockhash)?)
+    }
+
+    /// Compile et signe une transaction v0, et refuse de la produire si elle dépasse la taille d'un paquet.
+    pub fn build_transaction(
+        &self,
+        payer: &Keypair,
+        instructions: &[Instruction],
+        recent_blockhash: Hash,
+    ) -> Result<VersionedTransaction> {
+        let message = self.compile_message(&payer.pubkey(), instructions, recent_blockhash)?;
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &[payer])?;
+        check_transaction_size(&transaction)?;
+        Ok(transaction)
+    }
+
+    /// Crée une nouvelle table. Elle ne sera utilisable qu'une fois étendue, un slot plus tard.
+    pub fn create_table_instruction(authority: &Pubkey, payer: &Pubkey, recent_slot: u64) -> (Instruction, Pubkey) {
+        create_lookup_table(*authority, *payer, recent_slot)
+    }
+
+    /// Les instructions qui ajoutent à `table` les adresses qui ne sont encore dans aucune de nos tables.
+    /// L'état local n'est pas modifié : les adresses ne seront utilisables qu'après rechargement.
+    pub fn extend_table_instructions(
+        &self,
+        table: &Pubkey,
+        authority: &Pubkey,
+        payer: &Pubkey,
+        addresses: &[Pubkey],
+    ) -> Result<Vec<Instruction>> {
+        let current_len = match self.tables.iter().find(|known| known.key == *table) {
+            Some(known) => known.addresses.len(),
+            None => return Err(anyhow!("Unknown lookup table {}", table)),
+        };
+        let mut new_addresses: Vec<Pubkey> = Vec::new();
+        for address in addresses {
+            if !self.contains(address) && !new_addresses.contains(address) {
+                new_addresses.push(*address);
+            }
+        }
+        if current_len + new_addresses.len() > LOOKUP_TABLE_MAX_ADDRESSES {
+            return Err(anyhow!(
+                "Lookup table {} holds {} addresses, {} more would exceed {}",
+                table,
+                current_len,
+                new_addresses.len(),
+                LOOKUP_TABLE_MAX_ADDRESSES
+            ));
+        }
+        Ok(new_addresses
+            .chunks(MAX_ADDRESSES_PER_EXTEND)
+            .map(|chunk| extend_lookup_table(*table, *authority, Some(*payer), chunk.to_vec()))
+            .collect())
+    }
+
+    fn reindex(&mut self) {
+        self.index.clear();
+        for (position, table) in self.tables.iter().enumerate() {
+            for address in &table.addresses {
+                // En cas de doublon, la première table gagne.
+                self.index.entry(*address).or_insert(position);
+            }
+        }
+    }
+}
+
+/// La taille sérialisée de la transaction, telle qu'envoyée sur le réseau.
+pub fn transaction_size(transaction: &VersionedTransaction) -> Result<usize> {
+    Ok(bincode::serialize(transaction)?.len())
+}
+
+/// Vérifie qu'une transaction tient dans un paquet, et retourne sa taille.
+pub fn check_transaction_size(transaction: &VersionedTransaction) -> Result<usize> {
+    let size = transaction_size(transaction)?;
+    if size > PACKET_DATA_SIZE {
+        return Err(anyhow!("Transaction is {} bytes, over the {} byte packet limit", size, PACKET_DATA_SIZE));
+    }
+    Ok(size)
+}
+
+/// Les comptes des instructions qui peuvent être chargés depuis une table, sans doublon.
+fn lookup_candidates(instructions: &[Instruction]) -> Vec<Pubkey> {
+    let metas = || instructions.iter().flat_map(|instruction| &instruction.accounts);
+    // Un compte signataire dans une instruction l'est pour tout le message, même s'il apparaît ailleurs sans signer.
+    let mut excluded: Vec<Pubkey> = instructions.iter().map(|instruction| instruction.program_id).collect();
+    excluded.extend(metas().filter(|meta| meta.is_signer).map(|meta| meta.pubkey));
+    let mut candidates: Vec<Pubkey> = Vec::new();
+    for meta in metas() {
+        if !excluded.contains(&meta.pubkey) && !candidates.contains(&meta.pubkey) {
+            candidates.push(meta.pubkey);
+        }
+    }
+    candidates
+}
@@ -1,5 +1,8 @@
 // src/execution/mod.rs
 
+pub mod bundle_builder;
+pub mod bundle_sender;
+pub mod costs;
+pub mod lookup_tables;
 pub mod optimizer;
-// pub mod bundle_builder; // Pour plus tard
-// pub mod bundle_sender;  // Pour plus tard
\ No newline at end of file
+pub mod simulation;
@@ -1,6 +1,7 @@
 // src/execution/optimizer.rs
 
 // On importe les traits et structs dont on a VRAIMENT besoin.
+use super::costs::PathCosts;
 use crate::decoders::{Pool, PoolOperations};
 use anyhow::Result;
 use solana_sdk::pubkey::Pubkey;
@@ -12,7 +13,7 @@ pub struct ArbitragePath<'a> {
     pub output_mint: Pubkey,
 }
 
-/// Simule un trade à travers un cycle d'arbitrage et retourne le profit.
+/// Simule un trade à travers un cycle d'arbitrage et retourne le profit brut (sans les coûts d'exécution).
 // La fonction est maintenant complète et correcte.
 pub fn simulate_path_profit(
     initial_amount: u64,
@@ -34,51 +35,260 @@ pub fn simulate_path_profit(
     Ok(current_amount as i64 - initial_amount as i64)
 }
 
-/// Trouve le montant d'entrée optimal pour maximiser le profit.
+/// Nombre maximal de points de rupture (franchissements de ticks) pris en compte :
+/// chaque point ajoute une recherche ternaire complète.
+pub const MAX_BREAKPOINTS: usize = 16;
+
+/// Un point évalué de la courbe de profit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProfitSample {
+    pub amount_in: u64,
+    /// Le gain en tokens du chemin, avant coûts d'exécution.
+    pub gross_profit: i64,
+    pub net_profit: i64,
+}
+
+/// Le résultat de l'optimisation d'un chemin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptimizationOutcome {
+    /// `profit` est le profit net (coûts déduits).
+    Profitable { amount_in: u64, profit: i64, gross_profit: i64 },
+    /// Aucune taille n'est rentable une fois les coûts déduits :
+    /// la taille la moins perdante trouvée, et son profit net (<= 0).
+    NoProfitableSize { best_amount: u64, best_profit: i64 },
+}
+
+/// Le résultat de l'optimisation, avec ce qu'il faut pour le diagnostiquer.
+#[derive(Debug, Clone)]
+pub struct OptimizationReport {
+    pub outcome: OptimizationOutcome,
+    /// Tous les points de la courbe évalués pendant la recherche, triés par montant.
+    pub samples: Vec<ProfitSample>,
+    /// Les montants d'entrée auxquels un pool du chemin franchit un tick.
+    pub breakpoints: Vec<u64>,
+    /// Le plus grand montant que le chemin peut absorber (au plus `max_amount`).
+    pub max_feasible_amount: u64,
+}
+
+impl OptimizationReport {
+    /// (montant, profit net) si une taille rentable a été trouvée.
+    pub fn profitable(&self) -> Option<(u64, i64)> {
+        match self.outcome {
+            OptimizationOutcome::Profitable { amount_in, profit, .. } => Some((amount_in, profit)),
+            OptimizationOutcome::NoProfitableSize { .. } => None,
+        }
+    }
+}
+
+/// Trouve le montant d'entrée optimal pour maximiser le profit net.
+/// Retourne `(0, 0)` si aucune taille n'est rentable ; voir `optimize_path` pour le détail.
 pub fn find_optimal_amount(
     path: &[ArbitragePath],
     max_amount: u64,
+    costs: &PathCosts,
 ) -> Result<(u64, i64)> {
+    Ok(optimize_path(path, max_amount, costs)?.profitable().unwrap_or((0, 0)))
+}
+
+/// Optimise la taille d'un cycle, pour le profit net des coûts d'exécution `costs`.
+/// Si tous les pools du chemin sont à produit constant, l'optimum est calculé analytiquement.
+/// Sinon, la courbe de profit n'est concave qu'entre deux franchissements de ticks : on la
+/// découpe à chaque franchissement et on fait une recherche ternaire sur chaque morceau.
+pub fn optimize_path(path: &[ArbitragePath], max_amount: u64, costs: &PathCosts) -> Result<OptimizationReport> {
     if path.is_empty() {
         return Err(anyhow::anyhow!("Arbitrage path cannot be empty"));
     }
+    // Les erreurs de structure (mints incohérents, pool sans données) ne dépendent pas du montant.
+    simulate_path_profit(1, path)?;
 
-    let mut low = 0;
-    let mut high = max_amount;
-    let mut optimal_amount = 0;
-    let mut max_profit = 0;
+    let mut curve = ProfitCurve::new(path, *costs);
+    let max_feasible_amount = max_feasible_amount(path, max_amount);
+    let mut breakpoints = Vec::new();
 
-    println!("\n--- Starting Optimization Search (0 -> {} lamports) ---", max_amount);
-    for i in 0..100 {
-        if low > high {
-            break;
+    if max_feasible_amount > 0 {
+        match closed_form_optimal_amount(path, max_feasible_amount) {
+            // Le profit net est croissant en le profit brut : l'optimum brut est aussi l'optimum net.
+            Some(amount) => {
+                curve.profit(amount.max(1))?;
+            }
+            None => {
+                breakpoints = path_breakpoints(path, max_feasible_amount)?;
+                let mut bounds = vec![1];
+                bounds.extend(&breakpoints);
+                bounds.push(max_feasible_amount);
+                for segment in bounds.windows(2) {
+                    curve.search_segment(segment[0], segment[1])?;
+                }
+            }
         }
+    }
 
-        let m1 = low + (high - low) / 3;
-        let m2 = high - (high - low) / 3;
-
-        let profit1 = simulate_path_profit(m1, path)?;
-        let profit2 = simulate_path_profit(m2, path)?;
+    let outcome = match curve.best() {
+        Some(best) if best.net_profit > 0 => OptimizationOutcome::Profitable {
+            amount_in: best.amount_in,
+            profit: best.net_profit,
+            gross_profit: best.gross_profit,
+        },
+        Some(best) => OptimizationOutcome::NoProfitableSize { best_amount: best.amount_in, best_profit: best.net_profit },
+        None => OptimizationOutcome::NoProfitableSize { best_amount: 0, best_profit: 0 },
+    };
+    Ok(OptimizationReport { outcome, samples: curve.into_samples(), breakpoints, max_feasible_amount })
+}
 
-        if i < 10 {
-            println!("Iter {}: m1={}, profit1={} | m2={}, profit2={}", i, m1, profit1, m2, profit2);
+/// L'optimum analytique d'un chemin composé uniquement de pools à produit constant.
+///
+/// Un swap x·y=k avec frais s'écrit `f(x) = a·x / (b + c·x)` avec `a = γ·R_out`, `b = R_in`, `c = γ`.
+/// La composée de deux telles fonctions a la même forme :
+/// `(a2·a1, b2·b1, b2·c1 + c2·a1)`. Le chemin entier équivaut donc à un seul pool virtuel,
+/// dont le profit `f(x) - x` est maximal en `x* = (√(a·b) - b) / c` (rentable seulement si `a > b`).
+/// Retourne `None` si un pool du chemin n'est pas à produit constant.
+pub fn closed_form_optimal_amount(path: &[ArbitragePath], max_amount: u64) -> Option<u64> {
+    let (mut a, mut b, mut c) = (1.0f64, 1.0f64, 0.0f64);
+    for step in path {
+        let (reserve_in, reserve_out, gamma) = step.pool.constant_product_params(&step.input_mint)?;
+        (a, b, c) = (gamma * reserve_out * a, reserve_in * b, reserve_in * c + gamma * a);
+        // On normalise pour rester loin des limites du f64 sur les longs chemins.
+        let scale = b;
+        if scale <= 0.0 {
+            return Some(0);
         }
+        (a, b, c) = (a / scale, 1.0, c / scale);
+    }
+
+    if a <= b || c <= 0.0 {
+        return Some(0);
+    }
+    let optimum = ((a * b).sqrt() - b) / c;
+    Some(if optimum >= max_amount as f64 { max_amount } else { optimum.max(0.0) as u64 })
+}
+
+/// Recherche ternaire du montant optimal sur `[1, max_amount]`, sans découpage aux ticks ni coûts.
+/// Le profit retourné peut être négatif si aucune taille n'est rentable.
+pub fn ternary_search_optimal_amount(
+    path: &[ArbitragePath],
+    max_amount: u64,
+) -> Result<(u64, i64)> {
+    if path.is_empty() {
+        return Err(anyhow::anyhow!("Arbitrage path cannot be empty"));
+    }
+    simulate_path_profit(1, path)?;
 
-        if profit1 > max_profit {
-            max_profit = profit1;
-            optimal_amount = m1;
+    let upper = max_feasible_amount(path, max_amount);
+    if upper == 0 {
+        return Ok((0, 0));
+    }
+    let mut curve = ProfitCurve::new(path, PathCosts::default());
+    curve.search_segment(1, upper)?;
+    Ok(curve.best().map_or((0, 0), |best| (best.amount_in, best.net_profit)))
+}
+
+/// La courbe de profit net d'un chemin, avec la mémoire des points déjà évalués.
+struct ProfitCurve<'p, 'a> {
+    path: &'p [ArbitragePath<'a>],
+    costs: PathCosts,
+    samples: Vec<ProfitSample>,
+}
+
+impl<'p, 'a> ProfitCurve<'p, 'a> {
+    fn new(path: &'p [ArbitragePath<'a>], costs: PathCosts) -> Self {
+        Self { path, costs, samples: Vec::new() }
+    }
+
+    /// Le profit net pour `amount_in`.
+    fn profit(&mut self, amount_in: u64) -> Result<i64> {
+        let gross_profit = simulate_path_profit(amount_in, self.path)?;
+        let net_profit = self.costs.net_profit(gross_profit);
+        self.samples.push(ProfitSample { amount_in, gross_profit, net_profit });
+        Ok(net_profit)
+    }
+
+    /// Recherche ternaire sur `[low, high]`, où la courbe est supposée concave.
+    fn search_segment(&mut self, low: u64, high: u64) -> Result<()> {
+        let (mut low, mut high) = (low, high);
+        // Avec au moins 3 points d'écart, `low < m1 < m2 <= high` : pas de débordement possible.
+        while high - low > 2 {
+            let m1 = low + (high - low) / 3;
+            let m2 = high - (high - low) / 3;
+            if self.profit(m1)? < self.profit(m2)? {
+                low = m1 + 1;
+            } else {
+                high = m2 - 1;
+            }
         }
-        if profit2 > max_profit {
-            max_profit = profit2;
-            optimal_amount = m2;
+        for amount in low..=high {
+            self.profit(amount)?;
         }
+        Ok(())
+    }
 
-        if profit1 < profit2 {
-            low = m1 + 1;
+    /// Le meilleur point évalué (le plus petit montant en cas d'égalité).
+    fn best(&self) -> Option<ProfitSample> {
+        self.samples.iter().copied().max_by_key(|sample| (sample.net_profit, std::cmp::Reverse(sample.amount_in)))
+    }
+
+    fn into_samples(mut self) -> Vec<ProfitSample> {
+        self.samples.sort_by_key(|sample| sample.amount_in);
+        self.samples.dedup_by_key(|sample| sample.amount_in);
+        self.samples
+    }
+}
+
+/// Le plus grand montant (au plus `max_amount`) que le chemin peut absorber
+/// sans dépasser la liquidité d'un de ses pools.
+fn max_feasible_amount(path: &[ArbitragePath], max_amount: u64) -> u64 {
+    if simulate_path_profit(max_amount, path).is_ok() {
+        return max_amount;
+    }
+    let (mut feasible, mut infeasible) = (0, max_amount);
+    while infeasible - feasible > 1 {
+        let mid = feasible + (infeasible - feasible) / 2;
+        if simulate_path_profit(mid, path).is_ok() {
+            feasible = mid;
         } else {
-            high = m2 - 1;
+            infeasible = mid;
         }
     }
+    feasible
+}
 
-    Ok((optimal_amount, max_profit))
-}
\ No newline at end of file
+/// Les montants initiaux auxquels un pool du chemin franchit un tick, pour une entrée
+/// jusqu'à `max_amount`. Les franchissements d'un pool intermédiaire sont exprimés dans son
+/// propre token d'entrée : on les ramène au montant initial (la sortie de chaque étape est
+/// croissante en son entrée).
+fn path_breakpoints(path: &[ArbitragePath], max_amount: u64) -> Result<Vec<u64>> {
+    let mut breakpoints = Vec::new();
+    let mut step_amount = max_amount;
+    for (hop, step) in path.iter().enumerate() {
+        for crossing in step.pool.tick_crossings(&step.input_mint, step_amount)? {
+            breakpoints.push(initial_amount_reaching(&path[..hop], crossing, max_amount)?);
+        }
+        step_amount = step.pool.get_quote(&step.input_mint, step_amount)?;
+    }
+    breakpoints.sort_unstable();
+    breakpoints.dedup();
+    breakpoints.retain(|&amount| amount > 1 && amount < max_amount);
+
+    if breakpoints.len() > MAX_BREAKPOINTS {
+        // Trop de ticks franchis : on en garde un sous-ensemble réparti sur toute la plage.
+        let stride = breakpoints.len().div_ceil(MAX_BREAKPOINTS);
+        breakpoints = breakpoints.into_iter().step_by(stride).collect();
+    }
+    Ok(breakpoints)
+}
+
+/// Le plus petit montant initial pour lequel `prefix` produit au moins `target`.
+fn initial_amount_reaching(prefix: &[ArbitragePath], target: u64, max_amount: u64) -> Result<u64> {
+    let reach = |amount: u64| -> Result<u64> {
+        prefix.iter().try_fold(amount, |amount, step| step.pool.get_quote(&step.input_mint, amount))
+    };
+    let (mut low, mut high) = (0, max_amount);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if reach(mid)? >= target {
+            high = mid;
+        } else {
+            low = mid + 1;
+        }
+    }
+    Ok(low)
+}
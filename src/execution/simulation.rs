@@ -0,0 +1,281 @@
+// src/execution/simulation.rs
+
+// Vérification d'une transaction d'arbitrage avant sa soumission : on la simule sur l'état
+// courant de la chaîne et on mesure ce que le wallet a réellement gagné dans le token de départ.
+// Une transaction qui échoue, qui rapporte moins que le seuil, ou dont le gain s'écarte
+// de la cotation (l'état des pools a changé depuis) n'est pas envoyée.
+// Les soldes de départ sont lus juste avant la simulation, qui tourne au plus quelques slots plus tard :
+// seules nos propres transactions touchent ces comptes, donc ce léger écart ne fausse pas le gain mesuré.
+
+use crate::execution::bundle_builder::associated_token_address;
+use crate::execution::costs::{LAMPORTS_PER_SIGNATURE, SOL_MINT};
+use crate::execution::optimizer::simulate_path_profit;
+use crate::strategies::opportunity::OwnedOpportunity;
+use anyhow::{anyhow, Result};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig};
+use solana_client::rpc_response::RpcSimulateTransactionResult;
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_sdk::account::Account;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_system_interface::program as system_program;
+
+/// L'écart toléré entre le profit simulé et le profit coté, en points de base du profit coté.
+pub const DEFAULT_PROFIT_TOLERANCE_BPS: u64 = 100;
+
+const BPS_DENOMINATOR: u64 = 10_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// La limite de compute units par instruction quand la transaction n'en fixe pas, et le plafond par transaction.
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u64 = 200_000;
+const MAX_COMPUTE_UNIT_LIMIT: u64 = 1_400_000;
+
+/// L'avance maximale de la simulation sur la lecture des soldes de départ, en slots (~400 ms chacun).
+const MAX_SLOT_LAG: u64 = 4;
+
+/// Le nombre de tentatives pour lire les soldes de départ et simuler dans cet écart.
+const MAX_SLOT_ATTEMPTS: usize = 3;
+
+/// Les critères d'acceptation d'une transaction simulée.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationPolicy {
+    /// Le profit minimal, en unités de base du token de départ.
+    pub min_profit: i64,
+    pub tolerance_bps: u64,
+}
+
+impl Default for SimulationPolicy {
+    fn default() -> Self {
+        Self { min_profit: 1, tolerance_bps: DEFAULT_PROFIT_TOLERANCE_BPS }
+    }
+}
+
+impl SimulationPolicy {
+    pub fn with_min_profit(mut self, min_profit: i64) -> Self {
+        self.min_profit = min_profit;
+        self
+    }
+
+    pub fn with_tolerance_bps(mut self, tolerance_bps: u64) -> Self {
+        self.tolerance_bps = tolerance_bps;
+        self
+    }
+}
+
+/// Le verdict de la simulation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SimulationVerdict {
+    Accepted,
+    /// La transaction échoue (par exemple sur la garde de sortie minimale).
+    Failed(String),
+    /// Le profit simulé est sous le seuil.
+    BelowThreshold,
+    /// Le profit simulé est inférieur au profit coté, au-delà de la tolérance.
+    Diverged,
+}
+
+/// Le résultat de la simulation d'une transaction d'arbitrage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationReport {
+    pub verdict: SimulationVerdict,
+    /// Le profit brut coté sur le chemin de l'opportunité (`simulate_path_profit`).
+    pub expected_profit: i64,
+    /// Le profit mesuré sur les soldes simulés, si la transaction s'est exécutée.
+    pub realized_profit: Option<i64>,
+    pub units_consumed: Option<u64>,
+    pub logs: Vec<String>,
+}
+
+impl SimulationReport {
+    pub fn is_accepted(&self) -> bool {
+        self.verdict == SimulationVerdict::Accepted
+    }
+}
+
+/// Simule la transaction d'une opportunité (blockhash remplacé, sans vérification des signatures)
+/// et compare le gain du wallet dans le token de départ au profit coté sur le chemin.
+///
+/// Pour un token SPL, le gain est la variation du solde de son ATA. Pour le SOL, l'ATA WSOL est
+/// fermé en fin de transaction : le gain est la variation des lamports du wallet et de ses ATAs
+/// (le loyer des comptes créés reste au wallet), frais de transaction et pourboire réintégrés.
+pub fn simulate_candidate(
+    rpc_client: &RpcClient,
+    transaction: &VersionedTransaction,
+    opportunity: &OwnedOpportunity,
+    wallet: &Pubkey,
+    policy: &SimulationPolicy,
+) -> Result<SimulationReport> {
+    let start_mint = opportunity.start_mint().ok_or_else(|| anyhow!("Opportunity has no legs"))?;
+    let expected_profit = simulate_path_profit(opportunity.amount_in, &opportunity.arbitrage_path())?;
+    let watched = watched_accounts(opportunity, wallet, &start_mint);
+
+    let (pre_accounts, result) = simulate_at_balance_slot(rpc_client, transaction, &watched)?;
+
+    let mut report = SimulationReport {
+        verdict: SimulationVerdict::Accepted,
+        expected_profit,
+        realized_profit: None,
+        units_consumed: result.units_consumed,
+        logs: result.logs.unwrap_or_default(),
+    };
+    if let Some(err) = result.err {
+        report.verdict = SimulationVerdict::Failed(err.to_string());
+        return Ok(report);
+    }
+
+    let returned = result.accounts.ok_or_else(|| anyhow!("Simulation returned no accounts"))?;
+    if returned.len() != watched.len() {
+        return Err(anyhow!("Simulation returned {} accounts, {} requested", returned.len(), watched.len()));
+    }
+    let post_accounts: Vec<Option<Account>> =
+        returned.iter().map(|account| account.as_ref().and_then(|account| account.decode::<Account>())).collect();
+
+    let realized_profit = if start_mint == SOL_MINT {
+        let fee = transaction_fee(&transaction.message);
+        total_lamports(&post_accounts) as i64 - total_lamports(&pre_accounts) as i64 + fee as i64 + paid_tip(transaction) as i64
+    } else {
+        token_amount(&post_accounts[0])? as i64 - token_amount(&pre_accounts[0])? as i64
+    };
+    report.realized_profit = Some(realized_profit);
+
+    let tolerance = (expected_profit.unsigned_abs() * policy.tolerance_bps / BPS_DENOMINATOR) as i64;
+    report.verdict = if realized_profit < expected_profit - tolerance {
+        SimulationVerdict::Diverged
+    } else if realized_profit < policy.min_profit {
+        SimulationVerdict::BelowThreshold
+    } else {
+        SimulationVerdict::Accepted
+    };
+    Ok(report)
+}
+
+/// Les frais d'une transaction, calculés sur son message : frais de signature, plus le prix des
+/// compute units multiplié par leur limite. On ne les demande pas au RPC : `getFeeForMessage`
+/// répond `null` pour un blockhash expiré, alors que la simulation remplace justement le blockhash.
+pub fn transaction_fee(message: &VersionedMessage) -> u64 {
+    let keys = message.static_account_keys();
+    let mut compute_unit_limit = None;
+    let mut compute_unit_price = 0u64;
+    let mut other_instructions = 0u64;
+    for instruction in message.instructions() {
+        if keys.get(instruction.program_id_index as usize) != Some(&solana_compute_budget_interface::id()) {
+            other_instructions += 1;
+            continue;
+        }
+        match borsh::from_slice::<ComputeBudgetInstruction>(&instruction.data) {
+            Ok(ComputeBudgetInstruction::SetComputeUnitLimit(limit)) => compute_unit_limit = Some(limit as u64),
+            Ok(ComputeBudgetInstruction::SetComputeUnitPrice(price)) => compute_unit_price = price,
+            _ => {}
+        }
+    }
+    let compute_unit_limit = compute_unit_limit
+        .unwrap_or(other_instructions * DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT)
+        .min(MAX_COMPUTE_UNIT_LIMIT);
+    let priority_fee =
+        (compute_unit_limit as u128 * compute_unit_price as u128).div_ceil(MICRO_LAMPORTS_PER_LAMPORT) as u64;
+    message.header().num_required_signatures as u64 * LAMPORTS_PER_SIGNATURE + priority_fee
+}
+
+/// Lit les soldes de départ puis simule la transaction à partir de ce slot (`min_context_slot`).
+/// Si le RPC a avancé de plus de `MAX_SLOT_LAG` slots entre les deux appels, on recommence.
+fn simulate_at_balance_slot(
+    rpc_client: &RpcClient,
+    transaction: &VersionedTransaction,
+    watched: &[Pubkey],
+) -> Result<(Vec<Option<Account>>, RpcSimulateTransactionResult)> {
+    let mut slots = (0, 0);
+    for _ in 0..MAX_SLOT_ATTEMPTS {
+        let account_config = RpcAccountInfoConfig { encoding: Some(UiAccountEncoding::Base64Zstd), ..RpcAccountInfoConfig::default() };
+        let pre_accounts = rpc_client.get_multiple_accounts_with_config(watched, account_config)?;
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            // Les comptes sont retournés en base64 par défaut.
+            accounts: Some(RpcSimulateTransactionAccountsConfig {
+                encoding: None,
+                addresses: watched.iter().map(|key| key.to_string()).collect(),
+            }),
+            min_context_slot: Some(pre_accounts.context.slot),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let simulated = rpc_client.simulate_transaction_with_config(transaction, config)?;
+        if simulated.context.slot <= pre_accounts.context.slot + MAX_SLOT_LAG {
+            return Ok((pre_accounts.value, simulated.value));
+        }
+        slots = (pre_accounts.context.slot, simulated.context.slot);
+    }
+    Err(anyhow!("Balances read at slot {} but simulated at slot {}, {} attempts", slots.0, slots.1, MAX_SLOT_ATTEMPTS))
+}
+
+/// Simule chaque candidat et ne garde que ceux qui sont acceptés.
+/// Un candidat dont la simulation n'a pas pu être faite est écarté.
+pub fn retain_simulated(
+    rpc_client: &RpcClient,
+    candidates: Vec<(OwnedOpportunity, VersionedTransaction)>,
+    wallet: &Pubkey,
+    policy: &SimulationPolicy,
+) -> Vec<(OwnedOpportunity, VersionedTransaction, SimulationReport)> {
+    let mut accepted = Vec::new();
+    for (opportunity, transaction) in candidates {
+        match simulate_candidate(rpc_client, &transaction, &opportunity, wallet, policy) {
+            Ok(report) if report.is_accepted() => accepted.push((opportunity, transaction, report)),
+            Ok(report) => tracing::debug!(
+                "Dropping candidate over {:?}: {:?} (expected {}, realized {:?})",
+                opportunity.pool_ids(),
+                report.verdict,
+                report.expected_profit,
+                report.realized_profit
+            ),
+            Err(e) => tracing::debug!("Could not simulate candidate over {:?}: {}", opportunity.pool_ids(), e),
+        }
+    }
+    accepted
+}
+
+/// Les comptes dont les soldes mesurent le gain : l'ATA du token de départ, ou pour le SOL,
+/// le wallet, son ATA WSOL et les ATAs des mints intermédiaires.
+fn watched_accounts(opportunity: &OwnedOpportunity, wallet: &Pubkey, start_mint: &Pubkey) -> Vec<Pubkey> {
+    if *start_mint != SOL_MINT {
+        return vec![associated_token_address(wallet, start_mint)];
+    }
+    let mut watched = vec![*wallet, associated_token_address(wallet, &SOL_MINT)];
+    for leg in &opportunity.legs {
+        let account = associated_token_address(wallet, &leg.output_mint);
+        if !watched.contains(&account) {
+            watched.push(account);
+        }
+    }
+    watched
+}
+
+/// Le pourboire versé par la transaction : `arbitrage_instructions` le place en dernière instruction,
+/// sous forme d'un transfert système.
+fn paid_tip(transaction: &VersionedTransaction) -> u64 {
+    let message = &transaction.message;
+    let Some(instruction) = message.instructions().last() else { return 0 };
+    let is_system = message.static_account_keys().get(instruction.program_id_index as usize) == Some(&system_program::id());
+    let data = &instruction.data;
+    // Un transfert système : discriminant 2 sur 4 octets, puis le montant.
+    if !is_system || data.len() != 12 || data[..4] != 2u32.to_le_bytes() {
+        return 0;
+    }
+    u64::from_le_bytes(data[4..].try_into().unwrap_or_default())
+}
+
+fn total_lamports(accounts: &[Option<Account>]) -> u64 {
+    accounts.iter().flatten().map(|account| account.lamports).sum()
+}
+
+/// Le solde d'un compte de tokens SPL ; un compte absent a un solde nul.
+fn token_amount(account: &Option<Account>) -> Result<u64> {
+    match account {
+        None => Ok(0),
+        Some(account) => {
+            let bytes = account.data.get(64..72).ok_or_else(|| anyhow!("Account is not a token account"))?;
+            Ok(u64::from_le_bytes(bytes.try_into()?))
+        }
+    }
+}
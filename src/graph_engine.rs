@@ -2,8 +2,8 @@
 
 use crate::{
     data_pipeline,
-    decoders::{Pool, RaydiumAmmPool},
-    state::{Edge, MarketGraph},
+    decoders::{self, Pool, PoolOperations, RaydiumAmmPool},
+    state::MarketGraph,
 };
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::pubkey::Pubkey;
@@ -27,39 +27,219 @@ pub fn build_hydrated_test_graph(rpc_client: &RpcClient) -> MarketGraph {
     let pool_accounts = rpc_client.get_multiple_accounts(&pool_pubkeys).unwrap();
 
     let mut graph = MarketGraph::default();
-    let mut token_indices = HashMap::new();
-    let mut nodes: Vec<Vec<Edge>> = Vec::new();
 
     for (i, maybe_account) in pool_accounts.into_iter().enumerate() {
         if let Some(account) = maybe_account {
             let pool_id = pool_pubkeys[i];
-            if let Ok(mut pool) = crate::decoders::raydium_amm::decode_raydium_amm(&pool_id, &account.data) {
-                if data_pipeline::data_scraper::hydrate_single_pool(&mut pool, rpc_client).is_ok() && pool.mint_a_reserve > 0 {
-                    let mint_a = pool.mint_a; let mint_b = pool.mint_b;
-                    let idx_a = *token_indices.entry(mint_a).or_insert_with(|| { let i = nodes.len(); nodes.push(Vec::new()); i });
-                    let idx_b = *token_indices.entry(mint_b).or_insert_with(|| { let i = nodes.len(); nodes.push(Vec::new()); i });
-                    let pool_enum = Pool::RaydiumAmm(pool);
-                    nodes[idx_a].push(Edge { destination: idx_b, pool: pool_enum.clone() });
-                    nodes[idx_b].push(Edge { destination: idx_a, pool: pool_enum });
-                }
+            // Le programme propriétaire du compte nous dit quel décodeur utiliser.
+            if let Ok(mut pool) = decoders::decode_pool(&account.owner, &pool_id, &account.data)
+                && data_pipeline::data_scraper::hydrate_pool(&mut pool, rpc_client).is_ok()
+                && pool.has_liquidity()
+            {
+                graph.add_pool(pool);
             }
         }
     }
 
     let wsol_mint = Pubkey::from_str(SOL_MINT).unwrap();
     let usdc_mint = Pubkey::from_str(USDC_MINT).unwrap();
-    if let (Some(&idx_sol), Some(&idx_usdc)) = (token_indices.get(&wsol_mint), token_indices.get(&usdc_mint)) {
+    if graph.token_map.contains_key(&wsol_mint) && graph.token_map.contains_key(&usdc_mint) {
         let fake_pool = Pool::RaydiumAmm(RaydiumAmmPool {
             id: Pubkey::new_unique(), mint_a: usdc_mint, mint_b: wsol_mint,
             mint_a_reserve: 149_000_000 * 10u64.pow(USDC_DECIMALS),
             mint_b_reserve: 1_000_000 * 10u64.pow(SOL_DECIMALS),
             base_vault: Pubkey::new_unique(), quote_vault: Pubkey::new_unique(),
+            ..Default::default()
         });
-        nodes[idx_usdc].push(Edge { destination: idx_sol, pool: fake_pool.clone() });
-        nodes[idx_sol].push(Edge { destination: idx_usdc, pool: fake_pool });
+        graph.add_pool(fake_pool);
     }
 
-    graph.token_map = token_indices;
-    graph.nodes = nodes;
     graph
-}
\ No newline at end of file
+}
+
+/// Nombre maximal de comptes par appel `getMultipleAccounts`.
+const MAX_ACCOUNTS_PER_REQUEST: usize = 100;
+
+/// Ré-hydrate tous les pools d'une copie du graphe (réserves, prix, ticks) et
+/// retourne la nouvelle version avec la liste des pools dont l'état a changé.
+/// Le prix, la liquidité et le tick courant d'un pool à liquidité concentrée vivent dans son
+/// propre compte : il est relu et re-décodé avant l'hydratation, qui recentre les ticks.
+/// Un pool qui ne peut pas être relu ou ré-hydraté garde son état précédent.
+pub fn refresh_pools(graph: &MarketGraph, rpc_client: &RpcClient) -> (MarketGraph, Vec<Pubkey>) {
+    let mut fresh = fetch_concentrated_pools(graph, rpc_client);
+    let mut refreshed = graph.clone();
+    let mut changed_pools = Vec::new();
+    for (idx, (previous, pool)) in graph.pools.iter().zip(refreshed.pools.iter_mut()).enumerate() {
+        let mut updated = match previous {
+            Pool::RaydiumAmm(_) => pool.clone(),
+            _ => match fresh.remove(&idx) {
+                Some(decoded) => decoded,
+                None => continue,
+            },
+        };
+        if data_pipeline::data_scraper::hydrate_pool(&mut updated, rpc_client).is_err() {
+            continue;
+        }
+        // On compare les états sérialisés : les pools n'implémentent pas `PartialEq`.
+        if bincode::serialize(&updated).ok() != bincode::serialize(previous).ok() {
+            changed_pools.push(updated.get_id());
+            *pool = updated;
+        }
+    }
+    (refreshed, changed_pools)
+}
+
+/// Relit et re-décode les comptes des pools CLMM et Whirlpool, indexés par leur place dans le graphe.
+/// Un compte absent, illisible ou dont les mints ont changé est écarté.
+fn fetch_concentrated_pools(graph: &MarketGraph, rpc_client: &RpcClient) -> HashMap<usize, Pool> {
+    let concentrated: Vec<usize> =
+        (0..graph.pools.len()).filter(|&idx| !matches!(graph.pools[idx], Pool::RaydiumAmm(_))).collect();
+    let mut fresh = HashMap::new();
+    for chunk in concentrated.chunks(MAX_ACCOUNTS_PER_REQUEST) {
+        let ids: Vec<Pubkey> = chunk.iter().map(|&idx| graph.pools[idx].get_id()).collect();
+        let accounts = match rpc_client.get_multiple_accounts(&ids) {
+            Ok(accounts) => accounts,
+            Err(e) => {
+                tracing::warn!("Could not refetch {} pool accounts: {}", ids.len(), e);
+                continue;
+            }
+        };
+        for ((&idx, id), account) in chunk.iter().zip(&ids).zip(accounts) {
+            let Some(account) = account else { continue };
+            match decoders::decode_pool(&account.owner, id, &account.data) {
+                Ok(pool) if pool.get_mints() == graph.pools[idx].get_mints() => {
+                    fresh.insert(idx, pool);
+                }
+                Ok(_) => tracing::warn!("Pool {} changed mints, keeping its previous state", id),
+                Err(e) => tracing::warn!("Could not decode pool {}: {}", id, e),
+            }
+        }
+    }
+    fresh
+}
+
+/// Élague itérativement les tokens de degré < 2 (un seul pool ou aucun).
+/// Un tel token ne peut jamais faire partie d'un cycle : on entre et on ressort
+/// forcément par le même pool. Retirer un token peut faire tomber le degré de ses
+/// voisins, d'où la boucle jusqu'au point fixe. Le graphe retourné est ré-indexé.
+pub fn prune_leaves(graph: &MarketGraph) -> MarketGraph {
+    let num_nodes = graph.nodes.len();
+    let mut degree: Vec<usize> = graph.nodes.iter().map(|edges| edges.len()).collect();
+    let mut removed = vec![false; num_nodes];
+    let mut stack: Vec<usize> = (0..num_nodes).filter(|&u| degree[u] < 2).collect();
+
+    while let Some(u) = stack.pop() {
+        if removed[u] {
+            continue;
+        }
+        removed[u] = true;
+        for edge in &graph.nodes[u] {
+            let v = edge.destination;
+            if removed[v] {
+                continue;
+            }
+            degree[v] -= 1;
+            if degree[v] < 2 {
+                stack.push(v);
+            }
+        }
+    }
+
+    let keep: Vec<bool> = removed.iter().map(|&r| !r).collect();
+    subgraph(graph, &keep)
+}
+
+/// Calcule les composantes fortement connexes du graphe (algorithme de Tarjan, itératif
+/// pour ne pas exploser la pile sur un graphe de marché complet).
+/// Chaque composante est une liste d'index de tokens.
+pub fn strongly_connected_components(graph: &MarketGraph) -> Vec<Vec<usize>> {
+    let num_nodes = graph.nodes.len();
+    let mut index = vec![usize::MAX; num_nodes];
+    let mut low_link = vec![0; num_nodes];
+    let mut on_stack = vec![false; num_nodes];
+    let mut stack = Vec::new();
+    let mut components = Vec::new();
+    let mut next_index = 0;
+
+    for root in 0..num_nodes {
+        if index[root] != usize::MAX {
+            continue;
+        }
+        // Pile d'appels simulée : (nœud, position de la prochaine arête à explorer).
+        let mut call_stack = vec![(root, 0)];
+        index[root] = next_index;
+        low_link[root] = next_index;
+        next_index += 1;
+        stack.push(root);
+        on_stack[root] = true;
+
+        while let Some(&(u, edge_pos)) = call_stack.last() {
+            if let Some(edge) = graph.nodes[u].get(edge_pos) {
+                if let Some(frame) = call_stack.last_mut() {
+                    frame.1 += 1;
+                }
+                let v = edge.destination;
+                if index[v] == usize::MAX {
+                    index[v] = next_index;
+                    low_link[v] = next_index;
+                    next_index += 1;
+                    stack.push(v);
+                    on_stack[v] = true;
+                    call_stack.push((v, 0));
+                } else if on_stack[v] {
+                    low_link[u] = low_link[u].min(index[v]);
+                }
+                continue;
+            }
+
+            call_stack.pop();
+            if let Some(&(parent, _)) = call_stack.last() {
+                low_link[parent] = low_link[parent].min(low_link[u]);
+            }
+            if low_link[u] == index[u] {
+                let mut component = Vec::new();
+                while let Some(w) = stack.pop() {
+                    on_stack[w] = false;
+                    component.push(w);
+                    if w == u {
+                        break;
+                    }
+                }
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+/// Restreint le graphe aux composantes fortement connexes qui contiennent au moins
+/// un des tokens de base. Les stratégies n'ont aucune raison de regarder le reste :
+/// un cycle qui ne peut pas partir d'un token de base ne nous sert à rien.
+pub fn restrict_to_base_components(graph: &MarketGraph, base_mints: &[Pubkey]) -> MarketGraph {
+    let base_indices: Vec<usize> = base_mints.iter().filter_map(|mint| graph.token_map.get(mint).copied()).collect();
+
+    let mut keep = vec![false; graph.nodes.len()];
+    for component in strongly_connected_components(graph) {
+        if component.iter().any(|idx| base_indices.contains(idx)) {
+            for idx in component {
+                keep[idx] = true;
+            }
+        }
+    }
+    subgraph(graph, &keep)
+}
+
+/// Reconstruit le graphe en ne gardant que les pools dont les deux tokens sont conservés.
+/// L'ordre des pools est préservé, donc le résultat est déterministe.
+fn subgraph(graph: &MarketGraph, keep: &[bool]) -> MarketGraph {
+    let mut result = MarketGraph::default();
+    for pool in &graph.pools {
+        let (mint_a, mint_b) = pool.get_mints();
+        let kept = |mint: &Pubkey| graph.token_map.get(mint).is_some_and(|&idx| keep[idx]);
+        if kept(&mint_a) && kept(&mint_b) {
+            result.add_pool(pool.clone());
+        }
+    }
+    result
+}
@@ -0,0 +1,171 @@
+// src/graph_export.rs
+
+// Export du graphe de marché en DOT (Graphviz) et en JSON, pour le débogage.
+// Au lieu de lire des index de tokens imprimés, on visualise directement
+// les tokens, les pools, les DEX et les taux courants de chaque arête.
+
+use crate::decoders::PoolOperations;
+use crate::state::MarketGraph;
+use crate::token_registry::TokenRegistry;
+use anyhow::Result;
+use serde::Serialize;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeSet, HashSet};
+use std::fmt::Write;
+
+/// Montant d'entrée utilisé pour les tokens dont on ne connaît pas les décimales.
+const DEFAULT_QUOTE_AMOUNT: u64 = 1_000_000_000;
+
+/// La portion du graphe à exporter.
+#[derive(Debug, Clone, Default)]
+pub struct GraphView {
+    /// Les index des tokens à inclure.
+    pub nodes: BTreeSet<usize>,
+    /// Les arêtes (source, destination) à mettre en évidence (ex : un cycle trouvé).
+    pub highlighted: HashSet<(usize, usize)>,
+}
+
+impl GraphView {
+    /// Le graphe complet.
+    pub fn full(graph: &MarketGraph) -> Self {
+        Self { nodes: (0..graph.nodes.len()).collect(), highlighted: HashSet::new() }
+    }
+
+    /// Le sous-graphe des tokens à au plus `depth` sauts du token donné.
+    pub fn around_token(graph: &MarketGraph, token_idx: usize, depth: usize) -> Self {
+        let mut nodes = BTreeSet::from([token_idx]);
+        let mut frontier = vec![token_idx];
+        for _ in 0..depth {
+            let mut next = Vec::new();
+            for u in frontier {
+                for edge in &graph.nodes[u] {
+                    if nodes.insert(edge.destination) {
+                        next.push(edge.destination);
+                    }
+                }
+            }
+            frontier = next;
+        }
+        Self { nodes, highlighted: HashSet::new() }
+    }
+
+    /// Le sous-graphe d'un cycle (liste d'index de tokens), dont les arêtes sont mises en évidence.
+    pub fn cycle(cycle: &[usize]) -> Self {
+        Self {
+            nodes: cycle.iter().copied().collect(),
+            highlighted: cycle.windows(2).map(|w| (w[0], w[1])).collect(),
+        }
+    }
+
+    /// Met en évidence les arêtes d'un cycle dans une vue existante.
+    pub fn with_highlighted_cycle(mut self, cycle: &[usize]) -> Self {
+        self.nodes.extend(cycle.iter().copied());
+        self.highlighted.extend(cycle.windows(2).map(|w| (w[0], w[1])));
+        self
+    }
+}
+
+#[derive(Serialize)]
+struct ExportedNode {
+    index: usize,
+    mint: String,
+    symbol: String,
+}
+
+#[derive(Serialize)]
+struct ExportedEdge {
+    source: usize,
+    destination: usize,
+    pool_id: String,
+    dex: String,
+    /// Taux courant (sortie / entrée, en unités humaines si les décimales sont connues).
+    rate: Option<f64>,
+    highlighted: bool,
+}
+
+#[derive(Serialize)]
+struct ExportedGraph {
+    nodes: Vec<ExportedNode>,
+    edges: Vec<ExportedEdge>,
+}
+
+/// Rend la vue du graphe au format DOT (Graphviz).
+pub fn to_dot(graph: &MarketGraph, registry: &TokenRegistry, view: &GraphView) -> String {
+    let exported = export(graph, registry, view);
+    let mut dot = String::from("digraph market {\n    rankdir=LR;\n    node [shape=ellipse];\n");
+
+    for node in &exported.nodes {
+        let _ = writeln!(dot, "    n{} [label=\"{}\\n{}\"];", node.index, escape_label(&node.symbol), short_key(&node.mint));
+    }
+    for edge in &exported.edges {
+        let rate = edge.rate.map(|r| format!("{:.6}", r)).unwrap_or_else(|| "n/a".to_string());
+        let style = if edge.highlighted { ", color=red, penwidth=2" } else { "" };
+        let _ = writeln!(
+            dot,
+            "    n{} -> n{} [label=\"{}\\n{}\\nrate={}\"{}];",
+            edge.source, edge.destination, escape_label(&edge.dex), short_key(&edge.pool_id), rate, style
+        );
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+/// Rend la vue du graphe en JSON.
+pub fn to_json(graph: &MarketGraph, registry: &TokenRegistry, view: &GraphView) -> Result<String> {
+    Ok(serde_json::to_string_pretty(&export(graph, registry, view))?)
+}
+
+fn export(graph: &MarketGraph, registry: &TokenRegistry, view: &GraphView) -> ExportedGraph {
+    let mints = &graph.tokens;
+    let nodes = view
+        .nodes
+        .iter()
+        .map(|&idx| ExportedNode { index: idx, mint: mints[idx].to_string(), symbol: registry.symbol(&mints[idx]) })
+        .collect();
+
+    let mut edges = Vec::new();
+    for &u in &view.nodes {
+        for edge in &graph.nodes[u] {
+            let v = edge.destination;
+            if !view.nodes.contains(&v) {
+                continue;
+            }
+            let pool = graph.edge_pool(edge);
+            edges.push(ExportedEdge {
+                source: u,
+                destination: v,
+                pool_id: pool.get_id().to_string(),
+                dex: pool.dex().to_string(),
+                rate: edge_rate(pool, registry, &mints[u], &mints[v]),
+                highlighted: view.highlighted.contains(&(u, v)),
+            });
+        }
+    }
+
+    ExportedGraph { nodes, edges }
+}
+
+/// Calcule le taux courant d'une arête pour un token d'entrée (1 unité si les décimales sont connues).
+/// Pas de taux si une unité du token ne tient pas dans un `u64` (20 décimales ou plus).
+fn edge_rate<P: PoolOperations>(pool: &P, registry: &TokenRegistry, mint_in: &Pubkey, mint_out: &Pubkey) -> Option<f64> {
+    let amount_in = match registry.decimals(mint_in) {
+        Some(decimals) => 10u64.checked_pow(decimals as u32)?,
+        None => DEFAULT_QUOTE_AMOUNT,
+    };
+    let amount_out = pool.get_quote(mint_in, amount_in).ok()?;
+
+    let scale = match (registry.decimals(mint_in), registry.decimals(mint_out)) {
+        (Some(dec_in), Some(dec_out)) => 10f64.powi(dec_in as i32 - dec_out as i32),
+        _ => 1.0,
+    };
+    Some(amount_out as f64 / amount_in as f64 * scale)
+}
+
+/// Échappe une valeur pour une chaîne DOT entre guillemets (les symboles viennent de métadonnées externes).
+fn escape_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+fn short_key(key: &str) -> String {
+    format!("{}…{}", &key[..4], &key[key.len() - 4..])
+}
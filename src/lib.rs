@@ -6,6 +6,9 @@ pub mod config;
 pub mod error;
 pub mod state;
 pub mod graph_engine;
+pub mod snapshot;
+pub mod graph_export;
+pub mod token_registry;
 pub mod data_pipeline;
 pub mod decoders;
 pub mod strategies;
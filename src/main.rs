@@ -1,16 +1,95 @@
 // src/main.rs
 
-// On importe notre propre librairie pour pouvoir utiliser ses modules.
-use mev_scalpel;
+use anyhow::Result;
+use mev_scalpel::{
+    config::Config,
+    decoders::PoolOperations,
+    graph_engine,
+    state::AppState,
+    strategies::runner::{self, QueuedOpportunity, StrategyRunner},
+};
+use solana_client::rpc_client::RpcClient;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+/// Montant d'entrée maximal d'une opportunité (100 SOL, en lamports).
+const MAX_TRADE_AMOUNT: u64 = 100 * 1_000_000_000;
+/// Intervalle de ré-hydratation des pools, en attendant un flux temps réel.
+const REFRESH_INTERVAL: Duration = Duration::from_secs(2);
+/// Capacité de la file d'opportunités entre les stratégies et l'exécution.
+const OPPORTUNITY_QUEUE_SIZE: usize = 1024;
 
 #[tokio::main]
-async fn main() {
+async fn main() -> Result<()> {
     println!("--- MEV SCALPEL BOT - STARTING ---");
 
-    // Ici, nous allons reconstruire la logique d'orchestration
-    // en appelant les fonctions de notre librairie.
-    // Par exemple :
-    // mev_scalpel::data_pipeline::...
+    let config = Config::load()?;
+    let base_mints = config.base_token_mints()?;
+    let rpc_client = Arc::new(RpcClient::new(config.solana_rpc_url.clone()));
+
+    // 1. Le graphe initial, restreint à la partie utile aux stratégies.
+    let graph = {
+        let rpc_client = rpc_client.clone();
+        tokio::task::spawn_blocking(move || graph_engine::build_hydrated_test_graph(&rpc_client)).await?
+    };
+    let graph = graph_engine::restrict_to_base_components(&graph_engine::prune_leaves(&graph), &base_mints);
+    println!("Graph built: {} pools, {} tokens", graph.pools.len(), graph.tokens.len());
+
+    // 2. Les stratégies activées, pilotées par le runner à chaque nouvelle version du graphe.
+    let cost_model = config.cost_model();
+    let strategies = runner::build_strategies(&config.strategies, &graph, &base_mints, MAX_TRADE_AMOUNT, &cost_model)?;
+    let strategy_runner = StrategyRunner::new(strategies);
+    println!("Enabled strategies: {:?}", strategy_runner.strategy_names());
+
+    let state = AppState::new();
+    let all_pools = graph.pools.iter().map(|pool| pool.get_id()).collect();
+    let slot = rpc_client.get_slot().unwrap_or_default();
+    state.publish(graph, slot, all_pools)?;
+
+    let (queue_tx, mut queue_rx) = mpsc::channel::<QueuedOpportunity>(OPPORTUNITY_QUEUE_SIZE);
+    tokio::spawn(strategy_runner.run(state.clone(), queue_tx));
+    tokio::spawn(refresh_loop(state.clone(), rpc_client));
+
+    // 3. La file commune d'opportunités. L'exécution viendra se brancher ici.
+    loop {
+        tokio::select! {
+            Some(queued) = queue_rx.recv() => {
+                let opportunity = &queued.opportunity;
+                println!("[{}] v{} slot {} | profit: {} | amount in: {} | min out: {} | pools: {:?}",
+                    queued.strategy, opportunity.graph_version, opportunity.slot,
+                    opportunity.expected_profit, opportunity.amount_in, opportunity.min_out, opportunity.pool_ids());
+            }
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    println!("--- MEV SCALPEL BOT - SHUTTING DOWN ---");
+    Ok(())
+}
+
+/// Ré-hydrate périodiquement les pools et publie une nouvelle version quand l'un d'eux a changé.
+async fn refresh_loop(state: AppState, rpc_client: Arc<RpcClient>) {
+    let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+    loop {
+        interval.tick().await;
+        let graph = state.graph.load_full();
+        let rpc = rpc_client.clone();
+        let refreshed = tokio::task::spawn_blocking(move || {
+            let slot = rpc.get_slot();
+            let (graph, changed_pools) = graph_engine::refresh_pools(&graph, &rpc);
+            (slot, graph, changed_pools)
+        })
+        .await;
 
-    println!("--- MEV SCALPEL BOT - SHUTTING DOWN (for now) ---");
-}
\ No newline at end of file
+        match refreshed {
+            Ok((Ok(slot), graph, changed_pools)) if !changed_pools.is_empty() => {
+                if let Err(e) = state.publish(graph, slot, changed_pools) {
+                    println!("Dropping refreshed graph: {}", e);
+                }
+            }
+            Ok((Err(e), _, _)) => println!("Failed to fetch slot: {}", e),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,187 @@
+// src/snapshot.rs
+
+// Sauvegarde et rechargement du graphe de marché, pour pouvoir rejouer
+// hors-ligne (et de manière déterministe) un état de marché capturé en live.
+
+use crate::state::{Edge, MarketGraph};
+use crate::decoders::Pool;
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Les formats de snapshot supportés.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotFormat {
+    /// Format binaire compact (bincode), pour les dumps volumineux.
+    Binary,
+    /// JSON lisible, pour l'analyse manuelle.
+    Json,
+}
+
+impl SnapshotFormat {
+    /// Déduit le format depuis l'extension du fichier (`.json` => JSON, sinon binaire).
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => SnapshotFormat::Json,
+            _ => SnapshotFormat::Binary,
+        }
+    }
+}
+
+impl MarketGraph {
+    /// Sérialise le graphe au format binaire compact.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(bincode::serialize(self)?)
+    }
+
+    /// Recharge un graphe depuis le format binaire compact.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(bincode::deserialize(bytes)?)
+    }
+
+    /// Sérialise le graphe en JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Recharge un graphe depuis du JSON.
+    pub fn from_json(json: &str) -> Result<Self> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// Écrit le graphe dans un fichier, le format étant choisi selon l'extension.
+pub fn save_graph(graph: &MarketGraph, path: &Path) -> Result<()> {
+    let bytes = match SnapshotFormat::from_path(path) {
+        SnapshotFormat::Binary => graph.to_bytes()?,
+        SnapshotFormat::Json => graph.to_json()?.into_bytes(),
+    };
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Recharge un graphe depuis un fichier, le format étant choisi selon l'extension.
+pub fn load_graph(path: &Path) -> Result<MarketGraph> {
+    let bytes = std::fs::read(path)?;
+    match SnapshotFormat::from_path(path) {
+        SnapshotFormat::Binary => MarketGraph::from_bytes(&bytes),
+        SnapshotFormat::Json => MarketGraph::from_json(std::str::from_utf8(&bytes)?),
+    }
+}
+
+// --- REPRÉSENTATION SÉRIALISÉE DU GRAPHE ---
+// Les HashMap (`token_map`, `pool_map`) ne sont pas sérialisées : ce sont des index
+// dérivés, reconstruits au chargement. On ne stocke que la liste ordonnée des tokens,
+// les arêtes et la table des pools, ce qui garantit des index identiques après rechargement.
+
+#[derive(Serialize)]
+struct GraphSnapshotRef<'a> {
+    #[serde(with = "pubkey_vec")]
+    tokens: &'a [Pubkey],
+    nodes: &'a [Vec<Edge>],
+    pools: &'a [Pool],
+}
+
+#[derive(Deserialize)]
+struct GraphSnapshot {
+    #[serde(with = "pubkey_vec")]
+    tokens: Vec<Pubkey>,
+    nodes: Vec<Vec<Edge>>,
+    pools: Vec<Pool>,
+}
+
+impl Serialize for MarketGraph {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        GraphSnapshotRef { tokens: &self.tokens, nodes: &self.nodes, pools: &self.pools }.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for MarketGraph {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let snapshot = GraphSnapshot::deserialize(deserializer)?;
+        MarketGraph::try_from(snapshot).map_err(serde::de::Error::custom)
+    }
+}
+
+impl TryFrom<GraphSnapshot> for MarketGraph {
+    type Error = anyhow::Error;
+
+    fn try_from(snapshot: GraphSnapshot) -> Result<Self> {
+        use crate::decoders::PoolOperations;
+
+        if snapshot.tokens.len() != snapshot.nodes.len() {
+            return Err(anyhow!("Snapshot has {} tokens but {} nodes", snapshot.tokens.len(), snapshot.nodes.len()));
+        }
+        let token_map: HashMap<Pubkey, usize> =
+            snapshot.tokens.iter().enumerate().map(|(idx, mint)| (*mint, idx)).collect();
+        if token_map.len() != snapshot.tokens.len() {
+            return Err(anyhow!("Snapshot lists the same token mint twice"));
+        }
+        let pool_map: HashMap<Pubkey, usize> =
+            snapshot.pools.iter().enumerate().map(|(idx, pool)| (pool.get_id(), idx)).collect();
+        if pool_map.len() != snapshot.pools.len() {
+            return Err(anyhow!("Snapshot lists the same pool id twice"));
+        }
+        for (source, edges) in snapshot.nodes.iter().enumerate() {
+            for edge in edges {
+                if edge.destination >= snapshot.nodes.len() || edge.pool_index >= snapshot.pools.len() {
+                    return Err(anyhow!("Snapshot contains an edge pointing outside of the graph"));
+                }
+                // Une arête relie les deux mints de son pool, dans un sens ou dans l'autre.
+                let pool = &snapshot.pools[edge.pool_index];
+                let (mint_a, mint_b) = pool.get_mints();
+                let ends = (snapshot.tokens[source], snapshot.tokens[edge.destination]);
+                if ends != (mint_a, mint_b) && ends != (mint_b, mint_a) {
+                    return Err(anyhow!("Snapshot edge {} -> {} does not match the mints of pool {}", ends.0, ends.1, pool.get_id()));
+                }
+            }
+        }
+
+        Ok(MarketGraph { token_map, pool_map, tokens: snapshot.tokens, nodes: snapshot.nodes, pools: snapshot.pools })
+    }
+}
+
+/// (Dé)sérialisation d'une `Pubkey` : chaîne base58 dans les formats lisibles (JSON),
+/// 32 octets bruts dans les formats binaires.
+pub mod pubkey {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+    use std::str::FromStr;
+
+    pub fn serialize<S: Serializer>(key: &Pubkey, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&key.to_string())
+        } else {
+            serializer.serialize_bytes(key.as_ref())
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pubkey, D::Error> {
+        if deserializer.is_human_readable() {
+            let s = String::deserialize(deserializer)?;
+            Pubkey::from_str(&s).map_err(serde::de::Error::custom)
+        } else {
+            let bytes = <Vec<u8>>::deserialize(deserializer)?;
+            Pubkey::try_from(bytes.as_slice()).map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+/// Même chose que `pubkey`, pour un vecteur de clés.
+pub mod pubkey_vec {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use solana_sdk::pubkey::Pubkey;
+
+    #[derive(Serialize, Deserialize)]
+    struct Key(#[serde(with = "super::pubkey")] Pubkey);
+
+    pub fn serialize<S: Serializer>(keys: &[Pubkey], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(keys.iter().map(|key| Key(*key)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Pubkey>, D::Error> {
+        Ok(<Vec<Key>>::deserialize(deserializer)?.into_iter().map(|key| key.0).collect())
+    }
+}
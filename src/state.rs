@@ -1,34 +1,164 @@
 // src/state.rs
 
-use crate::decoders::Pool;
+use crate::decoders::{Pool, PoolOperations};
+use anyhow::{anyhow, Result};
 use arc_swap::ArcSwap;
+use serde::{Deserialize, Serialize};
 use solana_sdk::pubkey::Pubkey;
 use std::collections::HashMap;
 use std::sync::Arc;
+use tokio::sync::watch;
 
 /// Représente une arête dans notre graphe de marché.
 /// Une arête est une connexion unidirectionnelle d'un token vers un autre via un pool.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Edge {
     /// L'index du token de destination dans notre liste de nœuds.
     pub destination: usize,
-    /// Le pool de liquidité qui facilite cet échange.
-    /// Il contient toute la logique pour calculer les prix.
-    pub pool: Pool,
+    /// L'index du pool dans la table `MarketGraph::pools`.
+    /// Les deux arêtes (aller et retour) d'un même pool pointent vers la même entrée,
+    /// donc une mise à jour des réserves ne s'applique qu'une seule fois.
+    pub pool_index: usize,
 }
 
 /// La structure principale de notre graphe de marché.
+/// Sérialisable (binaire et JSON) via le module `snapshot`.
 #[derive(Debug, Clone, Default)]
 pub struct MarketGraph {
     /// Fait le lien entre la Pubkey d'un token et son index (0, 1, 2...) dans notre graphe.
     /// C'est notre annuaire de tokens.
     pub token_map: HashMap<Pubkey, usize>,
 
+    /// L'annuaire inverse : `tokens[i]` est le mint du token d'index `i`.
+    pub tokens: Vec<Pubkey>,
+
     /// La liste d'adjacence qui représente le graphe.
     /// L'index de ce vecteur correspond à l'index du token.
     /// `nodes[i]` contient un vecteur de toutes les arêtes (tous les swaps possibles)
     /// qui partent du token `i`.
     pub nodes: Vec<Vec<Edge>>,
+
+    /// La table des pools : chaque pool n'y existe qu'une seule fois.
+    /// C'est la seule source de vérité pour les réserves et les prix.
+    pub pools: Vec<Pool>,
+
+    /// Fait le lien entre la Pubkey d'un pool et son index dans `pools`.
+    pub pool_map: HashMap<Pubkey, usize>,
+}
+
+impl MarketGraph {
+    /// Ajoute un pool au graphe et crée ses deux arêtes (A -> B et B -> A).
+    /// Si le pool existe déjà, son état est remplacé ; ses arêtes ne sont refaites que si ses mints
+    /// ont changé (les anciennes arêtes partent, les tokens restent dans l'annuaire).
+    /// Retourne l'index du pool dans la table.
+    pub fn add_pool(&mut self, pool: Pool) -> usize {
+        let pool_id = pool.get_id();
+        let (mint_a, mint_b) = pool.get_mints();
+        if let Some(&pool_index) = self.pool_map.get(&pool_id) {
+            let previous = std::mem::replace(&mut self.pools[pool_index], pool);
+            if previous.get_mints() != (mint_a, mint_b) {
+                for edges in &mut self.nodes {
+                    edges.retain(|edge| edge.pool_index != pool_index);
+                }
+                self.add_edges(pool_index, mint_a, mint_b);
+            }
+            return pool_index;
+        }
+
+        let pool_index = self.pools.len();
+        self.pools.push(pool);
+        self.pool_map.insert(pool_id, pool_index);
+        self.add_edges(pool_index, mint_a, mint_b);
+        pool_index
+    }
+
+    /// Crée les deux arêtes (A -> B et B -> A) du pool d'index `pool_index`.
+    fn add_edges(&mut self, pool_index: usize, mint_a: Pubkey, mint_b: Pubkey) {
+        let idx_a = self.get_or_insert_token(mint_a);
+        let idx_b = self.get_or_insert_token(mint_b);
+        self.nodes[idx_a].push(Edge { destination: idx_b, pool_index });
+        self.nodes[idx_b].push(Edge { destination: idx_a, pool_index });
+    }
+
+    /// Retourne le pool partagé qui porte cette arête.
+    pub fn edge_pool(&self, edge: &Edge) -> &Pool {
+        &self.pools[edge.pool_index]
+    }
+
+    /// Accès mutable à un pool par sa Pubkey, pour appliquer une mise à jour de réserves.
+    pub fn pool_mut(&mut self, pool_id: &Pubkey) -> Option<&mut Pool> {
+        let pool_index = *self.pool_map.get(pool_id)?;
+        self.pools.get_mut(pool_index)
+    }
+
+    /// Retourne l'index d'un token, en créant son nœud s'il n'existe pas encore.
+    fn get_or_insert_token(&mut self, mint: Pubkey) -> usize {
+        if let Some(&idx) = self.token_map.get(&mint) {
+            return idx;
+        }
+        let idx = self.nodes.len();
+        self.nodes.push(Vec::new());
+        self.tokens.push(mint);
+        self.token_map.insert(mint, idx);
+        idx
+    }
+}
+
+/// Des versions modifiées de quelques pools, lues par-dessus la table d'un graphe sans la copier.
+/// Sert à coter l'état du marché après une transaction observée : seuls les pools qu'elle
+/// touche sont clonés, le reste est lu directement dans le graphe.
+#[derive(Debug, Clone, Default)]
+pub struct PoolOverlay {
+    /// Index du pool (dans `MarketGraph::pools`) -> son état modifié.
+    pools: HashMap<usize, Pool>,
+}
+
+impl PoolOverlay {
+    /// Le pool d'index `pool_index` : sa version modifiée s'il y en a une, sinon celle du graphe.
+    pub fn pool<'a>(&'a self, graph: &'a MarketGraph, pool_index: usize) -> Option<&'a Pool> {
+        self.pools.get(&pool_index).or_else(|| graph.pools.get(pool_index))
+    }
+
+    /// Accès mutable à un pool du graphe par sa Pubkey ; il est cloné dans l'overlay au premier accès.
+    pub fn pool_mut(&mut self, graph: &MarketGraph, pool_id: &Pubkey) -> Option<&mut Pool> {
+        let pool_index = *graph.pool_map.get(pool_id)?;
+        Some(self.pools.entry(pool_index).or_insert_with(|| graph.pools[pool_index].clone()))
+    }
+
+    /// Indique si le pool d'index `pool_index` a été modifié.
+    pub fn contains(&self, pool_index: usize) -> bool {
+        self.pools.contains_key(&pool_index)
+    }
+
+    /// Le nombre de pools modifiés.
+    pub fn len(&self) -> usize {
+        self.pools.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pools.is_empty()
+    }
+}
+
+/// Décrit une nouvelle version du graphe publiée dans l'`AppState`.
+/// C'est le message envoyé aux stratégies à chaque mise à jour.
+#[derive(Debug, Clone, Default)]
+pub struct GraphUpdate {
+    /// Numéro de version du graphe, strictement croissant (0 = graphe vide initial).
+    pub version: u64,
+    /// Le slot Solana auquel les données de cette version ont été observées.
+    pub slot: u64,
+    /// Les pools dont l'état a changé par rapport à la version précédente.
+    pub changed_pools: Vec<Pubkey>,
+}
+
+impl GraphUpdate {
+    /// Indique si des versions ont été sautées depuis `last_seen_version`.
+    /// Un canal `watch` ne garde que la dernière valeur : si une stratégie a raté
+    /// des versions intermédiaires, `changed_pools` est incomplet et elle doit tout réévaluer.
+    pub fn missed_versions_since(&self, last_seen_version: u64) -> bool {
+        self.version > last_seen_version + 1
+    }
 }
 
 /// La structure d'état global de l'application, conçue pour être partagée
@@ -43,13 +173,65 @@ pub struct AppState {
     /// - Le thread de données écrit (swap) une nouvelle version du graphe.
     /// - Les threads de stratégie lisent (load) la version la plus récente.
     pub graph: Arc<ArcSwap<MarketGraph>>,
+
+    /// Le canal de notification des nouvelles versions.
+    /// Les stratégies s'y abonnent via `subscribe()` au lieu de sonder le graphe.
+    updates: watch::Sender<GraphUpdate>,
 }
 
 impl AppState {
     /// Crée un nouvel état d'application avec un graphe vide.
     pub fn new() -> Self {
+        let (updates, _) = watch::channel(GraphUpdate::default());
         Self {
             graph: Arc::new(ArcSwap::from(Arc::new(MarketGraph::default()))),
+            updates,
         }
     }
-}
\ No newline at end of file
+
+    /// Publie une nouvelle version du graphe et notifie tous les abonnés.
+    /// Le remplacement du graphe et l'incrément de version se font sous le même verrou
+    /// du canal, donc deux écrivains concurrents ne peuvent pas désordonner les versions,
+    /// et un abonné qui charge le graphe pendant qu'il emprunte la notification obtient
+    /// exactement la version qu'elle décrit.
+    /// Un graphe observé à un slot plus ancien que la version courante est refusé : il
+    /// remplacerait un état plus récent. Le graphe, la version et le slot restent inchangés.
+    /// Retourne le numéro de la version publiée.
+    pub fn publish(&self, graph: MarketGraph, slot: u64, changed_pools: Vec<Pubkey>) -> Result<u64> {
+        let mut published = Err(anyhow!("Nothing published"));
+        self.updates.send_if_modified(|update| {
+            if slot < update.slot {
+                published = Err(anyhow!("Graph observed at slot {} is older than the published slot {}", slot, update.slot));
+                return false;
+            }
+            self.graph.store(Arc::new(graph));
+            update.version += 1;
+            update.slot = slot;
+            update.changed_pools = changed_pools;
+            published = Ok(update.version);
+            true
+        });
+        published
+    }
+
+    /// S'abonne aux nouvelles versions du graphe.
+    pub fn subscribe(&self) -> watch::Receiver<GraphUpdate> {
+        self.updates.subscribe()
+    }
+
+    /// La version actuellement publiée.
+    pub fn version(&self) -> u64 {
+        self.updates.borrow().version
+    }
+
+    /// Le slot de la version actuellement publiée.
+    pub fn slot(&self) -> u64 {
+        self.updates.borrow().slot
+    }
+}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
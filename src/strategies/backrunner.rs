@@ -0,0 +1,120 @@
+// src/strategies/backrunner.rs
+
+// Backrun : un swap d'un autre utilisateur (en attente, ou tout juste exécuté) déplace
+// le prix des pools qu'il traverse. On rejoue ce swap sur une copie des seuls pools touchés,
+// puis on cherche le cycle qui ramène ces pools vers le prix du marché. Le bundle candidat
+// doit atterrir juste après la transaction observée, dans le même bloc.
+
+use super::cycle_index::CycleIndex;
+use super::opportunity::{OwnedOpportunity, DEFAULT_SLIPPAGE_BPS};
+use crate::execution::costs::CostModel;
+pub use crate::decoders::swap_instructions::ObservedSwap;
+use crate::state::{MarketGraph, PoolOverlay};
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+/// Un arbitrage à placer juste derrière une transaction observée.
+#[derive(Debug, Clone)]
+pub struct BackrunCandidate {
+    /// La transaction à suivre, si elle est connue (absente pour un swap simulé).
+    pub target_signature: Option<Signature>,
+    /// Les swaps de la transaction qui ont été rejoués.
+    pub target_swaps: Vec<ObservedSwap>,
+    /// L'arbitrage, coté sur l'état des pools APRÈS la transaction observée :
+    /// ses sauts portent cet état, pas celui du graphe.
+    pub opportunity: OwnedOpportunity,
+}
+
+/// Applique les swaps observés (dans l'ordre) à une copie des pools qu'ils touchent.
+/// Les swaps sur des pools inconnus sont ignorés ; le graphe n'est pas modifié.
+pub fn apply_observed_swaps(graph: &MarketGraph, swaps: &[ObservedSwap]) -> Result<PoolOverlay> {
+    let mut post_swap = PoolOverlay::default();
+    for swap in swaps {
+        if let Some(pool) = post_swap.pool_mut(graph, &swap.pool_id) {
+            pool.apply_swap(&swap.input_mint, swap.amount_in)
+                .map_err(|e| anyhow!("Cannot replay swap on pool {}: {}", swap.pool_id, e))?;
+        }
+    }
+    Ok(post_swap)
+}
+
+pub struct Backrunner {
+    index: CycleIndex,
+    max_amount: u64,
+    costs: CostModel,
+    slippage_bps: u64,
+}
+
+impl Backrunner {
+    /// `max_amount` borne le montant d'entrée de l'arbitrage (en unités de base du token de départ).
+    pub fn new(graph: &MarketGraph, base_mints: &[Pubkey], max_hops: usize, max_amount: u64) -> Self {
+        Self {
+            index: CycleIndex::build(graph, base_mints, max_hops),
+            max_amount,
+            costs: CostModel::free(),
+            slippage_bps: DEFAULT_SLIPPAGE_BPS,
+        }
+    }
+
+    /// La tolérance de slippage appliquée à la sortie finale des arbitrages, en points de base.
+    pub fn with_slippage_bps(mut self, slippage_bps: u64) -> Self {
+        self.slippage_bps = slippage_bps;
+        self
+    }
+
+    /// Les coûts d'exécution déduits du profit (gratuits par défaut).
+    pub fn with_cost_model(mut self, costs: CostModel) -> Self {
+        self.costs = costs;
+        self
+    }
+
+    /// Cherche les arbitrages ouverts par une transaction observée, du plus rentable au moins rentable.
+    /// Seuls les cycles qui passent par un pool touché par la transaction sont re-cotés.
+    /// `graph_version` et `slot` sont ceux du graphe, reportés sur les opportunités.
+    pub fn find_backruns(
+        &mut self,
+        graph: &MarketGraph,
+        graph_version: u64,
+        slot: u64,
+        target_signature: Option<Signature>,
+        swaps: &[ObservedSwap],
+    ) -> Result<Vec<BackrunCandidate>> {
+        let mut touched: Vec<Pubkey> = swaps
+            .iter()
+            .map(|swap| swap.pool_id)
+            .filter(|pool_id| graph.pool_map.contains_key(pool_id))
+            .collect();
+        touched.sort_unstable();
+        touched.dedup();
+        if touched.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.index.refresh(graph);
+        let post_swap = apply_observed_swaps(graph, swaps)?;
+
+        Ok(self
+            .index
+            .requote_over(graph, &post_swap, &touched, self.max_amount, &self.costs)
+            .into_iter()
+            .filter_map(|opportunity| {
+                match OwnedOpportunity::from_opportunity_over(
+                    graph,
+                    &post_swap,
+                    &opportunity,
+                    graph_version,
+                    slot,
+                    self.slippage_bps,
+                ) {
+                    Ok(opportunity) => Some(opportunity),
+                    Err(e) => {
+                        tracing::debug!("[backrun] Dropping opportunity on {:?}: {}", opportunity.cycle.pools, e);
+                        None
+                    }
+                }
+            })
+            .map(|opportunity| BackrunCandidate { target_signature, target_swaps: swaps.to_vec(), opportunity })
+            .collect())
+    }
+}
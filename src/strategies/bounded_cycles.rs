@@ -0,0 +1,144 @@
+// src/strategies/bounded_cycles.rs
+
+// Contrairement à SPFA, qui s'arrête au premier cycle négatif, cette stratégie
+// énumère TOUS les cycles simples courts (2 à 4 sauts) qui passent par nos tokens
+// de base, et les classe par profit estimé.
+
+use super::Cycle;
+use crate::execution::optimizer::{simulate_path_profit, ArbitragePath};
+use crate::state::MarketGraph;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+
+/// Nombre minimal de sauts d'un cycle (aller-retour entre deux pools d'une même paire).
+pub const MIN_HOPS: usize = 2;
+/// Nombre maximal de sauts par défaut.
+pub const MAX_HOPS: usize = 4;
+
+/// Montant (en unités de base du token de départ) utilisé pour estimer le profit d'un cycle.
+/// Volontairement petit : on estime le taux marginal, la taille est le travail de l'optimiseur.
+pub const PROBE_AMOUNT: u64 = 1_000_000;
+
+/// Un cycle accompagné de son profit estimé.
+#[derive(Debug, Clone)]
+pub struct RankedCycle {
+    pub cycle: Cycle,
+    /// Rendement relatif estimé sur `PROBE_AMOUNT` (0.01 = +1 %).
+    pub estimated_return: f64,
+}
+
+/// Énumère tous les cycles simples de `MIN_HOPS` à `max_hops` sauts qui partent
+/// d'un des tokens de base et y reviennent.
+/// Un même cycle trouvé depuis deux tokens de base différents n'est gardé qu'une fois
+/// (celui du premier token de base de la liste).
+pub fn enumerate_cycles(graph: &MarketGraph, base_mints: &[Pubkey], max_hops: usize) -> Vec<Cycle> {
+    let mut cycles = Vec::new();
+    let mut seen = HashSet::new();
+
+    for mint in base_mints {
+        let Some(&start) = graph.token_map.get(mint) else { continue };
+        let mut tokens = vec![start];
+        let mut pools = Vec::new();
+        let mut visited = vec![false; graph.nodes.len()];
+        visited[start] = true;
+        walk(graph, max_hops, &mut tokens, &mut pools, &mut visited, &mut |cycle| {
+            if seen.insert(canonical_key(&cycle)) {
+                cycles.push(cycle);
+            }
+        });
+    }
+
+    cycles
+}
+
+/// Énumère les cycles et ne garde que ceux qui sont rentables sur `probe_amount`,
+/// classés du plus rentable au moins rentable.
+pub fn find_profitable_cycles(
+    graph: &MarketGraph,
+    base_mints: &[Pubkey],
+    max_hops: usize,
+    probe_amount: u64,
+) -> Vec<RankedCycle> {
+    let mut ranked: Vec<RankedCycle> = enumerate_cycles(graph, base_mints, max_hops)
+        .into_iter()
+        .filter_map(|cycle| {
+            let estimated_return = estimate_return(graph, &cycle, probe_amount)?;
+            (estimated_return > 0.0).then_some(RankedCycle { cycle, estimated_return })
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.estimated_return.total_cmp(&a.estimated_return));
+    ranked
+}
+
+/// Les chemins candidats pour l'optimiseur, du plus au moins rentable.
+pub fn candidate_paths<'a>(
+    graph: &'a MarketGraph,
+    base_mints: &[Pubkey],
+    max_hops: usize,
+) -> Vec<Vec<ArbitragePath<'a>>> {
+    find_profitable_cycles(graph, base_mints, max_hops, PROBE_AMOUNT)
+        .iter()
+        .filter_map(|ranked| ranked.cycle.to_arbitrage_path(graph).ok())
+        .collect()
+}
+
+/// Rendement relatif d'un cycle sur `probe_amount` (`None` si un pool ne peut pas coter).
+pub fn estimate_return(graph: &MarketGraph, cycle: &Cycle, probe_amount: u64) -> Option<f64> {
+    let path = cycle.to_arbitrage_path(graph).ok()?;
+    let profit = simulate_path_profit(probe_amount, &path).ok()?;
+    Some(profit as f64 / probe_amount as f64)
+}
+
+/// Parcours en profondeur borné. `tokens` et `pools` contiennent le chemin courant.
+fn walk(
+    graph: &MarketGraph,
+    max_hops: usize,
+    tokens: &mut Vec<usize>,
+    pools: &mut Vec<usize>,
+    visited: &mut [bool],
+    on_cycle: &mut dyn FnMut(Cycle),
+) {
+    let start = tokens[0];
+    let u = *tokens.last().unwrap();
+
+    for edge in &graph.nodes[u] {
+        let v = edge.destination;
+        // Repasser par le pool qu'on vient d'emprunter ne peut jamais être rentable.
+        if pools.last() == Some(&edge.pool_index) {
+            continue;
+        }
+
+        if v == start {
+            if pools.len() + 1 >= MIN_HOPS {
+                let mut cycle_tokens = tokens.clone();
+                cycle_tokens.push(start);
+                let mut cycle_pools = pools.clone();
+                cycle_pools.push(edge.pool_index);
+                on_cycle(Cycle { tokens: cycle_tokens, pools: cycle_pools });
+            }
+            continue;
+        }
+
+        if visited[v] || pools.len() + 1 >= max_hops {
+            continue;
+        }
+
+        visited[v] = true;
+        tokens.push(v);
+        pools.push(edge.pool_index);
+        walk(graph, max_hops, tokens, pools, visited, on_cycle);
+        pools.pop();
+        tokens.pop();
+        visited[v] = false;
+    }
+}
+
+/// Clé canonique d'un cycle : la suite de ses pools, tournée pour commencer par le plus petit index.
+/// Deux rotations d'un même cycle (trouvées depuis deux tokens de base) ont la même clé ;
+/// les deux sens de parcours restent distincts, car leurs profits diffèrent.
+pub fn canonical_key(cycle: &Cycle) -> Vec<usize> {
+    let hops = cycle.hops();
+    let offset = (0..hops).min_by_key(|&i| cycle.pools[i]).unwrap_or(0);
+    (0..hops).map(|i| cycle.pools[(offset + i) % hops]).collect()
+}
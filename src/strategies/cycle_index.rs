@@ -0,0 +1,172 @@
+// src/strategies/cycle_index.rs
+
+// Index des cycles, construit une seule fois par topologie de graphe.
+// Quand les réserves d'un pool changent, on ne re-cote que les cycles qui
+// passent par ce pool, au lieu de relancer une recherche sur tout le graphe.
+
+use super::bounded_cycles;
+use super::sizing::CycleSizer;
+use super::{Cycle, Opportunity, Strategy};
+use crate::decoders::PoolOperations;
+use crate::execution::costs::CostModel;
+use crate::state::{GraphUpdate, MarketGraph, PoolOverlay};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+pub struct CycleIndex {
+    base_mints: Vec<Pubkey>,
+    max_hops: usize,
+    /// Empreinte de la topologie (tokens + pools, dans l'ordre) pour laquelle l'index est valide.
+    topology: u64,
+    cycles: Vec<Cycle>,
+    /// Pubkey d'un pool -> index (dans `cycles`) des cycles qui l'empruntent.
+    by_pool: HashMap<Pubkey, Vec<usize>>,
+}
+
+impl CycleIndex {
+    /// Énumère les cycles du graphe et construit l'index pool -> cycles.
+    pub fn build(graph: &MarketGraph, base_mints: &[Pubkey], max_hops: usize) -> Self {
+        let cycles = bounded_cycles::enumerate_cycles(graph, base_mints, max_hops);
+
+        let mut by_pool: HashMap<Pubkey, Vec<usize>> = HashMap::new();
+        for (cycle_idx, cycle) in cycles.iter().enumerate() {
+            for &pool_index in &cycle.pools {
+                by_pool.entry(graph.pools[pool_index].get_id()).or_default().push(cycle_idx);
+            }
+        }
+
+        Self {
+            base_mints: base_mints.to_vec(),
+            max_hops,
+            topology: topology_fingerprint(graph),
+            cycles,
+            by_pool,
+        }
+    }
+
+    /// Reconstruit l'index si la topologie du graphe a changé (pools ajoutés, graphe élagué...).
+    /// Retourne `true` si une reconstruction a eu lieu.
+    pub fn refresh(&mut self, graph: &MarketGraph) -> bool {
+        if topology_fingerprint(graph) == self.topology {
+            return false;
+        }
+        *self = Self::build(graph, &self.base_mints, self.max_hops);
+        true
+    }
+
+    pub fn cycles(&self) -> &[Cycle] {
+        &self.cycles
+    }
+
+    /// Les index des cycles qui passent par au moins un des pools donnés (sans doublon).
+    pub fn affected_cycles(&self, changed_pools: &[Pubkey]) -> Vec<usize> {
+        let mut affected: Vec<usize> = changed_pools
+            .iter()
+            .filter_map(|pool_id| self.by_pool.get(pool_id))
+            .flatten()
+            .copied()
+            .collect();
+        affected.sort_unstable();
+        affected.dedup();
+        affected
+    }
+
+    /// Re-cote uniquement les cycles touchés par les pools modifiés, et retourne
+    /// les opportunités rentables (dimensionnées par l'optimiseur, coûts déduits),
+    /// de la plus rentable à la moins rentable.
+    pub fn requote(
+        &self,
+        graph: &MarketGraph,
+        changed_pools: &[Pubkey],
+        max_amount: u64,
+        costs: &CostModel,
+    ) -> Vec<Opportunity> {
+        self.quote_cycles(graph, None, &self.affected_cycles(changed_pools), max_amount, costs)
+    }
+
+    /// Comme `requote`, sur l'état des pools modifiés dans `overlay` (ex : après une transaction observée).
+    pub fn requote_over(
+        &self,
+        graph: &MarketGraph,
+        overlay: &PoolOverlay,
+        changed_pools: &[Pubkey],
+        max_amount: u64,
+        costs: &CostModel,
+    ) -> Vec<Opportunity> {
+        self.quote_cycles(graph, Some(overlay), &self.affected_cycles(changed_pools), max_amount, costs)
+    }
+
+    /// Re-cote tous les cycles de l'index (ex : après une version manquée).
+    pub fn requote_all(&self, graph: &MarketGraph, max_amount: u64, costs: &CostModel) -> Vec<Opportunity> {
+        let all: Vec<usize> = (0..self.cycles.len()).collect();
+        self.quote_cycles(graph, None, &all, max_amount, costs)
+    }
+
+    fn quote_cycles(
+        &self,
+        graph: &MarketGraph,
+        overlay: Option<&PoolOverlay>,
+        cycle_indices: &[usize],
+        max_amount: u64,
+        costs: &CostModel,
+    ) -> Vec<Opportunity> {
+        if cycle_indices.is_empty() {
+            return Vec::new();
+        }
+        let mut sizer = CycleSizer::new(graph, max_amount, costs);
+        if let Some(overlay) = overlay {
+            sizer = sizer.with_overlay(overlay);
+        }
+        let mut opportunities: Vec<Opportunity> =
+            cycle_indices.iter().filter_map(|&idx| sizer.size(self.cycles[idx].clone())).collect();
+
+        opportunities.sort_by_key(|opportunity| std::cmp::Reverse(opportunity.expected_profit));
+        opportunities
+    }
+}
+
+/// Les cycles courts des tokens de base, re-cotés uniquement quand un de leurs pools change.
+pub struct BoundedCyclesStrategy {
+    index: CycleIndex,
+    max_amount: u64,
+    costs: CostModel,
+}
+
+impl BoundedCyclesStrategy {
+    pub fn new(graph: &MarketGraph, base_mints: &[Pubkey], max_hops: usize, max_amount: u64) -> Self {
+        Self { index: CycleIndex::build(graph, base_mints, max_hops), max_amount, costs: CostModel::free() }
+    }
+
+    /// Les coûts d'exécution déduits du profit (gratuits par défaut).
+    pub fn with_cost_model(mut self, costs: CostModel) -> Self {
+        self.costs = costs;
+        self
+    }
+}
+
+impl Strategy for BoundedCyclesStrategy {
+    fn name(&self) -> &'static str {
+        "bounded_cycles"
+    }
+
+    fn on_update(&mut self, graph: &MarketGraph, update: &GraphUpdate) -> Vec<Opportunity> {
+        // Si la topologie a changé, les anciens index de cycles ne veulent plus rien dire : on re-cote tout.
+        if self.index.refresh(graph) {
+            return self.index.requote_all(graph, self.max_amount, &self.costs);
+        }
+        self.index.requote(graph, &update.changed_pools, self.max_amount, &self.costs)
+    }
+}
+
+/// Empreinte de la topologie : les `Cycle` stockent des index, qui ne restent valides
+/// que si les tokens et les pools sont dans le même ordre.
+pub fn topology_fingerprint(graph: &MarketGraph) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    graph.tokens.hash(&mut hasher);
+    for pool in &graph.pools {
+        pool.get_id().hash(&mut hasher);
+    }
+    hasher.finish()
+}
@@ -1,4 +1,89 @@
 // src/strategies/mod.rs
 
+use crate::decoders::PoolOperations;
+use crate::execution::optimizer::ArbitragePath;
+use crate::state::{GraphUpdate, MarketGraph, PoolOverlay};
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+pub mod backrunner;
+pub mod bounded_cycles;
+pub mod cycle_index;
+pub mod opportunity;
+pub mod runner;
+pub mod sizing;
+pub mod spatial_arb;
 pub mod spfa_arb;
-// pub mod spatial_arb; // Pour plus tard
\ No newline at end of file
+
+/// Un cycle d'arbitrage dans le graphe de marché.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle {
+    /// Les index des tokens parcourus. Le cycle est fermé : le premier et le dernier sont identiques.
+    pub tokens: Vec<usize>,
+    /// L'index (dans `MarketGraph::pools`) du pool emprunté à chaque saut.
+    /// `pools[i]` relie `tokens[i]` à `tokens[i + 1]`.
+    pub pools: Vec<usize>,
+}
+
+impl Cycle {
+    /// Le nombre de swaps du cycle.
+    pub fn hops(&self) -> usize {
+        self.pools.len()
+    }
+
+    /// Construit le chemin attendu par l'optimiseur, en empruntant les pools du graphe.
+    pub fn to_arbitrage_path<'a>(&self, graph: &'a MarketGraph) -> Result<Vec<ArbitragePath<'a>>> {
+        self.path_over(graph, None)
+    }
+
+    /// Comme `to_arbitrage_path`, mais les pools modifiés dans `overlay` remplacent ceux du graphe.
+    pub fn to_arbitrage_path_over<'a>(&self, graph: &'a MarketGraph, overlay: &'a PoolOverlay) -> Result<Vec<ArbitragePath<'a>>> {
+        self.path_over(graph, Some(overlay))
+    }
+
+    fn path_over<'a>(&self, graph: &'a MarketGraph, overlay: Option<&'a PoolOverlay>) -> Result<Vec<ArbitragePath<'a>>> {
+        let mut path = Vec::with_capacity(self.hops());
+        for (i, &pool_index) in self.pools.iter().enumerate() {
+            let pool = match overlay {
+                Some(overlay) => overlay.pool(graph, pool_index),
+                None => graph.pools.get(pool_index),
+            };
+            let pool = pool.ok_or_else(|| anyhow!("Pool {} is not in the graph", pool_index))?;
+            let input_mint = graph.tokens[self.tokens[i]];
+            let output_mint = graph.tokens[self.tokens[i + 1]];
+
+            let (mint_a, mint_b) = pool.get_mints();
+            if !((input_mint == mint_a && output_mint == mint_b) || (input_mint == mint_b && output_mint == mint_a)) {
+                return Err(anyhow!("Pool {} does not connect the cycle tokens", pool_index));
+            }
+            path.push(ArbitragePath { pool, input_mint, output_mint });
+        }
+        Ok(path)
+    }
+}
+
+/// Une opportunité d'arbitrage dimensionnée, prête à être exécutée.
+/// Les index du cycle se rapportent à la version du graphe sur laquelle elle a été calculée.
+#[derive(Debug, Clone)]
+pub struct Opportunity {
+    pub cycle: Cycle,
+    /// Les pools du cycle, dans l'ordre des sauts : ils permettent de vérifier que les index
+    /// désignent toujours les mêmes pools dans une autre version du graphe.
+    pub pool_ids: Vec<Pubkey>,
+    /// Le montant d'entrée optimal, en unités de base du premier token du cycle.
+    pub amount_in: u64,
+    /// Le profit net attendu pour ce montant (coûts d'exécution déduits), dans le même token.
+    pub expected_profit: i64,
+}
+
+/// Une stratégie de recherche d'opportunités, réévaluée à chaque nouvelle version du graphe.
+/// Les stratégies sont pilotées par le `StrategyRunner` (voir `runner.rs`).
+pub trait Strategy: Send {
+    /// Le nom court de la stratégie, utilisé dans la configuration (`STRATEGIES`) et les logs.
+    fn name(&self) -> &'static str;
+
+    /// Cherche les opportunités dans le graphe publié par `update`.
+    /// `update.changed_pools` liste les pools modifiés depuis la version précédente :
+    /// une stratégie incrémentale peut se limiter aux cycles qui les empruntent.
+    fn on_update(&mut self, graph: &MarketGraph, update: &GraphUpdate) -> Vec<Opportunity>;
+}
@@ -0,0 +1,156 @@
+// src/strategies/opportunity.rs
+
+// Une opportunité "détachée" du graphe. `Opportunity` désigne ses pools par leur index
+// dans une version précise du graphe, et `ArbitragePath` emprunte les pools de cette version :
+// ni l'un ni l'autre ne survit au remplacement de l'instantané. `OwnedOpportunity` copie
+// tout ce dont l'exécution a besoin, et peut traverser un channel vers l'exécuteur.
+
+use super::Opportunity;
+use crate::decoders::{Dex, Pool, PoolOperations};
+use crate::execution::optimizer::ArbitragePath;
+use crate::state::{MarketGraph, PoolOverlay};
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+
+/// La tolérance de slippage par défaut sur la sortie finale, en points de base.
+pub const DEFAULT_SLIPPAGE_BPS: u64 = 50;
+
+const BPS_DENOMINATOR: u64 = 10_000;
+
+/// Un saut du chemin, coté sur l'état du pool au moment de la cotation.
+#[derive(Debug, Clone)]
+pub struct PathLeg {
+    pub pool_id: Pubkey,
+    pub dex: Dex,
+    pub input_mint: Pubkey,
+    pub output_mint: Pubkey,
+    /// `true` si l'on vend le token A du pool (le premier de `get_mints`).
+    pub a_to_b: bool,
+    /// L'état du pool sur lequel le saut a été coté.
+    pub pool: Pool,
+    pub amount_in: u64,
+    pub expected_out: u64,
+    /// La sortie minimale acceptée, tolérance de slippage déduite.
+    pub min_out: u64,
+}
+
+/// Une opportunité prête à être exécutée, indépendante de la version du graphe.
+#[derive(Debug, Clone)]
+pub struct OwnedOpportunity {
+    pub legs: Vec<PathLeg>,
+    /// Le montant engagé, en unités de base du token de départ.
+    pub amount_in: u64,
+    /// Ce que le dernier saut doit rendre, dans le même token, sur les sorties cotées chaînées.
+    pub expected_out: u64,
+    /// La sortie minimale du dernier saut : jamais moins que le montant engagé.
+    pub min_out: u64,
+    /// Le profit net attendu (coûts d'exécution déduits), tel que coté par la stratégie.
+    pub expected_profit: i64,
+    /// La version du graphe (et le slot) sur laquelle l'opportunité a été cotée.
+    pub graph_version: u64,
+    pub slot: u64,
+}
+
+impl OwnedOpportunity {
+    /// Recote le cycle d'une opportunité sur `graph`, saut par saut, et copie l'état de ses pools.
+    /// Chaque saut engage la sortie cotée du précédent : s'il rend moins, le suivant ne peut pas
+    /// dépenser ce montant et toute la transaction échoue. La sortie minimale d'un saut
+    /// intermédiaire est donc sa sortie cotée ; la tolérance de slippage ne s'applique qu'au
+    /// dernier saut, qui garde le profit.
+    /// Échoue si les index du cycle ne désignent plus les pools sur lesquels l'opportunité a été cotée.
+    pub fn from_opportunity(
+        graph: &MarketGraph,
+        opportunity: &Opportunity,
+        graph_version: u64,
+        slot: u64,
+        slippage_bps: u64,
+    ) -> Result<Self> {
+        Self::quote(opportunity.cycle.to_arbitrage_path(graph)?, opportunity, graph_version, slot, slippage_bps)
+    }
+
+    /// Comme `from_opportunity`, mais sur les pools modifiés de `overlay` (ex : l'état des pools
+    /// après la transaction qu'un backrun doit suivre) plutôt que sur ceux du graphe.
+    pub fn from_opportunity_over(
+        graph: &MarketGraph,
+        overlay: &PoolOverlay,
+        opportunity: &Opportunity,
+        graph_version: u64,
+        slot: u64,
+        slippage_bps: u64,
+    ) -> Result<Self> {
+        let path = opportunity.cycle.to_arbitrage_path_over(graph, overlay)?;
+        Self::quote(path, opportunity, graph_version, slot, slippage_bps)
+    }
+
+    /// Cote les sauts du chemin d'une opportunité et copie l'état de leurs pools.
+    fn quote(
+        path: Vec<ArbitragePath<'_>>,
+        opportunity: &Opportunity,
+        graph_version: u64,
+        slot: u64,
+        slippage_bps: u64,
+    ) -> Result<Self> {
+        let pool_ids = path.iter().map(|step| step.pool.get_id());
+        if path.len() != opportunity.pool_ids.len() || !pool_ids.eq(opportunity.pool_ids.iter().copied()) {
+            return Err(anyhow!("Opportunity was quoted on another graph version: its pools have moved"));
+        }
+        let mut amount = opportunity.amount_in;
+        let mut legs = Vec::with_capacity(path.len());
+        for (i, step) in path.iter().enumerate() {
+            let expected_out = step.pool.get_quote(&step.input_mint, amount)?;
+            let min_out = if i + 1 == path.len() { apply_slippage(expected_out, slippage_bps) } else { expected_out };
+            legs.push(PathLeg {
+                pool_id: step.pool.get_id(),
+                dex: step.pool.dex(),
+                input_mint: step.input_mint,
+                output_mint: step.output_mint,
+                a_to_b: step.pool.get_mints().0 == step.input_mint,
+                pool: step.pool.clone(),
+                amount_in: amount,
+                expected_out,
+                min_out,
+            });
+            amount = expected_out;
+        }
+
+        let last = legs.last_mut().ok_or_else(|| anyhow!("Opportunity has an empty cycle"))?;
+        last.min_out = last.min_out.max(opportunity.amount_in);
+        let (expected_out, min_out) = (last.expected_out, last.min_out);
+        Ok(Self {
+            legs,
+            amount_in: opportunity.amount_in,
+            expected_out,
+            min_out,
+            expected_profit: opportunity.expected_profit,
+            graph_version,
+            slot,
+        })
+    }
+
+    /// Le profit brut attendu si chaque saut rend sa cotation, dans le token de départ.
+    pub fn expected_gross_profit(&self) -> i64 {
+        self.expected_out as i64 - self.amount_in as i64
+    }
+
+    /// Le token de départ (et d'arrivée) du cycle.
+    pub fn start_mint(&self) -> Option<Pubkey> {
+        self.legs.first().map(|leg| leg.input_mint)
+    }
+
+    pub fn pool_ids(&self) -> Vec<Pubkey> {
+        self.legs.iter().map(|leg| leg.pool_id).collect()
+    }
+
+    /// Le chemin attendu par l'optimiseur, sur les états de pools figés de l'opportunité.
+    pub fn arbitrage_path(&self) -> Vec<ArbitragePath<'_>> {
+        self.legs
+            .iter()
+            .map(|leg| ArbitragePath { pool: &leg.pool, input_mint: leg.input_mint, output_mint: leg.output_mint })
+            .collect()
+    }
+}
+
+/// `amount` moins la tolérance de slippage.
+fn apply_slippage(amount: u64, slippage_bps: u64) -> u64 {
+    (amount as u128 * (BPS_DENOMINATOR - slippage_bps.min(BPS_DENOMINATOR)) as u128 / BPS_DENOMINATOR as u128) as u64
+}
@@ -0,0 +1,140 @@
+// src/strategies/runner.rs
+
+// Le runner de stratégies : il s'abonne aux versions du graphe publiées dans l'`AppState`,
+// fait tourner chaque stratégie activée sur chaque mise à jour, et envoie toutes les
+// opportunités trouvées dans une file commune, consommée par l'exécution.
+// Les opportunités y sont "détachées" du graphe (`OwnedOpportunity`) : l'instantané sur
+// lequel elles ont été calculées peut être remplacé avant qu'elles ne soient exécutées.
+
+use super::bounded_cycles::MAX_HOPS;
+use super::cycle_index::BoundedCyclesStrategy;
+use super::opportunity::{OwnedOpportunity, DEFAULT_SLIPPAGE_BPS};
+use super::sizing::DEFAULT_REFERENCE_USD;
+use super::spatial_arb::SpatialStrategy;
+use super::spfa_arb::SpfaStrategy;
+use super::Strategy;
+use crate::decoders::PoolOperations;
+use crate::execution::costs::CostModel;
+use crate::state::{AppState, GraphUpdate, MarketGraph};
+use crate::token_registry::TokenRegistry;
+use anyhow::{anyhow, Result};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::mpsc;
+
+/// Les stratégies activées par défaut (variable `STRATEGIES`).
+pub const DEFAULT_STRATEGIES: &[&str] = &["bounded_cycles", "spatial"];
+
+/// Une opportunité dans la file commune, avec son origine.
+#[derive(Debug, Clone)]
+pub struct QueuedOpportunity {
+    /// Le nom de la stratégie qui l'a trouvée.
+    pub strategy: &'static str,
+    pub opportunity: OwnedOpportunity,
+}
+
+/// Construit les stratégies à partir de leurs noms (`spfa`, `bounded_cycles`, `spatial`).
+/// Toutes dimensionnent leurs opportunités pour un profit net des coûts `costs`.
+pub fn build_strategies(
+    names: &[String],
+    graph: &MarketGraph,
+    base_mints: &[Pubkey],
+    max_amount: u64,
+    costs: &CostModel,
+) -> Result<Vec<Box<dyn Strategy>>> {
+    names
+        .iter()
+        .map(|name| -> Result<Box<dyn Strategy>> {
+            match name.trim() {
+                "spfa" => Ok(Box::new(
+                    SpfaStrategy::new(base_mints, max_amount)
+                        .with_reference_size(TokenRegistry::with_known_tokens(), DEFAULT_REFERENCE_USD)
+                        .with_cost_model(costs.clone()),
+                )),
+                "bounded_cycles" => Ok(Box::new(
+                    BoundedCyclesStrategy::new(graph, base_mints, MAX_HOPS, max_amount).with_cost_model(costs.clone()),
+                )),
+                "spatial" => Ok(Box::new(SpatialStrategy::new(base_mints, max_amount).with_cost_model(costs.clone()))),
+                other => Err(anyhow!("Unknown strategy '{}'", other)),
+            }
+        })
+        .collect()
+}
+
+pub struct StrategyRunner {
+    strategies: Vec<Box<dyn Strategy>>,
+    last_seen_version: u64,
+    slippage_bps: u64,
+}
+
+impl StrategyRunner {
+    pub fn new(strategies: Vec<Box<dyn Strategy>>) -> Self {
+        Self { strategies, last_seen_version: 0, slippage_bps: DEFAULT_SLIPPAGE_BPS }
+    }
+
+    /// La tolérance de slippage appliquée aux sorties minimales des opportunités, en points de base.
+    pub fn with_slippage_bps(mut self, slippage_bps: u64) -> Self {
+        self.slippage_bps = slippage_bps;
+        self
+    }
+
+    /// Les noms des stratégies pilotées.
+    pub fn strategy_names(&self) -> Vec<&'static str> {
+        self.strategies.iter().map(|strategy| strategy.name()).collect()
+    }
+
+    /// Fait tourner toutes les stratégies sur une version du graphe.
+    /// Si des versions ont été manquées, `changed_pools` est incomplet : les stratégies
+    /// reçoivent alors la liste de tous les pools, pour tout réévaluer.
+    pub fn process(&mut self, graph: &MarketGraph, update: &GraphUpdate) -> Vec<QueuedOpportunity> {
+        let full_update;
+        let update = if update.missed_versions_since(self.last_seen_version) {
+            full_update = GraphUpdate {
+                changed_pools: graph.pools.iter().map(|pool| pool.get_id()).collect(),
+                ..update.clone()
+            };
+            &full_update
+        } else {
+            update
+        };
+        self.last_seen_version = update.version;
+
+        let mut queued = Vec::new();
+        for strategy in &mut self.strategies {
+            let name = strategy.name();
+            for opportunity in strategy.on_update(graph, update) {
+                match OwnedOpportunity::from_opportunity(graph, &opportunity, update.version, update.slot, self.slippage_bps) {
+                    Ok(opportunity) => queued.push(QueuedOpportunity { strategy: name, opportunity }),
+                    Err(e) => tracing::debug!("[{}] Dropping opportunity on {:?}: {}", name, opportunity.cycle.pools, e),
+                }
+            }
+        }
+        queued
+    }
+
+    /// Boucle principale : traite chaque nouvelle version publiée, jusqu'à ce que
+    /// l'`AppState` ou la file d'opportunités soit fermé.
+    /// Les stratégies sont du calcul pur : elles tournent via `block_in_place`,
+    /// ce qui exige un runtime Tokio multi-thread.
+    pub async fn run(mut self, state: AppState, queue: mpsc::Sender<QueuedOpportunity>) {
+        let mut updates = state.subscribe();
+        loop {
+            // `publish` stocke le graphe sous le verrou d'écriture du canal : chargé pendant
+            // l'emprunt, il est exactement la version décrite par `update`.
+            let (update, graph) = {
+                let update = updates.borrow_and_update();
+                (update.clone(), state.graph.load_full())
+            };
+            if update.version > self.last_seen_version {
+                let queued = tokio::task::block_in_place(|| self.process(&graph, &update));
+                for opportunity in queued {
+                    if queue.send(opportunity).await.is_err() {
+                        return;
+                    }
+                }
+            }
+            if updates.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+}
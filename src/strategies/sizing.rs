@@ -0,0 +1,140 @@
+// src/strategies/sizing.rs
+
+// La taille à laquelle on évalue les arêtes du graphe. Un pool peu profond offre un
+// excellent taux marginal, mais s'effondre dès qu'on y passe un montant réaliste :
+// évaluer chaque arête à une taille de référence (même valeur en USD pour tous les
+// tokens) évite que la recherche de cycles ne privilégie ces pools.
+
+use super::bounded_cycles::PROBE_AMOUNT;
+use super::{Cycle, Opportunity};
+use crate::decoders::PoolOperations;
+use crate::execution::costs::{CostModel, LAMPORTS_PER_SOL, SOL_MINT};
+use crate::execution::optimizer;
+use crate::state::{MarketGraph, PoolOverlay};
+use crate::token_registry::TokenRegistry;
+use std::collections::VecDeque;
+
+/// Valeur (en USD) de la taille de référence par défaut.
+pub const DEFAULT_REFERENCE_USD: f64 = 1_000.0;
+
+/// Fraction de la taille de référence utilisée pour convertir un montant à travers un pool :
+/// on veut le prix, pas l'impact de la conversion elle-même.
+const CONVERSION_PROBE_DIVISOR: u64 = 1_000;
+
+/// Le montant d'entrée auquel le poids d'une arête est évalué.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EdgeSizing {
+    /// Le même montant (en unités de base) pour tous les tokens.
+    Fixed(u64),
+    /// Un montant par token (indexé comme `MarketGraph::tokens`). Les arêtes qui partent
+    /// d'un token sans taille sont évaluées au montant de repli.
+    PerToken { sizes: Vec<Option<u64>>, fallback: u64 },
+}
+
+impl EdgeSizing {
+    /// Le montant d'entrée pour une arête qui part du token `token_idx`.
+    pub fn amount_for(&self, token_idx: usize) -> u64 {
+        match self {
+            EdgeSizing::Fixed(amount) => *amount,
+            EdgeSizing::PerToken { sizes, fallback } => sizes.get(token_idx).copied().flatten().unwrap_or(*fallback),
+        }
+    }
+}
+
+/// La taille de référence de chaque token : le montant qui vaut `usd_value` dollars.
+/// Les tokens dont le prix est connu dans l'annuaire servent de point de départ ; les autres
+/// héritent la taille d'un voisin déjà dimensionné, convertie au prix du pool qui les relie
+/// (parcours en largeur : le chemin de conversion le plus court l'emporte).
+pub fn reference_sizes(graph: &MarketGraph, registry: &TokenRegistry, usd_value: f64) -> Vec<Option<u64>> {
+    let sizes = graph.tokens.iter().map(|mint| registry.amount_for_usd(mint, usd_value)).collect();
+    propagate_sizes(graph, sizes)
+}
+
+/// La quantité de chaque token (en unités de base) qui vaut 1 SOL, convertie à travers les pools.
+/// `None` pour les tokens qui ne sont pas reliés au SOL.
+pub fn sol_equivalents(graph: &MarketGraph) -> Vec<Option<u64>> {
+    let sizes = graph.tokens.iter().map(|mint| (*mint == SOL_MINT).then_some(LAMPORTS_PER_SOL)).collect();
+    propagate_sizes(graph, sizes)
+}
+
+/// Étend des tailles connues aux tokens voisins, au prix des pools qui les relient.
+fn propagate_sizes(graph: &MarketGraph, mut sizes: Vec<Option<u64>>) -> Vec<Option<u64>> {
+    let mut queue: VecDeque<usize> = (0..sizes.len()).filter(|&idx| sizes[idx].is_some()).collect();
+
+    while let Some(u) = queue.pop_front() {
+        let Some(size) = sizes[u] else { continue };
+        let probe = (size / CONVERSION_PROBE_DIVISOR).max(1);
+        for edge in &graph.nodes[u] {
+            let v = edge.destination;
+            if sizes[v].is_some() {
+                continue;
+            }
+            let Ok(amount_out) = graph.edge_pool(edge).get_quote(&graph.tokens[u], probe) else { continue };
+            let converted = amount_out as u128 * size as u128 / probe as u128;
+            if converted > 0 && converted <= u64::MAX as u128 {
+                sizes[v] = Some(converted as u64);
+                queue.push_back(v);
+            }
+        }
+    }
+    sizes
+}
+
+/// Dimensionne des cycles sur une version du graphe, pour un profit net des coûts d'exécution.
+/// Les conversions SOL -> token de départ ne sont calculées qu'une fois, et seulement si
+/// le modèle de coûts n'est pas gratuit.
+pub struct CycleSizer<'g> {
+    graph: &'g MarketGraph,
+    overlay: Option<&'g PoolOverlay>,
+    max_amount: u64,
+    costs: &'g CostModel,
+    sol_equivalents: Vec<Option<u64>>,
+}
+
+impl<'g> CycleSizer<'g> {
+    pub fn new(graph: &'g MarketGraph, max_amount: u64, costs: &'g CostModel) -> Self {
+        let sol_equivalents = if costs.is_free() { Vec::new() } else { sol_equivalents(graph) };
+        Self { graph, overlay: None, max_amount, costs, sol_equivalents }
+    }
+
+    /// Cote les cycles sur les pools modifiés de `overlay` plutôt que sur ceux du graphe.
+    /// Les conversions en SOL restent celles du graphe : elles ne servent qu'à chiffrer les coûts.
+    pub fn with_overlay(mut self, overlay: &'g PoolOverlay) -> Self {
+        self.overlay = Some(overlay);
+        self
+    }
+
+    /// L'opportunité au montant optimal, si le cycle est rentable une fois les coûts déduits.
+    /// Un cycle dont le token de départ n'a pas de prix en SOL est écarté : ses coûts sont inconnus.
+    pub fn size(&self, cycle: Cycle) -> Option<Opportunity> {
+        let path = match self.overlay {
+            Some(overlay) => cycle.to_arbitrage_path_over(self.graph, overlay).ok()?,
+            None => cycle.to_arbitrage_path(self.graph).ok()?,
+        };
+        // Filtre rapide au taux marginal avant de lancer l'optimiseur.
+        if optimizer::simulate_path_profit(PROBE_AMOUNT, &path).ok()? <= 0 {
+            return None;
+        }
+        let token_per_sol = self.sol_equivalents.get(*cycle.tokens.first()?).copied().flatten();
+        let costs = self.costs.path_costs(&path, token_per_sol)?;
+        let (amount_in, expected_profit) = optimizer::find_optimal_amount(&path, self.max_amount, &costs).ok()?;
+        let pool_ids = path.iter().map(|step| step.pool.get_id()).collect();
+        (amount_in > 0 && expected_profit > 0).then_some(Opportunity { cycle, pool_ids, amount_in, expected_profit })
+    }
+}
+
+/// Second passage : chaque cycle est dimensionné par l'optimiseur, puis re-coté à cette taille.
+/// Les cycles qui ne sont plus rentables une fois dimensionnés (coûts déduits) sont écartés ;
+/// les autres sont classés par profit net attendu, du plus grand au plus petit.
+pub fn rescore_at_optimal_size(
+    graph: &MarketGraph,
+    cycles: Vec<Cycle>,
+    max_amount: u64,
+    costs: &CostModel,
+) -> Vec<Opportunity> {
+    let sizer = CycleSizer::new(graph, max_amount, costs);
+    let mut opportunities: Vec<Opportunity> = cycles.into_iter().filter_map(|cycle| sizer.size(cycle)).collect();
+
+    opportunities.sort_by_key(|opportunity| std::cmp::Reverse(opportunity.expected_profit));
+    opportunities
+}
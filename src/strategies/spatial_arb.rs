@@ -0,0 +1,131 @@
+// src/strategies/spatial_arb.rs
+
+// Arbitrage "spatial" : une même paire de tokens cotée par plusieurs pools,
+// sur des DEX différents (Raydium AMM, CLMM, Whirlpool) ou sur le même DEX
+// (deux pools AMM d'une paire, deux Whirlpools à frais différents...).
+// On achète sur le pool le moins cher et on revend sur le plus cher, en deux swaps.
+
+use super::sizing::CycleSizer;
+use super::{Cycle, Opportunity, Strategy};
+use crate::decoders::PoolOperations;
+use crate::execution::costs::CostModel;
+use crate::state::{GraphUpdate, MarketGraph};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::{BTreeMap, HashSet};
+
+/// Regroupe les pools par paire de tokens (index triés) ; seules les paires cotées
+/// par au moins deux pools sont retournées.
+pub fn pools_by_pair(graph: &MarketGraph) -> BTreeMap<(usize, usize), Vec<usize>> {
+    let mut pairs: BTreeMap<(usize, usize), Vec<usize>> = BTreeMap::new();
+    for (u, edges) in graph.nodes.iter().enumerate() {
+        for edge in edges {
+            // Chaque pool apparaît dans les deux sens : on ne le compte qu'une fois.
+            if u < edge.destination {
+                pairs.entry((u, edge.destination)).or_default().push(edge.pool_index);
+            }
+        }
+    }
+    pairs.retain(|_, pools| pools.len() >= 2);
+    pairs
+}
+
+/// Les pools qui cotent la paire `(token_a, token_b)`, dans l'ordre des arêtes.
+fn pools_on_pair(graph: &MarketGraph, (token_a, token_b): (usize, usize)) -> Vec<usize> {
+    graph.nodes[token_a].iter().filter(|edge| edge.destination == token_b).map(|edge| edge.pool_index).collect()
+}
+
+/// Cherche les allers-retours rentables entre deux pools d'une même paire.
+/// Le token de départ (et de profit) est toujours un token de base : les paires qui
+/// n'en contiennent pas sont ignorées. Chaque opportunité est dimensionnée par l'optimiseur,
+/// pour un profit net des coûts d'exécution.
+pub fn find_spatial_opportunities(
+    graph: &MarketGraph,
+    base_mints: &[Pubkey],
+    max_amount: u64,
+    costs: &CostModel,
+) -> Vec<Opportunity> {
+    size_pairs(graph, base_mints, pools_by_pair(graph), max_amount, costs, |_| true)
+}
+
+/// Dimensionne les allers-retours des paires données dont le cycle est retenu par `keep`.
+fn size_pairs(
+    graph: &MarketGraph,
+    base_mints: &[Pubkey],
+    pairs: BTreeMap<(usize, usize), Vec<usize>>,
+    max_amount: u64,
+    costs: &CostModel,
+    keep: impl Fn(&Cycle) -> bool,
+) -> Vec<Opportunity> {
+    let base_indices: Vec<usize> = base_mints.iter().filter_map(|mint| graph.token_map.get(mint).copied()).collect();
+    let sizer = CycleSizer::new(graph, max_amount, costs);
+    let mut opportunities = Vec::new();
+
+    for ((token_a, token_b), pools) in pairs {
+        for (start, other) in [(token_a, token_b), (token_b, token_a)] {
+            if !base_indices.contains(&start) {
+                continue;
+            }
+            for &buy_pool in &pools {
+                for &sell_pool in &pools {
+                    if buy_pool == sell_pool {
+                        continue;
+                    }
+                    let cycle = Cycle { tokens: vec![start, other, start], pools: vec![buy_pool, sell_pool] };
+                    if !keep(&cycle) {
+                        continue;
+                    }
+                    if let Some(opportunity) = sizer.size(cycle) {
+                        opportunities.push(opportunity);
+                    }
+                }
+            }
+        }
+    }
+
+    opportunities.sort_by_key(|opportunity| std::cmp::Reverse(opportunity.expected_profit));
+    opportunities
+}
+
+/// L'arbitrage spatial, limité aux allers-retours qui empruntent un pool modifié.
+pub struct SpatialStrategy {
+    base_mints: Vec<Pubkey>,
+    max_amount: u64,
+    costs: CostModel,
+}
+
+impl SpatialStrategy {
+    pub fn new(base_mints: &[Pubkey], max_amount: u64) -> Self {
+        Self { base_mints: base_mints.to_vec(), max_amount, costs: CostModel::free() }
+    }
+
+    /// Les coûts d'exécution déduits du profit (gratuits par défaut).
+    pub fn with_cost_model(mut self, costs: CostModel) -> Self {
+        self.costs = costs;
+        self
+    }
+}
+
+impl Strategy for SpatialStrategy {
+    fn name(&self) -> &'static str {
+        "spatial"
+    }
+
+    fn on_update(&mut self, graph: &MarketGraph, update: &GraphUpdate) -> Vec<Opportunity> {
+        let changed: HashSet<usize> =
+            update.changed_pools.iter().filter_map(|pool_id| graph.pool_map.get(pool_id).copied()).collect();
+        // Seules les paires d'un pool modifié sont dimensionnées, pas tout le graphe.
+        let mut pairs = BTreeMap::new();
+        for &pool_index in &changed {
+            let (mint_a, mint_b) = graph.pools[pool_index].get_mints();
+            let (Some(&token_a), Some(&token_b)) = (graph.token_map.get(&mint_a), graph.token_map.get(&mint_b)) else {
+                continue;
+            };
+            let pair = (token_a.min(token_b), token_a.max(token_b));
+            pairs.entry(pair).or_insert_with(|| pools_on_pair(graph, pair));
+        }
+        pairs.retain(|_, pools| pools.len() >= 2);
+        size_pairs(graph, &self.base_mints, pairs, self.max_amount, &self.costs, |cycle| {
+            cycle.pools.iter().any(|pool_index| changed.contains(pool_index))
+        })
+    }
+}
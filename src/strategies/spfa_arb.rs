@@ -1,70 +1,275 @@
 // src/strategies/spfa_arb.rs
 
+use super::bounded_cycles::canonical_key;
+use super::sizing::{self, EdgeSizing};
+use super::{Cycle, Opportunity, Strategy};
 use crate::decoders::PoolOperations;
-use crate::state::MarketGraph;
-use fixed::types::I80F48;
+use crate::execution::costs::CostModel;
+use crate::state::{Edge, GraphUpdate, MarketGraph};
+use crate::token_registry::TokenRegistry;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::collections::VecDeque;
+
+/// Montant de référence utilisé par défaut pour évaluer le taux de chaque arête.
+pub const TEST_SWAP_AMOUNT: u64 = 1_000_000_000; // 1 SOL
+
+/// Tolérance sur les relaxations : évite de boucler sur du bruit d'arrondi flottant.
+const EPSILON: f64 = 1e-12;
 
 /// Exécute l'algorithme SPFA pour trouver des cycles de poids négatif (opportunités d'arbitrage).
-pub fn find_negative_cycle(graph: &MarketGraph, start_node_idx: usize) -> Option<Vec<usize>> {
+///
+/// Le poids d'une arête est `ln(entrée) - ln(sortie)` : un cycle de poids total négatif
+/// rend plus de tokens qu'il n'en consomme. Un cycle est détecté dès qu'un plus court
+/// chemin compte au moins N arêtes (N = nombre de tokens), ce qui est impossible
+/// sans passer deux fois par le même token.
+/// Le cycle retourné commence au token de départ s'il en fait partie.
+/// Les arêtes sont évaluées à `TEST_SWAP_AMOUNT`, quel que soit le token.
+pub fn find_negative_cycle(graph: &MarketGraph, start_node_idx: usize) -> Option<Cycle> {
+    find_negative_cycle_sized(graph, start_node_idx, &EdgeSizing::Fixed(TEST_SWAP_AMOUNT))
+}
+
+/// Comme `find_negative_cycle`, mais chaque arête est évaluée au montant donné par `sizing`
+/// pour son token d'entrée (ex : une taille de référence en USD, voir `sizing::reference_sizes`).
+pub fn find_negative_cycle_sized(graph: &MarketGraph, start_node_idx: usize, sizing: &EdgeSizing) -> Option<Cycle> {
+    if start_node_idx >= graph.nodes.len() { return None; }
+    spfa(graph, start_node_idx, &compute_edge_weights(graph, sizing))
+}
+
+/// Lance SPFA depuis chaque token de base, en parallèle sur `workers` threads, contre le même
+/// instantané du graphe (typiquement l'`Arc<MarketGraph>` chargé depuis l'`AppState`).
+/// Les poids des arêtes ne sont calculés qu'une fois et partagés entre les threads.
+/// Les cycles sont retournés dans l'ordre des tokens de base ; un même cycle trouvé
+/// depuis plusieurs départs n'apparaît qu'une fois.
+pub fn find_negative_cycles(graph: &MarketGraph, base_mints: &[Pubkey], sizing: &EdgeSizing, workers: usize) -> Vec<Cycle> {
+    let mut starts: Vec<usize> = Vec::new();
+    for mint in base_mints {
+        if let Some(&start) = graph.token_map.get(mint) && !starts.contains(&start) {
+            starts.push(start);
+        }
+    }
+    if starts.is_empty() {
+        return Vec::new();
+    }
+
+    let weights = compute_edge_weights(graph, sizing);
+    let next_start = AtomicUsize::new(0);
+    let mut found: Vec<(usize, Cycle)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers.clamp(1, starts.len()))
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut local = Vec::new();
+                    loop {
+                        let i = next_start.fetch_add(1, Ordering::Relaxed);
+                        let Some(&start) = starts.get(i) else { break };
+                        if let Some(cycle) = spfa(graph, start, &weights) {
+                            local.push((i, cycle));
+                        }
+                    }
+                    local
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().expect("SPFA worker panicked")).collect()
+    });
+
+    found.sort_by_key(|(i, _)| *i);
+    let mut seen = HashSet::new();
+    found.into_iter().map(|(_, cycle)| cycle).filter(|cycle| seen.insert(canonical_key(cycle))).collect()
+}
+
+/// Le nombre de threads de recherche par défaut : un par cœur disponible.
+pub fn default_search_workers() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+/// SPFA depuis `start_node_idx`, sur des poids déjà calculés.
+fn spfa(graph: &MarketGraph, start_node_idx: usize, weights: &[Vec<Option<f64>>]) -> Option<Cycle> {
     let num_nodes = graph.nodes.len();
-    if num_nodes == 0 { return None; }
 
-    let mut dist: Vec<I80F48> = vec![I80F48::MAX; num_nodes];
-    let mut predecessor: Vec<Option<usize>> = vec![None; num_nodes];
-    let mut in_queue_count: Vec<usize> = vec![0; num_nodes];
-    let mut queue: std::collections::VecDeque<usize> = std::collections::VecDeque::new();
+    let mut dist: Vec<f64> = vec![f64::INFINITY; num_nodes];
+    // Le prédécesseur de chaque token sur le plus court chemin : (token, pool).
+    let mut predecessor: Vec<Option<(usize, usize)>> = vec![None; num_nodes];
+    // Le nombre d'arêtes du plus court chemin courant.
+    let mut path_len: Vec<usize> = vec![0; num_nodes];
+    let mut in_queue: Vec<bool> = vec![false; num_nodes];
+    let mut queue: VecDeque<usize> = VecDeque::new();
 
-    dist[start_node_idx] = I80F48::ZERO;
+    dist[start_node_idx] = 0.0;
     queue.push_back(start_node_idx);
-    in_queue_count[start_node_idx] = 1;
-
-    const TEST_SWAP_AMOUNT: u64 = 1_000_000_000; // 1 SOL
+    in_queue[start_node_idx] = true;
 
     while let Some(u) = queue.pop_front() {
-        // --- LA LIGNE FINALE ET CORRECTE (DICTÉE PAR LE COMPILATEUR) ---
-        let u_mint = graph.token_map.iter().find(|&(_, &v)| v == u).unwrap().0;
+        in_queue[u] = false;
 
-        for edge in &graph.nodes[u] {
+        for (edge, weight) in graph.nodes[u].iter().zip(&weights[u]) {
+            let Some(weight) = *weight else { continue };
             let v = edge.destination;
 
-            let weight = match &edge.pool {
-                crate::decoders::Pool::RaydiumAmm(pool) => {
-                    if let Ok(amount_out) = pool.get_quote(u_mint, TEST_SWAP_AMOUNT) {
-                        if amount_out > 0 {
-                            let rate = I80F48::from_num(amount_out) / I80F48::from_num(TEST_SWAP_AMOUNT);
-                            let rate_f64 = rate.to_num::<f64>();
-                            I80F48::from_num(-rate_f64.ln())
-                        } else { I80F48::MAX }
-                    } else { I80F48::MAX }
-                },
-                _ => I80F48::MAX,
-            };
-
-            if dist[u].checked_add(weight).unwrap_or(I80F48::MAX) < dist[v] {
+            if dist[u] + weight < dist[v] - EPSILON {
                 dist[v] = dist[u] + weight;
-                predecessor[v] = Some(u);
+                predecessor[v] = Some((u, edge.pool_index));
+                path_len[v] = path_len[u] + 1;
 
-                if !queue.contains(&v) {
-                    queue.push_back(v);
-                    in_queue_count[v] += 1;
-
-                    if in_queue_count[v] >= num_nodes {
-                        let mut path = vec![v];
-                        let mut current = predecessor[v].unwrap();
-                        while !path.contains(&current) {
-                            path.push(current);
-                            current = predecessor[current].unwrap();
-                        }
-                        path.push(current);
-                        path.reverse();
-                        let cycle_start_index = path.iter().position(|&x| x == current).unwrap_or(0);
-                        let cycle = path[cycle_start_index..].to_vec();
+                if path_len[v] >= num_nodes {
+                    // Si la chaîne de prédécesseurs n'est pas encore refermée, on continue :
+                    // le cycle finira par y apparaître aux prochaines relaxations.
+                    if let Some(cycle) = extract_cycle(graph, &predecessor, weights, v, start_node_idx) {
                         return Some(cycle);
                     }
                 }
+
+                if !in_queue[v] {
+                    queue.push_back(v);
+                    in_queue[v] = true;
+                }
             }
         }
     }
 
     None
-}
\ No newline at end of file
+}
+
+/// Calcule une fois pour toutes le poids de chaque arête (`None` si l'arête est inutilisable).
+/// `weights[u][k]` est le poids de `graph.nodes[u][k]`.
+fn compute_edge_weights(graph: &MarketGraph, sizing: &EdgeSizing) -> Vec<Vec<Option<f64>>> {
+    graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(u, edges)| edges.iter().map(|edge| edge_weight(graph, u, edge, sizing.amount_for(u))).collect())
+        .collect()
+}
+
+/// Le poids `-ln(sortie / entrée)` d'une arête pour `amount_in`, calculé directement depuis les montants entiers.
+/// La cotation passe par `PoolOperations` : tous les types de pools (AMM, CLMM, Whirlpool...) participent.
+/// Une arête que le pool ne sait pas coter (pas encore hydraté, liquidité insuffisante) est ignorée.
+fn edge_weight(graph: &MarketGraph, u: usize, edge: &Edge, amount_in: u64) -> Option<f64> {
+    let amount_out = graph.edge_pool(edge).get_quote(&graph.tokens[u], amount_in).ok()?;
+    if amount_out == 0 { return None; }
+    Some((amount_in as f64).ln() - (amount_out as f64).ln())
+}
+
+/// Reconstruit le cycle contenu dans la chaîne de prédécesseurs de `v`.
+/// On remonte d'abord N fois pour être sûr d'être *dans* le cycle, puis on en fait le tour.
+/// Retourne `None` si la chaîne est interrompue ou si le cycle n'est pas réellement négatif.
+fn extract_cycle(
+    graph: &MarketGraph,
+    predecessor: &[Option<(usize, usize)>],
+    weights: &[Vec<Option<f64>>],
+    v: usize,
+    start_node_idx: usize,
+) -> Option<Cycle> {
+    let num_nodes = predecessor.len();
+
+    let mut current = v;
+    for _ in 0..num_nodes {
+        current = predecessor[current]?.0;
+    }
+
+    // On fait le tour du cycle à l'envers, en suivant les prédécesseurs.
+    let cycle_start = current;
+    let mut tokens = vec![cycle_start];
+    let mut pools = Vec::new();
+    loop {
+        let (prev, pool_index) = predecessor[current]?;
+        tokens.push(prev);
+        pools.push(pool_index);
+        current = prev;
+        if current == cycle_start { break; }
+        if tokens.len() > num_nodes { return None; }
+    }
+    tokens.reverse();
+    pools.reverse();
+
+    // Vérification : la somme des poids doit être strictement négative.
+    let mut total_weight = 0.0;
+    for (i, &pool_index) in pools.iter().enumerate() {
+        let (u, v) = (tokens[i], tokens[i + 1]);
+        let k = graph.nodes[u].iter().position(|e| e.destination == v && e.pool_index == pool_index)?;
+        total_weight += weights[u][k]?;
+    }
+    if total_weight >= -EPSILON { return None; }
+
+    Some(rotate_to_start(Cycle { tokens, pools }, start_node_idx))
+}
+
+/// Fait tourner le cycle pour qu'il commence au token de départ, s'il en fait partie.
+fn rotate_to_start(cycle: Cycle, start_node_idx: usize) -> Cycle {
+    let Some(offset) = cycle.tokens[..cycle.hops()].iter().position(|&t| t == start_node_idx) else {
+        return cycle;
+    };
+    let hops = cycle.hops();
+    let mut tokens: Vec<usize> = (0..hops).map(|i| cycle.tokens[(offset + i) % hops]).collect();
+    tokens.push(tokens[0]);
+    let pools = (0..hops).map(|i| cycle.pools[(offset + i) % hops]).collect();
+    Cycle { tokens, pools }
+}
+
+/// SPFA lancé depuis chaque token de base (en parallèle) ; chaque cycle trouvé est dimensionné par l'optimiseur.
+/// SPFA n'est pas incrémental : le graphe entier est réévalué dès qu'un pool change.
+pub struct SpfaStrategy {
+    base_mints: Vec<Pubkey>,
+    max_amount: u64,
+    workers: usize,
+    /// Si défini, les arêtes sont évaluées à une taille de référence de cette valeur en USD.
+    reference_size: Option<(TokenRegistry, f64)>,
+    costs: CostModel,
+}
+
+impl SpfaStrategy {
+    pub fn new(base_mints: &[Pubkey], max_amount: u64) -> Self {
+        Self {
+            base_mints: base_mints.to_vec(),
+            max_amount,
+            workers: default_search_workers(),
+            reference_size: None,
+            costs: CostModel::free(),
+        }
+    }
+
+    /// Le nombre de threads de recherche.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
+    }
+
+    /// Évalue les arêtes à la taille qui vaut `usd_value` dollars dans chaque token
+    /// (recalculée à chaque mise à jour, les conversions dépendant des pools).
+    pub fn with_reference_size(mut self, registry: TokenRegistry, usd_value: f64) -> Self {
+        self.reference_size = Some((registry, usd_value));
+        self
+    }
+
+    /// Les coûts d'exécution déduits du profit (gratuits par défaut).
+    pub fn with_cost_model(mut self, costs: CostModel) -> Self {
+        self.costs = costs;
+        self
+    }
+
+    fn sizing(&self, graph: &MarketGraph) -> EdgeSizing {
+        match &self.reference_size {
+            Some((registry, usd_value)) => EdgeSizing::PerToken {
+                sizes: sizing::reference_sizes(graph, registry, *usd_value),
+                fallback: TEST_SWAP_AMOUNT,
+            },
+            None => EdgeSizing::Fixed(TEST_SWAP_AMOUNT),
+        }
+    }
+}
+
+impl Strategy for SpfaStrategy {
+    fn name(&self) -> &'static str {
+        "spfa"
+    }
+
+    fn on_update(&mut self, graph: &MarketGraph, update: &GraphUpdate) -> Vec<Opportunity> {
+        if update.changed_pools.is_empty() {
+            return Vec::new();
+        }
+        let cycles = find_negative_cycles(graph, &self.base_mints, &self.sizing(graph), self.workers);
+        sizing::rescore_at_optimal_size(graph, cycles, self.max_amount, &self.costs)
+    }
+}
@@ -0,0 +1,94 @@
+// src/token_registry.rs
+
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Les tokens que nous connaissons d'office (mint, symbole, décimales, prix USD).
+/// Seuls les stablecoins ont un prix fixe ; les autres se déduisent du graphe ou d'une source externe.
+const KNOWN_TOKENS: &[(&str, &str, u8, Option<f64>)] = &[
+    ("So11111111111111111111111111111111111111112", "SOL", 9, None),
+    ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "USDC", 6, Some(1.0)),
+    ("Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB", "USDT", 6, Some(1.0)),
+    ("4k3Dyjzvzp8eMZWUXbBCjEvwSkkk59S5iCNLY3QrkX6R", "RAY", 6, None),
+    ("mSoLzYCxHdYgdzU16g5QSh3i5K3z3KZK7ytfqcJm7So", "mSOL", 9, None),
+];
+
+/// Les métadonnées d'un token.
+#[derive(Debug, Clone)]
+pub struct TokenInfo {
+    pub mint: Pubkey,
+    pub symbol: String,
+    pub decimals: u8,
+    /// Le prix d'une unité entière du token, en USD, s'il est connu.
+    pub usd_price: Option<f64>,
+}
+
+/// L'annuaire des métadonnées de tokens (symbole, décimales).
+#[derive(Debug, Clone, Default)]
+pub struct TokenRegistry {
+    tokens: HashMap<Pubkey, TokenInfo>,
+}
+
+impl TokenRegistry {
+    /// Crée un annuaire pré-rempli avec les tokens majeurs.
+    pub fn with_known_tokens() -> Self {
+        let mut registry = Self::default();
+        for (mint, symbol, decimals, usd_price) in KNOWN_TOKENS {
+            registry.insert(TokenInfo {
+                mint: Pubkey::from_str(mint).unwrap(),
+                symbol: symbol.to_string(),
+                decimals: *decimals,
+                usd_price: *usd_price,
+            });
+        }
+        registry
+    }
+
+    /// Ajoute (ou remplace) un token dans l'annuaire.
+    pub fn insert(&mut self, info: TokenInfo) {
+        self.tokens.insert(info.mint, info);
+    }
+
+    pub fn get(&self, mint: &Pubkey) -> Option<&TokenInfo> {
+        self.tokens.get(mint)
+    }
+
+    /// Le symbole du token, ou un préfixe de sa Pubkey s'il est inconnu.
+    pub fn symbol(&self, mint: &Pubkey) -> String {
+        match self.tokens.get(mint) {
+            Some(info) => info.symbol.clone(),
+            None => mint.to_string()[..6].to_string(),
+        }
+    }
+
+    /// Le nombre de décimales du token, s'il est connu.
+    pub fn decimals(&self, mint: &Pubkey) -> Option<u8> {
+        self.tokens.get(mint).map(|info| info.decimals)
+    }
+
+    /// Met à jour le prix USD d'un token connu. Retourne `false` si le token est inconnu.
+    pub fn set_usd_price(&mut self, mint: &Pubkey, usd_price: f64) -> bool {
+        match self.tokens.get_mut(mint) {
+            Some(info) => {
+                info.usd_price = Some(usd_price);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Le prix USD du token, s'il est connu.
+    pub fn usd_price(&self, mint: &Pubkey) -> Option<f64> {
+        self.tokens.get(mint).and_then(|info| info.usd_price)
+    }
+
+    /// Le montant (en unités de base) du token qui vaut `usd_value` dollars,
+    /// si son prix et ses décimales sont connus.
+    pub fn amount_for_usd(&self, mint: &Pubkey, usd_value: f64) -> Option<u64> {
+        let info = self.tokens.get(mint)?;
+        let price = info.usd_price.filter(|price| *price > 0.0)?;
+        let amount = usd_value / price * 10f64.powi(info.decimals as i32);
+        (amount >= 1.0 && amount < u64::MAX as f64).then_some(amount as u64)
+    }
+}
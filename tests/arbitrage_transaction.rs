@@ -0,0 +1,140 @@
+// tests/arbitrage_transaction.rs
+
+mod common;
+
+use common::{amm_pool, clmm_whirlpool_opportunity, mints, spatial_opportunity};
+use mev_scalpel::decoders::{Dex, ORCA_WHIRLPOOL_PROGRAM_ID, RAYDIUM_CLMM_PROGRAM_ID};
+use mev_scalpel::decoders::swap_instructions::{decode_swaps, TransactionView};
+use mev_scalpel::execution::bundle_builder::{
+    arbitrage_instructions, associated_token_address, build_arbitrage_transaction, profit_guard,
+    ASSOCIATED_TOKEN_PROGRAM_ID,
+};
+use mev_scalpel::execution::bundle_sender::JITO_TIP_ACCOUNTS;
+use mev_scalpel::execution::costs::{CostModel, SOL_MINT};
+use mev_scalpel::execution::lookup_tables::LookupTableManager;
+use mev_scalpel::execution::optimizer::simulate_path_profit;
+use mev_scalpel::strategies::opportunity::OwnedOpportunity;
+use solana_compute_budget_interface as compute_budget;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::VersionedMessage;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+fn dex_program(opportunity: &OwnedOpportunity, leg: usize) -> Pubkey {
+    match opportunity.legs[leg].dex {
+        Dex::RaydiumClmm => RAYDIUM_CLMM_PROGRAM_ID,
+        Dex::OrcaWhirlpool => ORCA_WHIRLPOOL_PROGRAM_ID,
+        dex => panic!("unexpected {}", dex),
+    }
+}
+
+#[test]
+fn sol_cycle_is_wrapped_swapped_and_unwrapped_in_one_transaction() {
+    let other = mints(1)[0];
+    let opportunity = clmm_whirlpool_opportunity(SOL_MINT, other);
+    let wallet = Keypair::new();
+    let costs = CostModel::default();
+    let tip_account = JITO_TIP_ACCOUNTS[3];
+
+    let tables = LookupTableManager::default();
+    let transaction =
+        build_arbitrage_transaction(&opportunity, &wallet, &costs, None, Some(&tip_account), &tables, Hash::new_unique()).unwrap();
+    assert!(matches!(transaction.message, VersionedMessage::V0(_)));
+    assert!(transaction.verify_with_results().into_iter().all(|ok| ok));
+    // Une seule signature : le pourboire ne passe pas par une transaction à part.
+    assert_eq!(transaction.signatures.len(), 1);
+
+    let instructions = arbitrage_instructions(&opportunity, &wallet.pubkey(), &costs, None, Some(&tip_account)).unwrap();
+    let programs: Vec<_> = instructions.iter().map(|ix| ix.program_id).collect();
+    let wsol_account = associated_token_address(&wallet.pubkey(), &SOL_MINT);
+    let system_program = solana_system_interface::program::id();
+    // Budget (limite, prix), ATA intermédiaire, ATA WSOL, wrap (transfert, sync), 2 swaps, unwrap, pourboire.
+    assert_eq!(programs.len(), 10);
+    assert_eq!(&programs[..2], &[compute_budget::id(), compute_budget::id()]);
+    assert_eq!(&programs[2..4], &[ASSOCIATED_TOKEN_PROGRAM_ID, ASSOCIATED_TOKEN_PROGRAM_ID]);
+    assert_eq!(instructions[2].accounts[3].pubkey, other);
+    assert_eq!(instructions[5].accounts[0].pubkey, wsol_account);
+    assert_eq!(programs[4], system_program);
+    assert_eq!(
+        &programs[5..],
+        &[spl_token::id(), dex_program(&opportunity, 0), dex_program(&opportunity, 1), spl_token::id(), system_program]
+    );
+
+    // Le pourboire est la part du profit brut du modèle de coûts, versée au compte de tip.
+    let tip = costs.tip_lamports(&opportunity.arbitrage_path(), opportunity.expected_gross_profit(), None).unwrap();
+    assert!(tip > 0);
+    let tip_instruction = instructions.last().unwrap();
+    assert_eq!((tip_instruction.accounts[0].pubkey, tip_instruction.accounts[1].pubkey), (wallet.pubkey(), tip_account));
+    assert_eq!(tip_instruction.data[4..], tip.to_le_bytes());
+
+    // Les swaps relus dans la transaction enchaînent les montants de l'opportunité.
+    let swaps = decode_swaps(&TransactionView::from_transaction(&transaction, None));
+    assert_eq!(swaps.len(), 2);
+    for (swap, leg) in swaps.iter().zip(&opportunity.legs) {
+        assert_eq!((swap.swap.pool_id, swap.swap.amount), (leg.pool_id, leg.amount_in));
+    }
+    assert_eq!(swaps[0].swap.other_amount_threshold, opportunity.legs[0].min_out);
+
+    // Le dernier saut doit rendre la mise, plus la signature, les frais de priorité et le pourboire.
+    let path_costs = costs.path_costs(&opportunity.arbitrage_path(), None).unwrap();
+    let break_even = opportunity.amount_in
+        + path_costs.fixed
+        + path_costs.tip(opportunity.expected_gross_profit());
+    let guard = profit_guard(&opportunity, &costs, None).unwrap();
+    assert_eq!(guard, opportunity.legs[1].min_out.max(break_even));
+    assert_eq!(swaps[1].swap.other_amount_threshold, guard);
+    assert!(guard > opportunity.amount_in + path_costs.fixed);
+}
+
+#[test]
+fn token_cycle_neither_wraps_nor_creates_its_start_account() {
+    let m = mints(2);
+    let opportunity = clmm_whirlpool_opportunity(m[0], m[1]);
+    let wallet = Pubkey::new_unique();
+    let instructions = arbitrage_instructions(&opportunity, &wallet, &CostModel::free(), None, None).unwrap();
+
+    // Sans prix de compute unit : la limite seule, l'ATA intermédiaire, puis les deux swaps.
+    assert_eq!(instructions.len(), 4);
+    assert_eq!(instructions[0].program_id, compute_budget::id());
+    assert_eq!(instructions[1].program_id, ASSOCIATED_TOKEN_PROGRAM_ID);
+    assert_eq!(instructions[1].accounts[1].pubkey, associated_token_address(&wallet, &m[1]));
+    assert_eq!((instructions[2].program_id, instructions[3].program_id), (dex_program(&opportunity, 0), dex_program(&opportunity, 1)));
+}
+
+#[test]
+fn opportunity_that_does_not_cover_its_costs_is_refused() {
+    let m = mints(2);
+    let opportunity = clmm_whirlpool_opportunity(m[0], m[1]);
+    let wallet = Pubkey::new_unique();
+
+    // Gratuit, la garde est la mise ; à 10^18 unités du token par SOL, les frais dépassent le profit.
+    let free = CostModel::free();
+    assert_eq!(profit_guard(&opportunity, &free, None).unwrap(), opportunity.legs[1].min_out.max(opportunity.amount_in));
+    let costs = CostModel::default();
+    assert!(arbitrage_instructions(&opportunity, &wallet, &costs, Some(1_000_000_000_000_000_000), None).is_err());
+    // Sans prix en SOL, les coûts ne peuvent pas être couverts.
+    assert!(arbitrage_instructions(&opportunity, &wallet, &costs, None, None).is_err());
+    // Un pourboire nul n'ajoute pas de transfert.
+    let untipped = arbitrage_instructions(&opportunity, &wallet, &free, None, Some(&JITO_TIP_ACCOUNTS[0])).unwrap();
+    assert_eq!(untipped.len(), 4);
+}
+
+#[test]
+fn thin_mispricing_keeps_its_profit_up_to_the_final_guard() {
+    let m = mints(2);
+    // Deux pools AMM à 0.8 % d'écart : moins que deux fois la tolérance de slippage plus les frais.
+    let depth = 1_000_000_000_000;
+    let owned = spatial_opportunity(vec![amm_pool(m[0], m[1], depth, depth), amm_pool(m[0], m[1], depth, depth * 1_008 / 1_000)], m[0]);
+
+    // Les sauts s'enchaînent sur les sorties cotées : le profit brut est celui du chemin.
+    let quoted = simulate_path_profit(owned.amount_in, &owned.arbitrage_path()).unwrap();
+    assert!(quoted > 0);
+    assert_eq!(owned.expected_gross_profit(), quoted);
+    assert_eq!(owned.legs[1].amount_in, owned.legs[0].expected_out);
+
+    // Seule la sortie finale porte la tolérance, et la garde ne dépasse jamais ce que le chemin rend.
+    let guard = profit_guard(&owned, &CostModel::free(), None).unwrap();
+    assert_eq!(guard, owned.amount_in.max(owned.legs[1].min_out));
+    assert!(guard <= owned.expected_out);
+}
@@ -0,0 +1,114 @@
+// tests/backrunner.rs
+
+mod common;
+
+use common::{amm_pool, graph_of, mints, whirlpool_pool};
+use mev_scalpel::decoders::{Pool, PoolOperations};
+use mev_scalpel::strategies::backrunner::{apply_observed_swaps, Backrunner, ObservedSwap};
+use mev_scalpel::execution::optimizer::simulate_path_profit;
+use mev_scalpel::strategies::Cycle;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+const DEPTH: u64 = 1_000_000_000_000;
+const MAX_AMOUNT: u64 = 100_000_000_000;
+
+#[test]
+fn replayed_swap_moves_only_the_copy() {
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH), whirlpool_pool(m[0], m[1], 1.0, DEPTH as u128)]);
+    let swaps = [
+        ObservedSwap { pool_id: graph.pools[0].get_id(), input_mint: m[0], amount_in: DEPTH / 10 },
+        ObservedSwap { pool_id: graph.pools[1].get_id(), input_mint: m[1], amount_in: DEPTH / 10 },
+    ];
+
+    let quote = |pool: &Pool| pool.get_quote(&m[0], 1_000_000).unwrap();
+    let before = (quote(&graph.pools[0]), quote(&graph.pools[1]));
+
+    let post_swap = apply_observed_swaps(&graph, &swaps).unwrap();
+    // Token 0 s'est déprécié sur l'AMM, et apprécié sur le Whirlpool.
+    assert!(quote(post_swap.pool(&graph, 0).unwrap()) < before.0);
+    assert!(quote(post_swap.pool(&graph, 1).unwrap()) > before.1);
+    // Le graphe d'origine n'est pas modifié.
+    assert_eq!((quote(&graph.pools[0]), quote(&graph.pools[1])), before);
+}
+
+#[test]
+fn only_the_touched_pools_are_copied() {
+    let m = mints(3);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[1], m[2], DEPTH, DEPTH),
+        amm_pool(m[2], m[0], DEPTH, DEPTH),
+    ]);
+    let swaps = [
+        ObservedSwap { pool_id: graph.pools[1].get_id(), input_mint: m[1], amount_in: DEPTH / 10 },
+        ObservedSwap { pool_id: graph.pools[1].get_id(), input_mint: m[2], amount_in: DEPTH / 20 },
+        ObservedSwap { pool_id: Pubkey::new_unique(), input_mint: m[1], amount_in: DEPTH / 10 },
+    ];
+    let post_swap = apply_observed_swaps(&graph, &swaps).unwrap();
+    assert_eq!(post_swap.len(), 1);
+    assert!(post_swap.contains(1));
+    // Les pools non touchés sont lus dans le graphe lui-même.
+    assert!(std::ptr::eq(post_swap.pool(&graph, 0).unwrap(), &graph.pools[0]));
+    assert!(!std::ptr::eq(post_swap.pool(&graph, 1).unwrap(), &graph.pools[1]));
+
+    // Les deux swaps successifs s'appliquent au même pool copié.
+    let (quote_before, quote_after) =
+        (graph.pools[1].get_quote(&m[1], 1_000_000).unwrap(), post_swap.pool(&graph, 1).unwrap().get_quote(&m[1], 1_000_000).unwrap());
+    assert!(quote_after < quote_before);
+
+    // Le chemin d'un cycle lit les pools modifiés.
+    let cycle = Cycle { tokens: vec![0, 1, 2, 0], pools: vec![0, 1, 2] };
+    let path = cycle.to_arbitrage_path_over(&graph, &post_swap).unwrap();
+    assert!(std::ptr::eq(path[1].pool, post_swap.pool(&graph, 1).unwrap()));
+    assert!(std::ptr::eq(path[0].pool, &graph.pools[0]));
+}
+
+#[test]
+fn large_swap_opens_a_backrun_through_the_touched_pool() {
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH), amm_pool(m[0], m[1], DEPTH, DEPTH)]);
+    let mut backrunner = Backrunner::new(&graph, &[m[0]], 3, MAX_AMOUNT);
+
+    // Aucun arbitrage tant que les deux pools sont alignés.
+    let small = ObservedSwap { pool_id: graph.pools[0].get_id(), input_mint: m[1], amount_in: 1_000 };
+    assert!(backrunner.find_backruns(&graph, 3, 120, None, &[small]).unwrap().is_empty());
+
+    // Quelqu'un vend beaucoup de token 1 sur le pool 0 : le token 1 y devient bon marché.
+    let signature = Signature::new_unique();
+    let large = ObservedSwap { pool_id: graph.pools[0].get_id(), input_mint: m[1], amount_in: DEPTH / 10 };
+    let candidates = backrunner.find_backruns(&graph, 3, 120, Some(signature), std::slice::from_ref(&large)).unwrap();
+
+    assert!(!candidates.is_empty());
+    let best = &candidates[0];
+    assert_eq!(best.target_signature, Some(signature));
+    assert_eq!(best.target_swaps, vec![large]);
+    let pool_ids: Vec<Pubkey> = graph.pools.iter().map(|pool| pool.get_id()).collect();
+    assert_eq!(best.opportunity.pool_ids(), pool_ids);
+    assert!(best.opportunity.expected_profit > 0);
+    assert_eq!((best.opportunity.graph_version, best.opportunity.slot), (3, 120));
+
+    // Les sauts portent l'état du pool APRÈS le swap observé, pas celui du graphe.
+    let post_swap = apply_observed_swaps(&graph, &best.target_swaps).unwrap();
+    let first = &best.opportunity.legs[0];
+    assert_eq!(bincode::serialize(&first.pool).unwrap(), bincode::serialize(post_swap.pool(&graph, 0).unwrap()).unwrap());
+    assert_eq!(first.expected_out, post_swap.pool(&graph, 0).unwrap().get_quote(&m[0], first.amount_in).unwrap());
+    let profit = simulate_path_profit(best.opportunity.amount_in, &best.opportunity.arbitrage_path()).unwrap();
+    assert_eq!(best.opportunity.expected_gross_profit(), profit);
+    assert!(profit > 0);
+
+    // Recoté sur le graphe d'avant le swap, le même cycle ne rapporte rien.
+    let cycle = Cycle { tokens: vec![0, 1, 0], pools: vec![0, 1] };
+    let before = simulate_path_profit(best.opportunity.amount_in, &cycle.to_arbitrage_path(&graph).unwrap()).unwrap();
+    assert!(before < 0);
+}
+
+#[test]
+fn swaps_on_unknown_pools_are_ignored() {
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH), amm_pool(m[0], m[1], DEPTH, DEPTH)]);
+    let mut backrunner = Backrunner::new(&graph, &[m[0]], 3, MAX_AMOUNT);
+    let unknown = ObservedSwap { pool_id: Pubkey::new_unique(), input_mint: m[1], amount_in: DEPTH / 10 };
+    assert!(backrunner.find_backruns(&graph, 3, 120, None, &[unknown]).unwrap().is_empty());
+}
@@ -0,0 +1,67 @@
+// tests/bounded_cycles.rs
+
+mod common;
+
+use common::{amm_pool, graph_of, mints};
+use mev_scalpel::strategies::bounded_cycles::{enumerate_cycles, find_profitable_cycles, PROBE_AMOUNT};
+
+const DEPTH: u64 = 1_000_000_000_000;
+
+#[test]
+fn triangle_yields_one_cycle_per_direction() {
+    let m = mints(3);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[1], m[2], DEPTH, DEPTH),
+        amm_pool(m[2], m[0], DEPTH, DEPTH),
+    ]);
+
+    let cycles = enumerate_cycles(&graph, &[m[0]], 4);
+    assert_eq!(cycles.len(), 2);
+    assert!(cycles.iter().all(|c| c.hops() == 3 && c.tokens[0] == graph.token_map[&m[0]]));
+}
+
+#[test]
+fn cycle_through_two_base_tokens_is_deduplicated() {
+    let m = mints(3);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[1], m[2], DEPTH, DEPTH),
+        amm_pool(m[2], m[0], DEPTH, DEPTH),
+    ]);
+
+    // Le triangle passe par les deux tokens de base : chaque sens ne doit apparaître qu'une fois.
+    let cycles = enumerate_cycles(&graph, &[m[0], m[1]], 4);
+    assert_eq!(cycles.len(), 2);
+}
+
+#[test]
+fn hop_limit_is_respected() {
+    let m = mints(5);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[1], m[2], DEPTH, DEPTH),
+        amm_pool(m[2], m[3], DEPTH, DEPTH),
+        amm_pool(m[3], m[4], DEPTH, DEPTH),
+        amm_pool(m[4], m[0], DEPTH, DEPTH),
+    ]);
+
+    assert!(enumerate_cycles(&graph, &[m[0]], 4).is_empty());
+    assert_eq!(enumerate_cycles(&graph, &[m[0]], 5).len(), 2);
+}
+
+#[test]
+fn profitable_cycles_are_ranked_by_return() {
+    let m = mints(3);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[0], m[1], DEPTH, DEPTH * 103 / 100),
+        amm_pool(m[1], m[2], DEPTH, DEPTH),
+        amm_pool(m[2], m[0], DEPTH, DEPTH * 110 / 100),
+    ]);
+
+    let ranked = find_profitable_cycles(&graph, &[m[0]], 4, PROBE_AMOUNT);
+    assert!(ranked.len() >= 2);
+    assert!(ranked.windows(2).all(|w| w[0].estimated_return >= w[1].estimated_return));
+    assert!(ranked.iter().all(|r| r.estimated_return > 0.0));
+}
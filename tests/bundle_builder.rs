@@ -0,0 +1,261 @@
+// tests/bundle_builder.rs
+
+mod common;
+
+use common::{clmm_pool, mints, whirlpool_pool};
+use mev_scalpel::decoders::clmm_math::TickLiquidity;
+use mev_scalpel::decoders::{orca_whirlpool, raydium_clmm, Pool, PoolOperations};
+use mev_scalpel::decoders::raydium_amm::{decode_openbook_market, OpenBookMarketKeys, AMM_AUTHORITY};
+use mev_scalpel::decoders::swap_instructions::{decode_swaps, SwapDirection, SwapInstruction, TransactionView};
+use mev_scalpel::decoders::{instruction_discriminator, Dex, RaydiumAmmPool, RAYDIUM_AMM_V4_PROGRAM_ID, RAYDIUM_CPMM_PROGRAM_ID};
+use mev_scalpel::execution::bundle_builder::{
+    raydium_amm_swap_base_in, raydium_clmm_swap_tick_arrays, whirlpool_oracle_address, RaydiumCpmmPoolKeys,
+    SwapInstructionBuilder, UserSwapAccounts,
+};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use std::str::FromStr;
+
+fn key(s: &str) -> Pubkey {
+    Pubkey::from_str(s).unwrap()
+}
+
+/// Le pool SOL-USDC de Raydium AMM v4 et son marché OpenBook.
+fn sol_usdc_pool() -> RaydiumAmmPool {
+    RaydiumAmmPool {
+        id: key("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2"),
+        mint_a: key("So11111111111111111111111111111111111111112"),
+        mint_b: key("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"),
+        base_vault: key("DQyrAcCrDXQ7NeoqGgDCZwBvWDcYmFCjSb9JtteuvPpz"),
+        quote_vault: key("HLmqeL62xR1QoZ1HKKbXRrdN1p3phKpxRMb2VVopvBBz"),
+        open_orders: key("HmiHHzq4Fym9e1D4qzLS6LDDM3tNsCTBPDWHTLZ763jY"),
+        target_orders: key("CZza3Ej4Mc58MnxWA385itCC9jCo3L1D7zc3LKy1bZMR"),
+        market_id: key("8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6"),
+        market_program: key("srmqPvymJeFKQ4zGQed1GFppgkRHL9kaELCbyksJtPX"),
+        market: Some(OpenBookMarketKeys {
+            bids: key("5jWUncPNBMZJ3sTHKmMLszypVkoRK6bfEQMQUHweeQnh"),
+            asks: key("EaXdHx7x3mdGA38j5RSmKYSXMzAFzzUXCLNBEDXDn1d5"),
+            event_queue: key("8CvwxZ9Db6XbLD46NZwwmVDZZRDy7eydFcAGkXKh9axa"),
+            base_vault: key("CKxTHwM9fPMRRvZmFnFoqKNd9pQR21c5Aq9bh5h9oghX"),
+            quote_vault: key("6A5NHCj1yF6urc9wZNe6Bcjj4LVszQNj5DwAWG97yzMu"),
+            vault_signer: key("CTz5UMLQm2SRWHzQnU62Pi4yJqbNGjgRBHqqp6oDHfF7"),
+        }),
+        ..Default::default()
+    }
+}
+
+fn user() -> UserSwapAccounts {
+    UserSwapAccounts { source: Pubkey::new_unique(), destination: Pubkey::new_unique(), owner: Pubkey::new_unique() }
+}
+
+/// Les swaps que le décodeur relit dans une transaction contenant `instructions`.
+fn decode_built(instructions: &[solana_sdk::instruction::Instruction], payer: &Pubkey) -> Vec<SwapInstruction> {
+    let message = Message::new(instructions, Some(payer));
+    let transaction = VersionedTransaction::from(Transaction::new_unsigned(message));
+    decode_swaps(&TransactionView::from_transaction(&transaction, None)).into_iter().map(|decoded| decoded.swap).collect()
+}
+
+#[test]
+fn swap_base_in_matches_the_program_layout() {
+    let pool = sol_usdc_pool();
+    let user = user();
+    // 1 SOL contre au moins 150 USDC.
+    let ix = raydium_amm_swap_base_in(&pool, &user, 1_000_000_000, 150_000_000, true).unwrap();
+
+    assert_eq!(ix.program_id, RAYDIUM_AMM_V4_PROGRAM_ID);
+    assert_eq!(ix.data, vec![
+        0x09,
+        0x00, 0xca, 0x9a, 0x3b, 0x00, 0x00, 0x00, 0x00,
+        0x80, 0xd1, 0xf0, 0x08, 0x00, 0x00, 0x00, 0x00,
+    ]);
+
+    let market = pool.market.unwrap();
+    // (compte, modifiable, signataire), dans l'ordre attendu par le programme.
+    let expected = vec![
+        (spl_token::id(), false, false),
+        (pool.id, true, false),
+        (AMM_AUTHORITY, false, false),
+        (pool.open_orders, true, false),
+        (pool.target_orders, true, false),
+        (pool.base_vault, true, false),
+        (pool.quote_vault, true, false),
+        (pool.market_program, false, false),
+        (pool.market_id, true, false),
+        (market.bids, true, false),
+        (market.asks, true, false),
+        (market.event_queue, true, false),
+        (market.base_vault, true, false),
+        (market.quote_vault, true, false),
+        (market.vault_signer, false, false),
+        (user.source, true, false),
+        (user.destination, true, false),
+        (user.owner, false, true),
+    ];
+    let actual: Vec<_> = ix.accounts.iter().map(|meta| (meta.pubkey, meta.is_writable, meta.is_signer)).collect();
+    assert_eq!(actual, expected);
+
+    // La forme à 17 comptes retire uniquement `target_orders`.
+    let compact = raydium_amm_swap_base_in(&pool, &user, 1_000_000_000, 150_000_000, false).unwrap();
+    let mut without_target_orders = expected.clone();
+    without_target_orders.remove(4);
+    let actual: Vec<_> = compact.accounts.iter().map(|meta| (meta.pubkey, meta.is_writable, meta.is_signer)).collect();
+    assert_eq!(actual, without_target_orders);
+    assert_eq!(compact.data, ix.data);
+}
+
+#[test]
+fn built_swaps_are_read_back_by_the_swap_decoder() {
+    let pool = sol_usdc_pool();
+    let user = user();
+    for include_target_orders in [true, false] {
+        let ix = raydium_amm_swap_base_in(&pool, &user, 42_000, 41_000, include_target_orders).unwrap();
+        let message = Message::new(&[ix], Some(&user.owner));
+        let transaction = VersionedTransaction::from(Transaction::new_unsigned(message));
+        let swaps = decode_swaps(&TransactionView::from_transaction(&transaction, None));
+
+        assert_eq!(swaps.len(), 1);
+        let swap = swaps[0].swap;
+        assert_eq!((swap.dex, swap.pool_id), (Dex::RaydiumAmmV4, pool.id));
+        assert_eq!(swap.direction, SwapDirection::UserSource { account: user.source, owner: user.owner });
+        assert_eq!((swap.amount, swap.other_amount_threshold, swap.exact_in), (42_000, 41_000, true));
+    }
+}
+
+#[test]
+fn openbook_market_account_is_decoded() {
+    let (market_id, market_program) = (Pubkey::new_unique(), Pubkey::new_unique());
+    // Le premier nonce qui donne une adresse hors courbe, comme à la création du marché.
+    let nonce = (0u64..)
+        .find(|nonce| Pubkey::create_program_address(&[market_id.as_ref(), &nonce.to_le_bytes()], &market_program).is_ok())
+        .unwrap();
+    let keys: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+
+    let mut data = vec![0u8; 388];
+    data[..5].copy_from_slice(b"serum");
+    data[45..53].copy_from_slice(&nonce.to_le_bytes());
+    for (offset, key) in [117, 165, 253, 285, 317].into_iter().zip(&keys) {
+        data[offset..offset + 32].copy_from_slice(key.as_ref());
+    }
+    data[381..].copy_from_slice(b"padding");
+
+    let market = decode_openbook_market(&market_id, &market_program, &data).unwrap();
+    assert_eq!(
+        [market.base_vault, market.quote_vault, market.event_queue, market.bids, market.asks],
+        keys.as_slice()
+    );
+    let vault_signer = Pubkey::create_program_address(&[market_id.as_ref(), &nonce.to_le_bytes()], &market_program);
+    assert_eq!(market.vault_signer, vault_signer.unwrap());
+
+    data[0] = b'x';
+    assert!(decode_openbook_market(&market_id, &market_program, &data).is_err());
+}
+
+#[test]
+fn pool_without_market_keys_cannot_be_swapped() {
+    let pool = RaydiumAmmPool { market: None, ..sol_usdc_pool() };
+    assert!(raydium_amm_swap_base_in(&pool, &user(), 1, 1, false).is_err());
+}
+
+#[test]
+fn every_pool_variant_builds_a_swap_the_decoder_reads_back() {
+    let m = mints(2);
+    let user = user();
+    let pools = [
+        Pool::RaydiumAmm(sol_usdc_pool()),
+        clmm_pool(m[0], m[1], 1.0, 1_000_000_000),
+        whirlpool_pool(m[0], m[1], 1.0, 1_000_000_000),
+    ];
+    for pool in &pools {
+        for a_to_b in [true, false] {
+            let built = pool.build_swap(a_to_b, 5_000, 4_900, &user).unwrap();
+            assert_eq!(built.instructions.len(), 1);
+            // Le pool fait partie des comptes requis, pas les comptes de l'utilisateur.
+            assert!(built.accounts.contains(&pool.get_id()));
+            assert!(!built.accounts.contains(&user.source) && !built.accounts.contains(&user.owner));
+
+            let swaps = decode_built(&built.instructions, &user.owner);
+            assert_eq!(swaps.len(), 1, "{}", pool.dex());
+            let swap = swaps[0];
+            assert_eq!((swap.dex, swap.pool_id), (pool.dex(), pool.get_id()));
+            assert_eq!((swap.amount, swap.other_amount_threshold, swap.exact_in), (5_000, 4_900, true));
+
+            let expected_direction = match pool {
+                Pool::RaydiumAmm(_) => SwapDirection::UserSource { account: user.source, owner: user.owner },
+                Pool::RaydiumClmm(clmm) => SwapDirection::InputVault(if a_to_b { clmm.vault_a } else { clmm.vault_b }),
+                Pool::OrcaWhirlpool(_) => SwapDirection::AToB(a_to_b),
+            };
+            assert_eq!(swap.direction, expected_direction);
+        }
+    }
+}
+
+#[test]
+fn cpmm_swap_base_input_is_built_but_not_decoded() {
+    let keys = RaydiumCpmmPoolKeys {
+        id: Pubkey::new_unique(),
+        amm_config: Pubkey::new_unique(),
+        mint_a: Pubkey::new_unique(),
+        mint_b: Pubkey::new_unique(),
+        vault_a: Pubkey::new_unique(),
+        vault_b: Pubkey::new_unique(),
+        observation_key: Pubkey::new_unique(),
+    };
+    let user = user();
+    let built = keys.build_swap(false, 7_000, 6_500, &user).unwrap();
+    let ix = &built.instructions[0];
+    assert_eq!(ix.program_id, RAYDIUM_CPMM_PROGRAM_ID);
+    // payer, authority, amm_config, pool_state, comptes utilisateur, vaults (entrée puis sortie), ..., mints d'entrée et de sortie.
+    assert_eq!((ix.accounts[3].pubkey, ix.accounts[6].pubkey, ix.accounts[7].pubkey), (keys.id, keys.vault_b, keys.vault_a));
+    assert_eq!((ix.accounts[10].pubkey, ix.accounts[11].pubkey), (keys.mint_b, keys.mint_a));
+    assert_eq!(ix.data[..8], instruction_discriminator("swap_base_input"));
+    assert_eq!((&ix.data[8..16], &ix.data[16..24]), (&7_000u64.to_le_bytes()[..], &6_500u64.to_le_bytes()[..]));
+    // Aucun pool CPMM n'est coté : le décodeur de swaps ne lit pas ce programme.
+    assert!(decode_built(&built.instructions, &user.owner).is_empty());
+}
+
+#[test]
+fn whirlpool_swap_walks_three_tick_arrays_in_the_swap_direction() {
+    let m = mints(2);
+    let Pool::OrcaWhirlpool(pool) = whirlpool_pool(m[0], m[1], 1.0, 1_000_000_000) else { unreachable!() };
+    let user = user();
+    let ticks_in_array = orca_whirlpool::TICK_ARRAY_SIZE * pool.tick_spacing as i32;
+    let start = orca_whirlpool::tick_array_start_index(pool.tick_current_index, pool.tick_spacing);
+
+    for (a_to_b, step) in [(true, -ticks_in_array), (false, ticks_in_array)] {
+        let ix = &pool.build_swap(a_to_b, 1, 0, &user).unwrap().instructions[0];
+        // token_program, authority, whirlpool, A, vault A, B, vault B, 3 TickArrays, oracle.
+        assert_eq!(ix.accounts.len(), 11);
+        assert!(ix.accounts[1].is_signer);
+        let owner_a = if a_to_b { user.source } else { user.destination };
+        assert_eq!(ix.accounts[3].pubkey, owner_a);
+
+        let tick_arrays: Vec<_> = ix.accounts[7..10].iter().map(|meta| meta.pubkey).collect();
+        let expected: Vec<_> =
+            (0..3).map(|i| orca_whirlpool::tick_array_address(&pool.id, start + i * step)).collect();
+        assert_eq!(tick_arrays, expected);
+        assert_eq!(ix.accounts[10].pubkey, whirlpool_oracle_address(&pool.id));
+    }
+}
+
+#[test]
+fn clmm_swap_only_references_tick_arrays_holding_liquidity() {
+    let m = mints(2);
+    let Pool::RaydiumClmm(mut pool) = clmm_pool(m[0], m[1], 1.0, 1_000_000_000) else { unreachable!() };
+    let spacing = pool.tick_spacing;
+    let ticks_in_array = raydium_clmm::TICK_ARRAY_SIZE * spacing as i32;
+    let current = raydium_clmm::tick_array_start_index(pool.current_tick, spacing);
+    let address = |start: i32| raydium_clmm::tick_array_address(&pool.id, start);
+
+    // Sans tick hydraté, seul le TickArray courant est passé.
+    assert_eq!(raydium_clmm_swap_tick_arrays(&pool, true), vec![address(current)]);
+
+    // Des ticks deux TickArrays plus bas, et un TickArray plus haut.
+    pool.ticks = vec![
+        TickLiquidity { tick_index: current - 2 * ticks_in_array, liquidity_net: 1 },
+        TickLiquidity { tick_index: current - 2 * ticks_in_array + spacing as i32, liquidity_net: -1 },
+        TickLiquidity { tick_index: current + ticks_in_array, liquidity_net: -1 },
+    ];
+    assert_eq!(raydium_clmm_swap_tick_arrays(&pool, true), vec![address(current - 2 * ticks_in_array)]);
+    assert_eq!(raydium_clmm_swap_tick_arrays(&pool, false), vec![address(current + ticks_in_array)]);
+}
@@ -0,0 +1,199 @@
+// tests/bundle_sender.rs
+
+use base64::Engine;
+use mev_scalpel::execution::bundle_sender::{BundleOutcome, BundleSender, DEFAULT_BLOCK_ENGINE_URL, JITO_TIP_ACCOUNTS};
+use serde_json::{json, Value};
+use solana_compute_budget_interface::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+type Handler = Arc<dyn Fn(&str, &Value, usize) -> Value + Send + Sync>;
+
+/// Un faux block engine : répond à chaque requête JSON-RPC avec `handler(method, params, n)`,
+/// où `n` compte les appels à cette méthode, et garde les requêtes reçues.
+struct MockBlockEngine {
+    url: String,
+    requests: Arc<Mutex<Vec<Value>>>,
+}
+
+impl MockBlockEngine {
+    async fn start(handler: impl Fn(&str, &Value, usize) -> Value + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let handler: Handler = Arc::new(handler);
+        let recorded = requests.clone();
+        tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else { return };
+                tokio::spawn(serve(stream, handler.clone(), recorded.clone()));
+            }
+        });
+        Self { url, requests }
+    }
+
+    fn methods(&self) -> Vec<String> {
+        self.requests.lock().unwrap().iter().map(|request| request["method"].as_str().unwrap().to_string()).collect()
+    }
+}
+
+/// Sert les requêtes HTTP d'une connexion (keep-alive) jusqu'à sa fermeture.
+async fn serve(mut stream: TcpStream, handler: Handler, requests: Arc<Mutex<Vec<Value>>>) {
+    let mut buffer = Vec::new();
+    loop {
+        let header_end = loop {
+            if let Some(position) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+                break position + 4;
+            }
+            let mut chunk = [0u8; 4096];
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+            }
+        };
+        let headers = String::from_utf8_lossy(&buffer[..header_end]).to_lowercase();
+        let content_length: usize = headers
+            .lines()
+            .find_map(|line| line.strip_prefix("content-length:"))
+            .map(|value| value.trim().parse().unwrap())
+            .unwrap_or(0);
+        while buffer.len() < header_end + content_length {
+            let mut chunk = [0u8; 4096];
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return,
+                Ok(n) => buffer.extend_from_slice(&chunk[..n]),
+            }
+        }
+        let request: Value = serde_json::from_slice(&buffer[header_end..header_end + content_length]).unwrap();
+        buffer.drain(..header_end + content_length);
+
+        let method = request["method"].as_str().unwrap().to_string();
+        let calls = {
+            let mut requests = requests.lock().unwrap();
+            requests.push(request.clone());
+            requests.iter().filter(|known| known["method"] == method.as_str()).count()
+        };
+        let body = handler(&method, &request["params"], calls).to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        if stream.write_all(response.as_bytes()).await.is_err() {
+            return;
+        }
+    }
+}
+
+fn rpc_result(result: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "id": 1, "result": result })
+}
+
+fn landed(bundle_id: &str, slot: u64, err: Value) -> Value {
+    rpc_result(json!({
+        "context": { "slot": slot + 1 },
+        "value": [{
+            "bundle_id": bundle_id,
+            "transactions": ["5sig"],
+            "slot": slot,
+            "confirmation_status": "confirmed",
+            "err": err,
+        }],
+    }))
+}
+
+#[tokio::test]
+async fn bundle_is_sent_base64_encoded_with_a_tip_inside_the_transaction() {
+    let engine = MockBlockEngine::start(|method, _, _| match method {
+        "sendBundle" => rpc_result(json!("bundle-1")),
+        _ => json!({ "jsonrpc": "2.0", "id": 1, "error": { "code": -32601, "message": "unknown method" } }),
+    })
+    .await;
+    let sender = BundleSender::new(&format!("{}/", engine.url));
+    let payer = Keypair::new();
+    let instructions = [
+        ComputeBudgetInstruction::set_compute_unit_limit(50_000),
+        sender.tip_instruction(&payer.pubkey(), 25_000).unwrap(),
+    ];
+    let message = v0::Message::try_compile(&payer.pubkey(), &instructions, &[], Hash::new_unique()).unwrap();
+    let arbitrage = VersionedTransaction::try_new(VersionedMessage::V0(message), &[&payer]).unwrap();
+
+    let bundle_id = sender.send_bundle(std::slice::from_ref(&arbitrage)).await.unwrap();
+    assert_eq!(bundle_id, "bundle-1");
+
+    let request = engine.requests.lock().unwrap()[0].clone();
+    assert_eq!(request["method"], "sendBundle");
+    assert_eq!(request["params"][1]["encoding"], "base64");
+    let transactions: Vec<VersionedTransaction> = request["params"][0]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|encoded| {
+            let bytes = base64::engine::general_purpose::STANDARD.decode(encoded.as_str().unwrap()).unwrap();
+            bincode::deserialize(&bytes).unwrap()
+        })
+        .collect();
+    // Le bundle ne contient que la transaction d'arbitrage : pas de transaction de pourboire à part.
+    assert_eq!(transactions, vec![arbitrage.clone()]);
+
+    // Le pourboire : un transfert système (index 2) de 25 000 lamports vers un compte de tip.
+    let keys = arbitrage.message.static_account_keys();
+    let instruction = &arbitrage.message.instructions()[1];
+    assert_eq!(keys[instruction.accounts[0] as usize], payer.pubkey());
+    assert!(JITO_TIP_ACCOUNTS.contains(&keys[instruction.accounts[1] as usize]));
+    assert_eq!(instruction.data[..4], 2u32.to_le_bytes());
+    assert_eq!(instruction.data[4..], 25_000u64.to_le_bytes());
+
+    // Les erreurs JSON-RPC remontent, et un bundle trop long n'est pas envoyé.
+    assert!(sender.get_tip_accounts().await.unwrap_err().to_string().contains("unknown method"));
+    assert!(sender.send_bundle(&vec![arbitrage; 6]).await.is_err());
+    assert_eq!(engine.methods(), vec!["sendBundle", "getTipAccounts"]);
+}
+
+#[test]
+fn tip_accounts_are_spread_across_the_list() {
+    let sender = BundleSender::new(DEFAULT_BLOCK_ENGINE_URL);
+    let picked: HashSet<Pubkey> = (0..200).map(|_| sender.random_tip_account().unwrap()).collect();
+    // Sur 200 tirages uniformes parmi 8 comptes, en voir moins de 4 est hautement improbable.
+    assert!(picked.len() >= 4, "only {} tip accounts picked", picked.len());
+    assert!(picked.iter().all(|account| JITO_TIP_ACCOUNTS.contains(account)));
+}
+
+#[tokio::test]
+async fn landing_is_polled_until_the_bundle_appears() {
+    let engine = MockBlockEngine::start(|method, params, calls| match (method, params[0][0].as_str()) {
+        // Le bundle "lands" apparaît au troisième appel ; "fails" atterrit en erreur ; "lost" jamais.
+        ("getBundleStatuses", Some("lands")) if calls >= 3 => landed("lands", 42, json!({ "Ok": null })),
+        ("getBundleStatuses", Some("fails")) => landed("fails", 43, json!({ "Err": "BundleFailed" })),
+        ("getBundleStatuses", _) => rpc_result(json!({ "context": { "slot": 1 }, "value": [null] })),
+        ("getTipAccounts", _) => rpc_result(json!(["96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"])),
+        _ => unreachable!(),
+    })
+    .await;
+    let sender = BundleSender::new(&engine.url);
+    let (timeout, poll) = (Duration::from_secs(5), Duration::from_millis(10));
+
+    assert_eq!(sender.wait_for_landing("lands", timeout, poll).await.unwrap(), BundleOutcome::Landed { slot: 42 });
+    assert_eq!(engine.methods().len(), 3);
+    assert_eq!(
+        sender.wait_for_landing("fails", timeout, poll).await.unwrap(),
+        BundleOutcome::Failed { slot: 43, err: json!({ "Err": "BundleFailed" }) }
+    );
+    let short = Duration::from_millis(50);
+    assert_eq!(sender.wait_for_landing("lost", short, poll).await.unwrap(), BundleOutcome::Expired);
+
+    let tip_accounts = sender.get_tip_accounts().await.unwrap();
+    assert_eq!(tip_accounts, vec![JITO_TIP_ACCOUNTS[0]]);
+    let sender = sender.with_tip_accounts(tip_accounts);
+    assert_eq!(sender.random_tip_account().unwrap(), JITO_TIP_ACCOUNTS[0]);
+    assert!(BundleSender::new(&engine.url).with_tip_accounts(Vec::<Pubkey>::new()).random_tip_account().is_err());
+}
@@ -0,0 +1,172 @@
+// tests/clmm_math.rs
+
+mod common;
+
+use common::{mints, sqrt_price_x64, whirlpool_pool, whirlpool_pool_with_ticks};
+use mev_scalpel::decoders::clmm_math::{sqrt_price_from_tick, tick_from_sqrt_price, TickLiquidity};
+use mev_scalpel::decoders::raydium_clmm::{self, TICK_ARRAY_SIZE};
+use mev_scalpel::decoders::{account_discriminator, orca_whirlpool, PoolOperations, RAYDIUM_CLMM_PROGRAM_ID};
+use solana_sdk::pubkey::Pubkey;
+
+const LIQUIDITY: u128 = 1_000_000_000_000;
+
+#[test]
+fn small_swap_quotes_close_to_spot_price_minus_fee() {
+    let m = mints(2);
+    let pool = whirlpool_pool(m[0], m[1], 2.0, LIQUIDITY);
+
+    let out_b = pool.get_quote(&m[0], 1_000_000).unwrap();
+    let expected_b = 1_000_000.0 * 2.0 * (1.0 - 0.003);
+    assert!((out_b as f64 - expected_b).abs() / expected_b < 1e-4, "got {}", out_b);
+
+    let out_a = pool.get_quote(&m[1], 1_000_000).unwrap();
+    let expected_a = 1_000_000.0 / 2.0 * (1.0 - 0.003);
+    assert!((out_a as f64 - expected_a).abs() / expected_a < 1e-4, "got {}", out_a);
+}
+
+#[test]
+fn crossing_into_thinner_liquidity_reduces_output() {
+    let m = mints(2);
+    let tick = tick_from_sqrt_price(sqrt_price_x64(1.0));
+    // Une position qui s'arrête 1 % sous le prix courant : en dessous, la liquidité chute de 90 %.
+    let boundary = TickLiquidity { tick_index: tick - 100, liquidity_net: (LIQUIDITY * 9 / 10) as i128 };
+
+    let deep = whirlpool_pool(m[0], m[1], 1.0, LIQUIDITY);
+    let thin = whirlpool_pool_with_ticks(m[0], m[1], 1.0, LIQUIDITY, vec![boundary]);
+
+    let small = 1_000_000;
+    assert_eq!(deep.get_quote(&m[0], small).unwrap(), thin.get_quote(&m[0], small).unwrap());
+
+    let large = LIQUIDITY as u64 / 20;
+    assert!(thin.get_quote(&m[0], large).unwrap() < deep.get_quote(&m[0], large).unwrap());
+}
+
+#[test]
+fn tick_and_sqrt_price_conversions_round_trip() {
+    for tick in [-200_000, -1_000, -1, 0, 1, 1_000, 200_000] {
+        assert_eq!(tick_from_sqrt_price(sqrt_price_from_tick(tick)), tick);
+    }
+}
+
+#[test]
+fn whirlpool_account_is_decoded() {
+    let (mint_a, mint_b, vault_a, vault_b) =
+        (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let mut data = vec![0u8; 653];
+    data[..8].copy_from_slice(&account_discriminator("Whirlpool"));
+    data[41..43].copy_from_slice(&64u16.to_le_bytes());
+    data[45..47].copy_from_slice(&3000u16.to_le_bytes());
+    data[49..65].copy_from_slice(&LIQUIDITY.to_le_bytes());
+    data[65..81].copy_from_slice(&sqrt_price_x64(1.0).to_le_bytes());
+    data[81..85].copy_from_slice(&(-5i32).to_le_bytes());
+    data[101..133].copy_from_slice(mint_a.as_ref());
+    data[133..165].copy_from_slice(vault_a.as_ref());
+    data[181..213].copy_from_slice(mint_b.as_ref());
+    data[213..245].copy_from_slice(vault_b.as_ref());
+
+    let id = Pubkey::new_unique();
+    let pool = orca_whirlpool::decode_orca_whirlpool(&id, &data).unwrap();
+    assert_eq!((pool.mint_a, pool.mint_b, pool.vault_a, pool.vault_b), (mint_a, mint_b, vault_a, vault_b));
+    assert_eq!((pool.tick_spacing, pool.fee_rate, pool.liquidity), (64, 3000, LIQUIDITY));
+    assert_eq!(pool.tick_current_index, -5);
+
+    data[0] ^= 0xff;
+    assert!(orca_whirlpool::decode_orca_whirlpool(&id, &data).is_err());
+}
+
+#[test]
+fn raydium_clmm_pool_state_is_decoded() {
+    let keys: Vec<Pubkey> = (0..6).map(|_| Pubkey::new_unique()).collect();
+    let (amm_config, mint_a, mint_b, vault_a, vault_b, observation) = (keys[0], keys[1], keys[2], keys[3], keys[4], keys[5]);
+    // Un PoolState fait 1544 octets ; seul l'en-tête jusqu'au tick courant est lu.
+    let mut data = vec![0u8; 1544];
+    data[..8].copy_from_slice(&account_discriminator("PoolState"));
+    data[9..41].copy_from_slice(amm_config.as_ref());
+    data[73..105].copy_from_slice(mint_a.as_ref());
+    data[105..137].copy_from_slice(mint_b.as_ref());
+    data[137..169].copy_from_slice(vault_a.as_ref());
+    data[169..201].copy_from_slice(vault_b.as_ref());
+    data[201..233].copy_from_slice(observation.as_ref());
+    data[233] = 9;
+    data[234] = 6;
+    data[235..237].copy_from_slice(&10u16.to_le_bytes());
+    data[237..253].copy_from_slice(&LIQUIDITY.to_le_bytes());
+    data[253..269].copy_from_slice(&sqrt_price_x64(1.0).to_le_bytes());
+    data[269..273].copy_from_slice(&(-7i32).to_le_bytes());
+
+    let id = Pubkey::new_unique();
+    let pool = raydium_clmm::decode_raydium_clmm(&id, &data).unwrap();
+    assert_eq!((pool.mint_a, pool.mint_b, pool.vault_a, pool.vault_b), (mint_a, mint_b, vault_a, vault_b));
+    assert_eq!((pool.amm_config, pool.observation_key), (amm_config, observation));
+    assert_eq!((pool.tick_spacing, pool.liquidity, pool.current_sqrt_price), (10, LIQUIDITY, sqrt_price_x64(1.0)));
+    assert_eq!(pool.current_tick, -7);
+    // Les frais viennent de l'AmmConfig : le pool n'est pas encore hydraté.
+    assert_eq!(pool.fee_rate, 0);
+
+    assert!(raydium_clmm::decode_raydium_clmm(&id, &data[..272]).is_err());
+    data[0] ^= 0xff;
+    assert!(raydium_clmm::decode_raydium_clmm(&id, &data).is_err());
+}
+
+#[test]
+fn raydium_amm_config_fee_rate_is_read() {
+    // discriminateur, bump, index, owner, protocol_fee_rate, puis trade_fee_rate.
+    let mut data = vec![0u8; 117];
+    data[..8].copy_from_slice(&account_discriminator("AmmConfig"));
+    data[9..11].copy_from_slice(&4u16.to_le_bytes());
+    data[43..47].copy_from_slice(&120_000u32.to_le_bytes());
+    data[47..51].copy_from_slice(&2_500u32.to_le_bytes());
+    assert_eq!(raydium_clmm::decode_amm_config_fee_rate(&data).unwrap(), 2_500);
+    assert!(raydium_clmm::decode_amm_config_fee_rate(&data[..50]).is_err());
+}
+
+#[test]
+fn raydium_tick_array_keeps_only_initialized_ticks() {
+    const TICKS_OFFSET: usize = 8 + 32 + 4;
+    const TICK_STATE_SIZE: usize = 168;
+    let pool_id = Pubkey::new_unique();
+    let mut data = vec![0u8; 10240];
+    data[..8].copy_from_slice(&account_discriminator("TickArrayState"));
+    data[8..40].copy_from_slice(pool_id.as_ref());
+    data[40..44].copy_from_slice(&(-600i32).to_le_bytes());
+    // (position dans le tableau, tick, liquidity_net, liquidity_gross)
+    for (slot, tick, net, gross) in [(0, -600i32, 5_000i128, 5_000u128), (3, -570, -2_000, 2_000), (7, -530, 0, 0)] {
+        let tick_data = &mut data[TICKS_OFFSET + slot * TICK_STATE_SIZE..];
+        tick_data[0..4].copy_from_slice(&tick.to_le_bytes());
+        tick_data[4..20].copy_from_slice(&net.to_le_bytes());
+        tick_data[20..36].copy_from_slice(&gross.to_le_bytes());
+    }
+
+    let ticks = raydium_clmm::decode_tick_array(&data).unwrap();
+    // Le tick sans liquidité brute n'est pas initialisé : il est ignoré.
+    assert_eq!(
+        ticks,
+        vec![
+            TickLiquidity { tick_index: -600, liquidity_net: 5_000 },
+            TickLiquidity { tick_index: -570, liquidity_net: -2_000 },
+        ]
+    );
+    assert!(raydium_clmm::decode_tick_array(&data[..TICKS_OFFSET + 59 * TICK_STATE_SIZE]).is_err());
+}
+
+#[test]
+fn raydium_tick_array_start_and_address() {
+    // 60 ticks par tableau : avec un espacement de 10, un tableau couvre 600 ticks.
+    assert_eq!(TICK_ARRAY_SIZE, 60);
+    assert_eq!(raydium_clmm::tick_array_start_index(0, 10), 0);
+    assert_eq!(raydium_clmm::tick_array_start_index(599, 10), 0);
+    assert_eq!(raydium_clmm::tick_array_start_index(600, 10), 600);
+    assert_eq!(raydium_clmm::tick_array_start_index(-1, 10), -600);
+    assert_eq!(raydium_clmm::tick_array_start_index(-601, 10), -1200);
+    assert_eq!(raydium_clmm::tick_array_start_index(-7, 1), -60);
+
+    // Raydium encode l'index de départ en big-endian (Orca l'écrit en décimal).
+    let pool_id = Pubkey::new_unique();
+    let (expected, _) = Pubkey::find_program_address(
+        &[b"tick_array", pool_id.as_ref(), &(-600i32).to_be_bytes()],
+        &RAYDIUM_CLMM_PROGRAM_ID,
+    );
+    assert_eq!(raydium_clmm::tick_array_address(&pool_id, -600), expected);
+    assert_ne!(raydium_clmm::tick_array_address(&pool_id, -600), raydium_clmm::tick_array_address(&pool_id, 0));
+    assert_ne!(raydium_clmm::tick_array_address(&pool_id, 0), orca_whirlpool::tick_array_address(&pool_id, 0));
+}
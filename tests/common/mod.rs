@@ -0,0 +1,132 @@
+// tests/common/mod.rs
+
+// Constructeurs de pools, de graphes, d'opportunités et de comptes RPC synthétiques
+// partagés par les tests d'intégration.
+#![allow(dead_code)]
+
+use base64::Engine;
+use mev_scalpel::decoders::clmm_math::{tick_from_sqrt_price, TickLiquidity};
+use mev_scalpel::decoders::{OrcaWhirlpoolPool, Pool, RaydiumAmmPool, RaydiumClmmPool};
+use mev_scalpel::execution::costs::{CostModel, TOKEN_ACCOUNT_RENT_LAMPORTS};
+use mev_scalpel::state::MarketGraph;
+use mev_scalpel::strategies::opportunity::{OwnedOpportunity, DEFAULT_SLIPPAGE_BPS};
+use mev_scalpel::strategies::spatial_arb::find_spatial_opportunities;
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+
+/// Un pool Raydium AMM v4 avec les réserves données.
+pub fn amm_pool(mint_a: Pubkey, mint_b: Pubkey, reserve_a: u64, reserve_b: u64) -> Pool {
+    Pool::RaydiumAmm(RaydiumAmmPool {
+        id: Pubkey::new_unique(),
+        mint_a,
+        mint_b,
+        mint_a_reserve: reserve_a,
+        mint_b_reserve: reserve_b,
+        base_vault: Pubkey::new_unique(),
+        quote_vault: Pubkey::new_unique(),
+        ..Default::default()
+    })
+}
+
+/// √prix en Q64.64 pour un prix donné (token B par token A, en unités de base).
+pub fn sqrt_price_x64(price: f64) -> u128 {
+    (price.sqrt() * 18_446_744_073_709_551_616.0) as u128
+}
+
+/// Un Whirlpool sans tick initialisé (liquidité constante) au prix donné, frais 0.3 %.
+pub fn whirlpool_pool(mint_a: Pubkey, mint_b: Pubkey, price: f64, liquidity: u128) -> Pool {
+    whirlpool_pool_with_ticks(mint_a, mint_b, price, liquidity, Vec::new())
+}
+
+/// Un Whirlpool avec les ticks initialisés donnés.
+pub fn whirlpool_pool_with_ticks(
+    mint_a: Pubkey,
+    mint_b: Pubkey,
+    price: f64,
+    liquidity: u128,
+    ticks: Vec<TickLiquidity>,
+) -> Pool {
+    let sqrt_price = sqrt_price_x64(price);
+    Pool::OrcaWhirlpool(OrcaWhirlpoolPool {
+        id: Pubkey::new_unique(),
+        mint_a,
+        mint_b,
+        vault_a: Pubkey::new_unique(),
+        vault_b: Pubkey::new_unique(),
+        tick_spacing: 64,
+        fee_rate: 3000,
+        liquidity,
+        sqrt_price,
+        tick_current_index: tick_from_sqrt_price(sqrt_price),
+        ticks,
+    })
+}
+
+/// Un pool Raydium CLMM sans tick initialisé au prix donné, frais 0.25 %.
+pub fn clmm_pool(mint_a: Pubkey, mint_b: Pubkey, price: f64, liquidity: u128) -> Pool {
+    let sqrt_price = sqrt_price_x64(price);
+    Pool::RaydiumClmm(RaydiumClmmPool {
+        id: Pubkey::new_unique(),
+        mint_a,
+        mint_b,
+        vault_a: Pubkey::new_unique(),
+        vault_b: Pubkey::new_unique(),
+        amm_config: Pubkey::new_unique(),
+        observation_key: Pubkey::new_unique(),
+        tick_spacing: 10,
+        liquidity,
+        current_sqrt_price: sqrt_price,
+        current_tick: tick_from_sqrt_price(sqrt_price),
+        fee_rate: 2500,
+        ticks: Vec::new(),
+    })
+}
+
+/// `n` mints distincts.
+pub fn mints(n: usize) -> Vec<Pubkey> {
+    (0..n).map(|_| Pubkey::new_unique()).collect()
+}
+
+/// Construit un graphe à partir d'une liste de pools.
+pub fn graph_of(pools: Vec<Pool>) -> MarketGraph {
+    let mut graph = MarketGraph::default();
+    for pool in pools {
+        graph.add_pool(pool);
+    }
+    graph
+}
+
+/// Le montant maximal engagé par les opportunités de test.
+pub const MAX_AMOUNT: u64 = 100_000_000_000;
+
+/// Le meilleur arbitrage spatial qui part de `start` sur ces pools, détaché de leur graphe.
+pub fn spatial_opportunity(pools: Vec<Pool>, start: Pubkey) -> OwnedOpportunity {
+    let graph = graph_of(pools);
+    let opportunity = find_spatial_opportunities(&graph, &[start], MAX_AMOUNT, &CostModel::free()).remove(0);
+    OwnedOpportunity::from_opportunity(&graph, &opportunity, 1, 100, DEFAULT_SLIPPAGE_BPS).unwrap()
+}
+
+/// Un arbitrage spatial entre un CLMM et un Whirlpool 5 % plus cher, qui part de `start`.
+pub fn clmm_whirlpool_opportunity(start: Pubkey, other: Pubkey) -> OwnedOpportunity {
+    const LIQUIDITY: u128 = 1_000_000_000_000;
+    spatial_opportunity(vec![clmm_pool(start, other, 1.0, LIQUIDITY), whirlpool_pool(start, other, 1.05, LIQUIDITY)], start)
+}
+
+/// Un compte au format JSON du RPC (données en base64).
+pub fn ui_account(lamports: u64, owner: &Pubkey, data: &[u8]) -> Value {
+    json!({
+        "lamports": lamports,
+        "data": [base64::engine::general_purpose::STANDARD.encode(data), "base64"],
+        "owner": owner.to_string(),
+        "executable": false,
+        "rentEpoch": 0,
+        "space": data.len(),
+    })
+}
+
+/// Un compte de tokens SPL au solde donné.
+pub fn token_account(amount: u64) -> Value {
+    let mut data = vec![0u8; 165];
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    ui_account(TOKEN_ACCOUNT_RENT_LAMPORTS, &spl_token::id(), &data)
+}
@@ -0,0 +1,93 @@
+// tests/costs.rs
+
+mod common;
+
+use common::{amm_pool, graph_of, mints};
+use mev_scalpel::execution::costs::{CostModel, PathCosts, TipPolicy, SOL_MINT};
+use mev_scalpel::execution::optimizer::find_optimal_amount;
+use mev_scalpel::strategies::sizing::sol_equivalents;
+use mev_scalpel::strategies::spatial_arb::find_spatial_opportunities;
+use mev_scalpel::strategies::Cycle;
+
+const SOL: u64 = 1_000_000_000;
+const MAX_AMOUNT: u64 = 100 * SOL;
+
+#[test]
+fn tip_share_has_a_floor_and_keeps_net_profit_increasing() {
+    let costs = PathCosts { fixed: 100, tip_bps: 5_000, min_tip: 10 };
+    assert_eq!(costs.net_profit(-50), -160);
+    assert_eq!(costs.net_profit(10), -100);
+    assert_eq!(costs.net_profit(1_000), 400);
+    assert!((0..2_000).step_by(7).map(|gross| costs.net_profit(gross)).collect::<Vec<_>>().is_sorted());
+    assert_eq!(PathCosts::default().net_profit(1_234), 1_234);
+}
+
+#[test]
+fn sol_costs_are_converted_into_the_start_token() {
+    let m = mints(2);
+    let (usdc, other) = (m[0], m[1]);
+    // 1 SOL = 150 USDC (6 décimales).
+    let graph = graph_of(vec![
+        amm_pool(SOL_MINT, usdc, 10_000 * SOL, 1_500_000_000_000),
+        amm_pool(usdc, other, 1_000_000_000_000, 1_000_000_000_000),
+        amm_pool(usdc, other, 1_000_000_000_000, 1_050_000_000_000),
+    ]);
+    let model = CostModel {
+        // 1 lamport par compute unit.
+        compute_unit_price_micro_lamports: 1_000_000,
+        tip: TipPolicy::ProfitShare { bps: 2_000, min_lamports: 10_000 },
+        ..CostModel::default()
+    };
+
+    // 20 000 CU de base + 2 swaps AMM à 45 000 CU, plus une signature.
+    let cycle = Cycle { tokens: vec![1, 2, 1], pools: vec![2, 1] };
+    let path = cycle.to_arbitrage_path(&graph).unwrap();
+    assert_eq!(model.compute_units(&path), 110_000);
+    assert_eq!(model.fixed_cost_lamports(&path), 115_000);
+
+    let usdc_per_sol = sol_equivalents(&graph)[1].unwrap();
+    assert!((usdc_per_sol as f64 / 150_000_000.0 - 1.0).abs() < 0.01, "got {}", usdc_per_sol);
+    let costs = model.path_costs(&path, Some(usdc_per_sol)).unwrap();
+    assert_eq!(costs.fixed, (115_000 * usdc_per_sol).div_ceil(SOL));
+    assert_eq!(costs.min_tip, (10_000 * usdc_per_sol).div_ceil(SOL));
+    assert_eq!(costs.tip_bps, 2_000);
+    // Sans prix en SOL, les coûts ne peuvent pas être exprimés.
+    assert_eq!(model.path_costs(&path, None), None);
+    // Le pourboire versé en lamports : 20 % du profit brut reconverti en SOL, avec son plancher.
+    let gross = 10 * usdc_per_sol as i64;
+    assert_eq!(model.tip_lamports(&path, gross, Some(usdc_per_sol)), Some(2 * SOL));
+    assert_eq!(model.tip_lamports(&path, 1, Some(usdc_per_sol)), Some(10_000));
+    assert_eq!(model.tip_lamports(&path, gross, None), None);
+
+    // Le profit net est le profit brut au même montant, coûts déduits.
+    let (gross_amount, gross_profit) = find_optimal_amount(&path, MAX_AMOUNT, &PathCosts::default()).unwrap();
+    let (net_amount, net_profit) = find_optimal_amount(&path, MAX_AMOUNT, &costs).unwrap();
+    assert_eq!(net_amount, gross_amount);
+    assert_eq!(net_profit, costs.net_profit(gross_profit));
+
+    // Un cycle qui part du SOL paie ses coûts directement en lamports.
+    let sol_path = Cycle { tokens: vec![0, 1, 0], pools: vec![0, 0] }.to_arbitrage_path(&graph).unwrap();
+    assert_eq!(model.path_costs(&sol_path, None).unwrap().fixed, 115_000);
+    assert_eq!(CostModel::free().path_costs(&path, None), Some(PathCosts::default()));
+}
+
+#[test]
+fn thin_opportunities_do_not_survive_execution_costs() {
+    let m = mints(2);
+    let thin = graph_of(vec![
+        amm_pool(SOL_MINT, m[0], SOL, SOL),
+        amm_pool(SOL_MINT, m[0], SOL, SOL * 101 / 100),
+    ]);
+    assert_eq!(find_spatial_opportunities(&thin, &[SOL_MINT], MAX_AMOUNT, &CostModel::free()).len(), 1);
+    assert!(find_spatial_opportunities(&thin, &[SOL_MINT], MAX_AMOUNT, &CostModel::default()).is_empty());
+
+    let wide = graph_of(vec![
+        amm_pool(SOL_MINT, m[1], 1_000 * SOL, 1_000 * SOL),
+        amm_pool(SOL_MINT, m[1], 1_000 * SOL, 1_050 * SOL),
+    ]);
+    let gross = &find_spatial_opportunities(&wide, &[SOL_MINT], MAX_AMOUNT, &CostModel::free())[0];
+    let net = &find_spatial_opportunities(&wide, &[SOL_MINT], MAX_AMOUNT, &CostModel::default())[0];
+    assert_eq!((net.cycle.clone(), net.amount_in), (gross.cycle.clone(), gross.amount_in));
+    // La moitié du profit part en pourboire, le reste paie la signature et les frais de priorité.
+    assert!(net.expected_profit > 0 && net.expected_profit < gross.expected_profit / 2);
+}
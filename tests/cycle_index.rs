@@ -0,0 +1,58 @@
+// tests/cycle_index.rs
+
+mod common;
+
+use common::{amm_pool, graph_of, mints};
+use mev_scalpel::decoders::{Pool, PoolOperations};
+use mev_scalpel::execution::costs::CostModel;
+use mev_scalpel::strategies::cycle_index::CycleIndex;
+
+const DEPTH: u64 = 1_000_000_000_000;
+const MAX_AMOUNT: u64 = 100_000_000_000;
+
+#[test]
+fn reserve_change_only_requotes_cycles_through_that_pool() {
+    let m = mints(5);
+    // Deux triangles fairs qui partagent le token de base m[0].
+    let mut graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[1], m[2], DEPTH, DEPTH),
+        amm_pool(m[2], m[0], DEPTH, DEPTH),
+        amm_pool(m[0], m[3], DEPTH, DEPTH),
+        amm_pool(m[3], m[4], DEPTH, DEPTH),
+        amm_pool(m[4], m[0], DEPTH, DEPTH),
+    ]);
+    let index = CycleIndex::build(&graph, &[m[0]], 4);
+    assert_eq!(index.cycles().len(), 4);
+    assert!(index.requote_all(&graph, MAX_AMOUNT, &CostModel::free()).is_empty());
+
+    // Le pool m[2]/m[0] se désaligne de 10 %.
+    let changed = graph.pools[2].get_id();
+    if let Some(Pool::RaydiumAmm(pool)) = graph.pool_mut(&changed) {
+        pool.mint_b_reserve = DEPTH * 11 / 10;
+    }
+
+    let affected = index.affected_cycles(&[changed]);
+    assert_eq!(affected.len(), 2, "only the two directions of the first triangle use this pool");
+
+    let opportunities = index.requote(&graph, &[changed], MAX_AMOUNT, &CostModel::free());
+    assert_eq!(opportunities.len(), 1);
+    assert!(opportunities[0].expected_profit > 0);
+    assert!(opportunities[0].cycle.pools.contains(&2));
+}
+
+#[test]
+fn index_is_rebuilt_when_topology_changes() {
+    let m = mints(3);
+    let mut graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[1], m[2], DEPTH, DEPTH),
+    ]);
+    let mut index = CycleIndex::build(&graph, &[m[0]], 4);
+    assert!(index.cycles().is_empty());
+    assert!(!index.refresh(&graph));
+
+    graph.add_pool(amm_pool(m[2], m[0], DEPTH, DEPTH));
+    assert!(index.refresh(&graph));
+    assert_eq!(index.cycles().len(), 2);
+}
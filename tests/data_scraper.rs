@@ -0,0 +1,64 @@
+// tests/data_scraper.rs
+
+mod common;
+
+use common::{token_account, ui_account};
+use mev_scalpel::data_pipeline::data_scraper::hydrate_single_pool;
+use mev_scalpel::decoders::RaydiumAmmPool;
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_rpc_client::mock_sender::MocksMap;
+use solana_sdk::pubkey::Pubkey;
+
+/// Un marché OpenBook minimal, au nonce de signataire valide pour `market_id`.
+fn market_account(market_id: &Pubkey, market_program: &Pubkey) -> Vec<u8> {
+    let nonce = (0u64..)
+        .find(|nonce| Pubkey::create_program_address(&[market_id.as_ref(), &nonce.to_le_bytes()], market_program).is_ok())
+        .unwrap();
+    let mut data = vec![0u8; 388];
+    data[..5].copy_from_slice(b"serum");
+    data[45..53].copy_from_slice(&nonce.to_le_bytes());
+    data[381..].copy_from_slice(b"padding");
+    data
+}
+
+fn mock_rpc(accounts: Vec<Value>) -> RpcClient {
+    let mocks: MocksMap =
+        [(RpcRequest::GetMultipleAccounts, json!({ "context": { "slot": 1 }, "value": accounts }))].into_iter().collect();
+    RpcClient::new_mock_with_mocks_map("succeeds", mocks)
+}
+
+fn unhydrated_pool() -> RaydiumAmmPool {
+    RaydiumAmmPool {
+        id: Pubkey::new_unique(),
+        base_vault: Pubkey::new_unique(),
+        quote_vault: Pubkey::new_unique(),
+        market_id: Pubkey::new_unique(),
+        market_program: Pubkey::new_unique(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn unreadable_market_keeps_the_fresh_reserves() {
+    let mut pool = unhydrated_pool();
+    let garbage = ui_account(1_000_000, &pool.market_program, &[0u8; 100]);
+    let rpc = mock_rpc(vec![token_account(7_000), token_account(9_000), garbage]);
+
+    // Le marché est illisible : les réserves sont tout de même à jour, et le pool reste cotable.
+    hydrate_single_pool(&mut pool, &rpc).unwrap();
+    assert_eq!((pool.mint_a_reserve, pool.mint_b_reserve), (7_000, 9_000));
+    assert!(pool.market.is_none());
+}
+
+#[test]
+fn readable_market_is_stored_with_the_reserves() {
+    let mut pool = unhydrated_pool();
+    let market = ui_account(1_000_000, &pool.market_program, &market_account(&pool.market_id, &pool.market_program));
+    let rpc = mock_rpc(vec![token_account(7_000), token_account(9_000), market]);
+
+    hydrate_single_pool(&mut pool, &rpc).unwrap();
+    assert_eq!((pool.mint_a_reserve, pool.mint_b_reserve), (7_000, 9_000));
+    assert!(pool.market.is_some());
+}
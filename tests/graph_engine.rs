@@ -0,0 +1,191 @@
+// tests/graph_engine.rs
+
+mod common;
+
+use common::{amm_pool, graph_of, mints, sqrt_price_x64, ui_account, whirlpool_pool};
+use mev_scalpel::decoders::clmm_math::tick_from_sqrt_price;
+use mev_scalpel::decoders::{account_discriminator, Pool, PoolOperations, ORCA_WHIRLPOOL_PROGRAM_ID};
+use mev_scalpel::graph_engine::{
+    prune_leaves, refresh_pools, restrict_to_base_components, strongly_connected_components,
+};
+use mev_scalpel::state::{Edge, MarketGraph};
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_rpc_client::mock_sender::MocksMap;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashSet;
+
+const DEPTH: u64 = 1_000_000_000_000;
+
+/// Un triangle de pools entre trois mints.
+fn triangle(m: &[Pubkey]) -> Vec<Pool> {
+    vec![amm_pool(m[0], m[1], DEPTH, DEPTH), amm_pool(m[1], m[2], DEPTH, DEPTH), amm_pool(m[2], m[0], DEPTH, DEPTH)]
+}
+
+/// Les annuaires et les arêtes décrivent la même table de pools : chaque arête relie
+/// les deux mints de son pool, et chaque pool porte exactement ses deux arêtes.
+fn assert_consistent(graph: &MarketGraph) {
+    assert_eq!(graph.tokens.len(), graph.nodes.len());
+    for (idx, mint) in graph.tokens.iter().enumerate() {
+        assert_eq!(graph.token_map[mint], idx);
+    }
+    assert_eq!(graph.pool_map.len(), graph.pools.len());
+    let mut edges_per_pool = vec![0; graph.pools.len()];
+    for (source, edges) in graph.nodes.iter().enumerate() {
+        for edge in edges {
+            let pool = &graph.pools[edge.pool_index];
+            assert_eq!(graph.pool_map[&pool.get_id()], edge.pool_index);
+            let (mint_a, mint_b) = pool.get_mints();
+            let ends = (graph.tokens[source], graph.tokens[edge.destination]);
+            assert!(ends == (mint_a, mint_b) || ends == (mint_b, mint_a));
+            edges_per_pool[edge.pool_index] += 1;
+        }
+    }
+    assert!(edges_per_pool.iter().all(|&count| count == 2));
+}
+
+fn mint_set(graph: &MarketGraph) -> HashSet<Pubkey> {
+    graph.tokens.iter().copied().collect()
+}
+
+fn components(graph: &MarketGraph) -> HashSet<Vec<Pubkey>> {
+    strongly_connected_components(graph)
+        .into_iter()
+        .map(|component| {
+            let mut mints: Vec<Pubkey> = component.into_iter().map(|idx| graph.tokens[idx]).collect();
+            mints.sort();
+            mints
+        })
+        .collect()
+}
+
+#[test]
+fn chain_of_leaves_is_pruned_down_to_the_cycle() {
+    let m = mints(6);
+    let mut pools = triangle(&m);
+    // Une chaîne m2 - m3 - m4 - m5 : m5 est une feuille, puis m4, puis m3.
+    pools.push(amm_pool(m[2], m[3], DEPTH, DEPTH));
+    pools.push(amm_pool(m[3], m[4], DEPTH, DEPTH));
+    pools.push(amm_pool(m[4], m[5], DEPTH, DEPTH));
+    let graph = graph_of(pools);
+
+    let pruned = prune_leaves(&graph);
+    assert_eq!(mint_set(&pruned), m[..3].iter().copied().collect());
+    assert_eq!(pruned.pools.len(), 3);
+    // L'ordre des pools restants est celui du graphe d'origine.
+    let ids: Vec<Pubkey> = pruned.pools.iter().map(|pool| pool.get_id()).collect();
+    assert_eq!(ids, graph.pools[..3].iter().map(|pool| pool.get_id()).collect::<Vec<_>>());
+    assert_consistent(&pruned);
+}
+
+#[test]
+fn two_pools_on_the_same_pair_survive_pruning() {
+    let m = mints(3);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[0], m[1], DEPTH, 2 * DEPTH),
+        amm_pool(m[1], m[2], DEPTH, DEPTH),
+    ]);
+    let pruned = prune_leaves(&graph);
+    assert_eq!(mint_set(&pruned), m[..2].iter().copied().collect());
+    assert_eq!(pruned.pools.len(), 2);
+    assert_consistent(&pruned);
+}
+
+#[test]
+fn one_way_edge_does_not_merge_components() {
+    let m = mints(6);
+    let mut pools = triangle(&m[..3]);
+    pools.extend(triangle(&m[3..]));
+    let mut graph = graph_of(pools);
+    // Une arête à sens unique de m2 vers m3 : m3 est atteignable depuis m2, mais pas l'inverse.
+    let (from, to) = (graph.token_map[&m[2]], graph.token_map[&m[3]]);
+    graph.nodes[from].push(Edge { destination: to, pool_index: 0 });
+
+    let mut first = m[..3].to_vec();
+    let mut second = m[3..].to_vec();
+    first.sort();
+    second.sort();
+    assert_eq!(components(&graph), HashSet::from([first, second]));
+
+    // Le composant de la base est gardé ; celui qui ne fait que la rejoindre est écarté.
+    let restricted = restrict_to_base_components(&graph, &[m[4]]);
+    assert_eq!(mint_set(&restricted), m[3..].iter().copied().collect());
+    assert_consistent(&restricted);
+    let restricted = restrict_to_base_components(&graph, &[m[0]]);
+    assert_eq!(mint_set(&restricted), m[..3].iter().copied().collect());
+    assert_consistent(&restricted);
+}
+
+#[test]
+fn restriction_keeps_only_components_holding_a_base_token() {
+    let m = mints(7);
+    let mut pools = triangle(&m[..3]);
+    pools.extend(triangle(&m[3..6]));
+    let graph = graph_of(pools);
+
+    // Une base dans chaque composant : tout est gardé, avec les mêmes pools.
+    let both = restrict_to_base_components(&graph, &[m[0], m[5]]);
+    assert_eq!(mint_set(&both), mint_set(&graph));
+    assert_eq!(both.pools.len(), 6);
+    assert_consistent(&both);
+
+    // Aucune base connue (m6 n'a pas de pool) : le graphe est vide.
+    let none = restrict_to_base_components(&graph, &[m[6]]);
+    assert!(none.tokens.is_empty() && none.pools.is_empty());
+
+    // Les pools gardés sont ré-indexés, et leurs arêtes pointent sur la nouvelle table.
+    let second = restrict_to_base_components(&graph, &[m[3]]);
+    assert_eq!(second.pools.len(), 3);
+    for pool in &second.pools {
+        let id = pool.get_id();
+        assert_eq!(second.pools[second.pool_map[&id]].get_id(), id);
+        assert!(graph.pool_map[&id] >= 3);
+    }
+    assert_consistent(&second);
+}
+
+/// Le compte Whirlpool d'un pool du graphe, au prix donné.
+fn whirlpool_account(pool: &Pool, price: f64) -> Value {
+    let Pool::OrcaWhirlpool(pool) = pool else { panic!("not a Whirlpool") };
+    let sqrt_price = sqrt_price_x64(price);
+    let mut data = vec![0u8; 653];
+    data[..8].copy_from_slice(&account_discriminator("Whirlpool"));
+    data[41..43].copy_from_slice(&pool.tick_spacing.to_le_bytes());
+    data[45..47].copy_from_slice(&(pool.fee_rate as u16).to_le_bytes());
+    data[49..65].copy_from_slice(&pool.liquidity.to_le_bytes());
+    data[65..81].copy_from_slice(&sqrt_price.to_le_bytes());
+    data[81..85].copy_from_slice(&tick_from_sqrt_price(sqrt_price).to_le_bytes());
+    data[101..133].copy_from_slice(pool.mint_a.as_ref());
+    data[133..165].copy_from_slice(pool.vault_a.as_ref());
+    data[181..213].copy_from_slice(pool.mint_b.as_ref());
+    data[213..245].copy_from_slice(pool.vault_b.as_ref());
+    ui_account(1_000_000, &ORCA_WHIRLPOOL_PROGRAM_ID, &data)
+}
+
+#[test]
+fn refresh_rereads_the_price_of_concentrated_pools() {
+    let m = mints(2);
+    let graph = graph_of(vec![whirlpool_pool(m[0], m[1], 1.0, DEPTH as u128), whirlpool_pool(m[0], m[1], 1.0, DEPTH as u128)]);
+    let accounts = |value: Value| json!({ "context": { "slot": 1 }, "value": value });
+    let no_tick_arrays = accounts(json!([null, null, null, null, null]));
+    // Les comptes des pools (le premier a bougé), puis les TickArrays de chacun.
+    let mocks: MocksMap = [
+        (RpcRequest::GetMultipleAccounts, accounts(json!([whirlpool_account(&graph.pools[0], 1.02), whirlpool_account(&graph.pools[1], 1.0)]))),
+        (RpcRequest::GetMultipleAccounts, no_tick_arrays.clone()),
+        (RpcRequest::GetMultipleAccounts, no_tick_arrays),
+    ]
+    .into_iter()
+    .collect();
+    let rpc = RpcClient::new_mock_with_mocks_map("succeeds", mocks);
+
+    let (refreshed, changed_pools) = refresh_pools(&graph, &rpc);
+    assert_eq!(changed_pools, vec![graph.pools[0].get_id()]);
+    let (Pool::OrcaWhirlpool(before), Pool::OrcaWhirlpool(after)) = (&graph.pools[0], &refreshed.pools[0]) else {
+        panic!("not a Whirlpool")
+    };
+    assert_eq!(after.sqrt_price, sqrt_price_x64(1.02));
+    assert!(after.tick_current_index > before.tick_current_index);
+    assert!(refreshed.pools[0].get_quote(&m[0], 1_000_000).unwrap() > graph.pools[0].get_quote(&m[0], 1_000_000).unwrap());
+}
@@ -0,0 +1,173 @@
+// tests/graph_export.rs
+
+mod common;
+
+use common::{amm_pool, graph_of, whirlpool_pool};
+use mev_scalpel::decoders::PoolOperations;
+use mev_scalpel::graph_export::{to_dot, to_json, GraphView};
+use mev_scalpel::state::MarketGraph;
+use mev_scalpel::token_registry::{TokenInfo, TokenRegistry};
+use serde_json::Value;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+const DEPTH: u64 = 1_000_000_000_000;
+const LIQUIDITY: u128 = 1_000_000_000_000_000;
+
+/// SOL - USDC (Whirlpool, 150 USDC le SOL), SOL - X (AMM), puis X - Y (AMM) : X et Y sont inconnus de l'annuaire.
+fn market() -> (MarketGraph, [Pubkey; 4]) {
+    let sol = Pubkey::from_str("So11111111111111111111111111111111111111112").unwrap();
+    let usdc = Pubkey::from_str("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v").unwrap();
+    let (x, y) = (Pubkey::new_unique(), Pubkey::new_unique());
+    let graph = graph_of(vec![
+        // Le prix est en unités de base : 150 * 10^6 / 10^9.
+        whirlpool_pool(sol, usdc, 0.15, LIQUIDITY),
+        amm_pool(x, sol, DEPTH, DEPTH),
+        amm_pool(y, x, DEPTH, DEPTH),
+    ]);
+    (graph, [sol, usdc, x, y])
+}
+
+fn index(graph: &MarketGraph, mint: &Pubkey) -> usize {
+    graph.token_map[mint]
+}
+
+fn exported(graph: &MarketGraph, view: &GraphView) -> Value {
+    serde_json::from_str(&to_json(graph, &TokenRegistry::with_known_tokens(), view).unwrap()).unwrap()
+}
+
+/// L'arête exportée de `source` vers `destination`.
+fn edge(json: &Value, source: usize, destination: usize) -> &Value {
+    json["edges"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|edge| edge["source"] == source && edge["destination"] == destination)
+        .unwrap()
+}
+
+#[test]
+fn nodes_are_labelled_with_the_symbol_or_a_short_key() {
+    let (graph, [sol, usdc, x, _]) = market();
+    let registry = TokenRegistry::with_known_tokens();
+    assert_eq!(registry.symbol(&sol), "SOL");
+    assert_eq!(registry.symbol(&usdc), "USDC");
+    assert_eq!(registry.symbol(&x), x.to_string()[..6]);
+
+    let dot = to_dot(&graph, &registry, &GraphView::full(&graph));
+    assert!(dot.starts_with("digraph market {") && dot.ends_with("}\n"));
+    let x_key = x.to_string();
+    assert!(dot.contains(&format!("n{} [label=\"SOL\\nSo11…1112\"];", index(&graph, &sol))));
+    assert!(dot.contains(&format!("n{} [label=\"USDC\\nEPjF…Dt1v\"];", index(&graph, &usdc))));
+    assert!(dot.contains(&format!(
+        "n{} [label=\"{}\\n{}…{}\"];",
+        index(&graph, &x),
+        &x_key[..6],
+        &x_key[..4],
+        &x_key[x_key.len() - 4..]
+    )));
+
+    let json = exported(&graph, &GraphView::full(&graph));
+    let nodes = json["nodes"].as_array().unwrap();
+    assert_eq!(nodes.len(), 4);
+    let sol_node = nodes.iter().find(|node| node["index"] == index(&graph, &sol)).unwrap();
+    assert_eq!((sol_node["symbol"].as_str(), sol_node["mint"].as_str()), (Some("SOL"), Some(sol.to_string().as_str())));
+}
+
+#[test]
+fn edges_carry_the_dex_and_the_current_rate() {
+    let (graph, [sol, usdc, x, _]) = market();
+    let (sol_idx, usdc_idx, x_idx) = (index(&graph, &sol), index(&graph, &usdc), index(&graph, &x));
+    let json = exported(&graph, &GraphView::full(&graph));
+    // Deux arêtes par pool.
+    assert_eq!(json["edges"].as_array().unwrap().len(), 6);
+
+    // Décimales connues : le taux est en unités humaines, frais de 0.3 % déduits.
+    let sol_usdc = edge(&json, sol_idx, usdc_idx);
+    assert_eq!(sol_usdc["dex"], "Orca Whirlpool");
+    assert_eq!(sol_usdc["pool_id"], graph.pools[0].get_id().to_string());
+    let rate = sol_usdc["rate"].as_f64().unwrap();
+    assert!((149.0..150.0).contains(&rate), "{}", rate);
+    let rate = edge(&json, usdc_idx, sol_idx)["rate"].as_f64().unwrap();
+    assert!((1.0 / 150.0 * 0.99..1.0 / 150.0).contains(&rate), "{}", rate);
+
+    // X est inconnu : le taux est brut, en unités de base, sur un pool équilibré.
+    let x_sol = edge(&json, x_idx, sol_idx);
+    assert_eq!(x_sol["dex"], "Raydium AMM v4");
+    let rate = x_sol["rate"].as_f64().unwrap();
+    assert!((0.99..1.0).contains(&rate), "{}", rate);
+
+    let dot = to_dot(&graph, &TokenRegistry::with_known_tokens(), &GraphView::full(&graph));
+    let pool_key = graph.pools[0].get_id().to_string();
+    let line = format!("n{} -> n{} [label=\"Orca Whirlpool\\n{}…{}\\nrate=", sol_idx, usdc_idx, &pool_key[..4], &pool_key[pool_key.len() - 4..]);
+    assert!(dot.contains(&line), "{}", dot);
+}
+
+#[test]
+fn view_around_a_token_stops_at_the_requested_depth() {
+    let (graph, [sol, usdc, x, y]) = market();
+    let sol_idx = index(&graph, &sol);
+
+    let view = GraphView::around_token(&graph, sol_idx, 0);
+    assert_eq!(view.nodes, BTreeSet::from([sol_idx]));
+    let view = GraphView::around_token(&graph, sol_idx, 1);
+    assert_eq!(view.nodes, BTreeSet::from([sol_idx, index(&graph, &usdc), index(&graph, &x)]));
+    let view = GraphView::around_token(&graph, sol_idx, 2);
+    assert_eq!(view.nodes, (0..4).collect());
+
+    // Seules les arêtes entre tokens de la vue sont exportées : X -> Y est hors champ à un saut.
+    let json = exported(&graph, &GraphView::around_token(&graph, sol_idx, 1));
+    assert_eq!(json["nodes"].as_array().unwrap().len(), 3);
+    assert_eq!(json["edges"].as_array().unwrap().len(), 4);
+    let y_idx = index(&graph, &y);
+    assert!(json["edges"].as_array().unwrap().iter().all(|edge| edge["source"] != y_idx && edge["destination"] != y_idx));
+}
+
+#[test]
+fn highlighted_cycle_is_marked_in_both_formats() {
+    let (graph, [sol, usdc, x, _]) = market();
+    let (sol_idx, usdc_idx, x_idx) = (index(&graph, &sol), index(&graph, &usdc), index(&graph, &x));
+    let view = GraphView::around_token(&graph, usdc_idx, 0).with_highlighted_cycle(&[sol_idx, usdc_idx, sol_idx]);
+    // Les tokens du cycle rejoignent la vue.
+    assert_eq!(view.nodes, BTreeSet::from([sol_idx, usdc_idx]));
+
+    let json = exported(&graph, &view);
+    assert_eq!(edge(&json, sol_idx, usdc_idx)["highlighted"], true);
+    assert_eq!(edge(&json, usdc_idx, sol_idx)["highlighted"], true);
+
+    // Sur le graphe complet, les arêtes hors du cycle ne sont pas mises en évidence.
+    let view = GraphView::full(&graph).with_highlighted_cycle(&[sol_idx, usdc_idx, sol_idx]);
+    let json = exported(&graph, &view);
+    assert_eq!(edge(&json, sol_idx, x_idx)["highlighted"], false);
+    let highlighted = json["edges"].as_array().unwrap().iter().filter(|edge| edge["highlighted"] == true).count();
+    assert_eq!(highlighted, 2);
+
+    let dot = to_dot(&graph, &TokenRegistry::with_known_tokens(), &view);
+    assert_eq!(dot.matches("color=red").count(), 2);
+    let x_line = dot.lines().find(|line| line.contains(&format!("n{} -> n{} ", sol_idx, x_idx))).unwrap();
+    assert!(!x_line.contains("color=red"));
+}
+
+#[test]
+fn external_symbols_are_escaped_and_huge_decimals_have_no_rate() {
+    let (graph, [sol, _, x, _]) = market();
+    let (sol_idx, x_idx) = (index(&graph, &sol), index(&graph, &x));
+    let mut registry = TokenRegistry::with_known_tokens();
+    // Des métadonnées on-chain arbitraires : guillemet, antislash et retour à la ligne.
+    registry.insert(TokenInfo { mint: x, symbol: "a\"b\\c\nd".to_string(), decimals: 25, usd_price: None });
+
+    let dot = to_dot(&graph, &registry, &GraphView::full(&graph));
+    assert!(dot.contains(&format!("n{} [label=\"a\\\"b\\\\c\\nd\\n", x_idx)), "{}", dot);
+    // Chaque ligne de nœud ou d'arête garde ses guillemets appariés.
+    for line in dot.lines().filter(|line| line.contains("label=")) {
+        let unescaped_quotes = line.replace("\\\\", "").replace("\\\"", "").matches('"').count();
+        assert_eq!(unescaped_quotes, 2, "{}", line);
+    }
+
+    // Une unité de X (10^25) ne tient pas dans un u64 : pas de taux, mais pas de panique.
+    let json: Value = serde_json::from_str(&to_json(&graph, &registry, &GraphView::full(&graph)).unwrap()).unwrap();
+    assert!(edge(&json, x_idx, sol_idx)["rate"].is_null());
+    assert!(edge(&json, sol_idx, x_idx)["rate"].is_number());
+    assert!(dot.contains("rate=n/a"));
+}
@@ -0,0 +1,99 @@
+// tests/lookup_tables.rs
+
+mod common;
+
+use common::{amm_pool, mints, spatial_opportunity};
+use mev_scalpel::decoders::raydium_amm::OpenBookMarketKeys;
+use mev_scalpel::decoders::Pool;
+use mev_scalpel::execution::bundle_builder::{arbitrage_instructions, build_arbitrage_transaction};
+use mev_scalpel::execution::costs::CostModel;
+use mev_scalpel::execution::lookup_tables::{check_transaction_size, LookupTableManager, MAX_ADDRESSES_PER_EXTEND};
+use mev_scalpel::strategies::opportunity::OwnedOpportunity;
+use solana_sdk::hash::Hash;
+use solana_sdk::message::{AddressLookupTableAccount, VersionedMessage};
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+
+const DEPTH: u64 = 1_000_000_000_000;
+
+/// Un pool Raydium AMM v4 hydraté avec les comptes de son marché OpenBook.
+fn amm_pool_with_market(mint_a: Pubkey, mint_b: Pubkey, reserve_a: u64, reserve_b: u64) -> Pool {
+    let Pool::RaydiumAmm(mut pool) = amm_pool(mint_a, mint_b, reserve_a, reserve_b) else { unreachable!() };
+    pool.open_orders = Pubkey::new_unique();
+    pool.target_orders = Pubkey::new_unique();
+    pool.market_id = Pubkey::new_unique();
+    pool.market_program = Pubkey::new_unique();
+    pool.market = Some(OpenBookMarketKeys {
+        bids: Pubkey::new_unique(),
+        asks: Pubkey::new_unique(),
+        event_queue: Pubkey::new_unique(),
+        base_vault: Pubkey::new_unique(),
+        quote_vault: Pubkey::new_unique(),
+        vault_signer: Pubkey::new_unique(),
+    });
+    Pool::RaydiumAmm(pool)
+}
+
+/// Un arbitrage entre deux pools Raydium v4.
+fn two_hop_amm_opportunity() -> OwnedOpportunity {
+    let m = mints(2);
+    spatial_opportunity(
+        vec![amm_pool_with_market(m[0], m[1], DEPTH, DEPTH), amm_pool_with_market(m[0], m[1], DEPTH, DEPTH * 105 / 100)],
+        m[0],
+    )
+}
+
+#[test]
+fn two_raydium_swaps_only_fit_in_a_packet_through_a_lookup_table() {
+    let opportunity = two_hop_amm_opportunity();
+    let wallet = Keypair::new();
+    let costs = CostModel::free();
+
+    let empty = LookupTableManager::default();
+    let oversize = build_arbitrage_transaction(&opportunity, &wallet, &costs, None, None, &empty, Hash::new_unique());
+    assert!(oversize.unwrap_err().to_string().contains("packet limit"));
+
+    // Une table avec tous les comptes des pools, plus une table sans rapport.
+    let instructions = arbitrage_instructions(&opportunity, &wallet.pubkey(), &costs, None, None).unwrap();
+    let missing = empty.missing_accounts(&instructions);
+    assert!(!missing.contains(&wallet.pubkey()));
+    let hot_pools = AddressLookupTableAccount { key: Pubkey::new_unique(), addresses: missing.clone() };
+    let unrelated = AddressLookupTableAccount { key: Pubkey::new_unique(), addresses: vec![Pubkey::new_unique()] };
+    let tables = LookupTableManager::new(vec![unrelated, hot_pools.clone()]);
+    assert!(tables.missing_accounts(&instructions).is_empty());
+    assert_eq!(tables.table_for(&missing[0]), Some(hot_pools.key));
+    assert_eq!(tables.tables_for(&instructions), vec![hot_pools.clone()]);
+
+    let transaction = build_arbitrage_transaction(&opportunity, &wallet, &costs, None, None, &tables, Hash::new_unique()).unwrap();
+    let size = check_transaction_size(&transaction).unwrap();
+    assert!(size <= PACKET_DATA_SIZE, "{} bytes", size);
+    let VersionedMessage::V0(message) = &transaction.message else { panic!("legacy message") };
+    assert_eq!(message.address_table_lookups.len(), 1);
+    assert_eq!(message.address_table_lookups[0].account_key, hot_pools.key);
+}
+
+#[test]
+fn hot_pool_accounts_are_added_in_bounded_chunks() {
+    let (table, authority, payer) = (Pubkey::new_unique(), Pubkey::new_unique(), Pubkey::new_unique());
+    let known = Pubkey::new_unique();
+    let tables = LookupTableManager::new(vec![AddressLookupTableAccount { key: table, addresses: vec![known] }]);
+
+    // Les adresses déjà présentes (ou en double) ne sont pas ajoutées une seconde fois.
+    let fresh: Vec<Pubkey> = (0..MAX_ADDRESSES_PER_EXTEND + 5).map(|_| Pubkey::new_unique()).collect();
+    let mut requested = vec![known, fresh[0]];
+    requested.extend(&fresh);
+    let instructions = tables.extend_table_instructions(&table, &authority, &payer, &requested).unwrap();
+    assert_eq!(instructions.len(), 2);
+    assert!(instructions.iter().all(|ix| ix.accounts[0].pubkey == table && ix.accounts[1].is_signer));
+    assert!(!instructions.iter().any(|ix| ix.data.windows(32).any(|window| window == known.as_ref())));
+
+    // Une table ne dépasse jamais 256 adresses, et on n'étend que nos tables.
+    let too_many: Vec<Pubkey> = (0..256).map(|_| Pubkey::new_unique()).collect();
+    assert!(tables.extend_table_instructions(&table, &authority, &payer, &too_many).is_err());
+    assert!(tables.extend_table_instructions(&Pubkey::new_unique(), &authority, &payer, &fresh).is_err());
+
+    let (create, address) = LookupTableManager::create_table_instruction(&authority, &payer, 1_234);
+    assert_eq!(create.accounts[0].pubkey, address);
+}
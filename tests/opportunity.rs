@@ -0,0 +1,75 @@
+// tests/opportunity.rs
+
+mod common;
+
+use common::{amm_pool, graph_of, mints, whirlpool_pool};
+use mev_scalpel::decoders::{Dex, PoolOperations};
+use mev_scalpel::execution::costs::CostModel;
+use mev_scalpel::execution::optimizer::simulate_path_profit;
+use mev_scalpel::strategies::opportunity::OwnedOpportunity;
+use mev_scalpel::strategies::spatial_arb::find_spatial_opportunities;
+
+const DEPTH: u64 = 1_000_000_000_000;
+const MAX_AMOUNT: u64 = 100_000_000_000;
+
+#[test]
+fn owned_opportunity_outlives_its_graph_and_crosses_threads() {
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH), whirlpool_pool(m[0], m[1], 1.05, DEPTH as u128)]);
+    let opportunity = find_spatial_opportunities(&graph, &[m[0]], MAX_AMOUNT, &CostModel::free()).remove(0);
+    let pool_ids: Vec<_> = opportunity.cycle.pools.iter().map(|&idx| graph.pools[idx].get_id()).collect();
+
+    let owned = OwnedOpportunity::from_opportunity(&graph, &opportunity, 7, 1_234, 100).unwrap();
+    drop(graph);
+    let owned = std::thread::spawn(move || owned).join().unwrap();
+
+    assert_eq!((owned.graph_version, owned.slot), (7, 1_234));
+    assert_eq!(owned.pool_ids(), pool_ids);
+    assert_eq!(owned.start_mint(), Some(m[0]));
+
+    // Whirlpool (m0 -> m1, A vers B), puis AMM (m1 -> m0, B vers A).
+    let (buy, sell) = (&owned.legs[0], &owned.legs[1]);
+    assert_eq!((buy.dex, buy.input_mint, buy.output_mint, buy.a_to_b), (Dex::OrcaWhirlpool, m[0], m[1], true));
+    assert_eq!((sell.dex, sell.input_mint, sell.output_mint, sell.a_to_b), (Dex::RaydiumAmmV4, m[1], m[0], false));
+    // Le second saut engage la sortie cotée du premier, qui doit la rendre en entier.
+    assert_eq!((buy.amount_in, sell.amount_in), (owned.amount_in, buy.expected_out));
+    assert_eq!(buy.min_out, buy.expected_out);
+    assert_eq!(sell.min_out, (sell.expected_out * 99 / 100).max(owned.amount_in));
+
+    // La sortie minimale finale ne descend jamais sous le montant engagé.
+    assert_eq!(owned.expected_out, sell.expected_out);
+    assert_eq!(owned.min_out, sell.min_out);
+    assert!(owned.min_out >= owned.amount_in && owned.min_out <= owned.expected_out);
+
+    // Les pools figés rejouent exactement la cotation de la stratégie et celle de chaque saut.
+    let profit = simulate_path_profit(owned.amount_in, &owned.arbitrage_path()).unwrap();
+    assert_eq!(profit, owned.expected_profit);
+    for leg in &owned.legs {
+        assert_eq!(leg.pool.get_quote(&leg.input_mint, leg.amount_in).unwrap(), leg.expected_out);
+    }
+    // Sans coûts, le profit brut chaîné est exactement celui coté sur le chemin.
+    assert_eq!(owned.expected_gross_profit(), profit);
+}
+
+#[test]
+fn opportunity_from_another_graph_version_is_rejected() {
+    let m = mints(2);
+    let (amm, whirlpool) = (amm_pool(m[0], m[1], DEPTH, DEPTH), whirlpool_pool(m[0], m[1], 1.05, DEPTH as u128));
+    let graph = graph_of(vec![amm.clone(), whirlpool.clone()]);
+    let opportunity = find_spatial_opportunities(&graph, &[m[0]], MAX_AMOUNT, &CostModel::free()).remove(0);
+    assert_eq!(opportunity.pool_ids, vec![whirlpool.get_id(), amm.get_id()]);
+    assert!(OwnedOpportunity::from_opportunity(&graph, &opportunity, 8, 1_235, 100).is_ok());
+
+    // Les mêmes pools dans l'autre ordre : le cycle est toujours valide, mais achèterait sur le mauvais pool.
+    let reordered = graph_of(vec![whirlpool, amm.clone()]);
+    assert!(opportunity.cycle.to_arbitrage_path(&reordered).is_ok());
+    assert!(OwnedOpportunity::from_opportunity(&reordered, &opportunity, 8, 1_235, 100).is_err());
+
+    // Un pool remplacé par un autre de la même paire, à la même place.
+    let replaced = graph_of(vec![amm, whirlpool_pool(m[0], m[1], 1.05, DEPTH as u128)]);
+    assert!(OwnedOpportunity::from_opportunity(&replaced, &opportunity, 8, 1_235, 100).is_err());
+
+    // Le graphe a été élagué : l'index du second pool n'existe plus.
+    let pruned = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH)]);
+    assert!(OwnedOpportunity::from_opportunity(&pruned, &opportunity, 8, 1_235, 100).is_err());
+}
@@ -0,0 +1,141 @@
+// tests/optimizer.rs
+
+mod common;
+
+use common::{amm_pool, graph_of, mints, sqrt_price_x64, whirlpool_pool, whirlpool_pool_with_ticks};
+use mev_scalpel::decoders::clmm_math::{tick_from_sqrt_price, TickLiquidity};
+use mev_scalpel::execution::costs::PathCosts;
+use mev_scalpel::execution::optimizer::{
+    closed_form_optimal_amount, find_optimal_amount, optimize_path, simulate_path_profit,
+    ternary_search_optimal_amount, OptimizationOutcome,
+};
+use mev_scalpel::state::MarketGraph;
+use mev_scalpel::strategies::Cycle;
+
+const DEPTH: u64 = 1_000_000_000_000;
+const MAX_AMOUNT: u64 = 1_000_000_000_000;
+
+fn two_hop(pools: [usize; 2]) -> Cycle {
+    Cycle { tokens: vec![0, 1, 0], pools: pools.to_vec() }
+}
+
+/// L'optimum analytique doit faire au moins aussi bien que la recherche ternaire,
+/// et tomber (à l'arrondi près) sur le même montant.
+fn assert_matches_ternary(graph: &MarketGraph, cycle: &Cycle) {
+    let path = cycle.to_arbitrage_path(graph).unwrap();
+    let (closed_amount, closed_profit) = find_optimal_amount(&path, MAX_AMOUNT, &PathCosts::default()).unwrap();
+    let (ternary_amount, ternary_profit) = ternary_search_optimal_amount(&path, MAX_AMOUNT).unwrap();
+
+    assert!(closed_profit > 0);
+    assert!(closed_profit >= ternary_profit - 1, "closed form {} < ternary {}", closed_profit, ternary_profit);
+    let gap = (closed_amount as f64 - ternary_amount as f64).abs() / ternary_amount as f64;
+    assert!(gap < 1e-3, "closed form {} vs ternary {}", closed_amount, ternary_amount);
+
+    // Autour de l'optimum, le profit ne fait que baisser.
+    for amount in [closed_amount * 99 / 100, closed_amount * 101 / 100] {
+        assert!(simulate_path_profit(amount, &path).unwrap() <= closed_profit);
+    }
+}
+
+#[test]
+fn two_hop_constant_product_cycle_matches_ternary_search() {
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH), amm_pool(m[0], m[1], DEPTH, DEPTH * 105 / 100)]);
+    assert_matches_ternary(&graph, &two_hop([1, 0]));
+}
+
+#[test]
+fn three_hop_constant_product_cycle_matches_ternary_search() {
+    let m = mints(3);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, 3 * DEPTH),
+        amm_pool(m[1], m[2], 5 * DEPTH, DEPTH / 2),
+        // Le token 2 vaut ici 5 % de plus en token 0 qu'ailleurs, sur un pool moins profond.
+        amm_pool(m[2], m[0], DEPTH / 10, DEPTH * 105 / 300),
+    ]);
+    let cycle = Cycle { tokens: vec![0, 1, 2, 0], pools: vec![0, 1, 2] };
+    assert_matches_ternary(&graph, &cycle);
+}
+
+#[test]
+fn unprofitable_and_bounded_constant_product_paths() {
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH), amm_pool(m[0], m[1], DEPTH, DEPTH * 105 / 100)]);
+
+    // Dans le mauvais sens, aucun montant n'est rentable.
+    let path = two_hop([0, 1]).to_arbitrage_path(&graph).unwrap();
+    assert_eq!(closed_form_optimal_amount(&path, MAX_AMOUNT), Some(0));
+    assert_eq!(find_optimal_amount(&path, MAX_AMOUNT, &PathCosts::default()).unwrap(), (0, 0));
+
+    // Si l'optimum dépasse le plafond, le plafond est retenu.
+    let path = two_hop([1, 0]).to_arbitrage_path(&graph).unwrap();
+    assert_eq!(closed_form_optimal_amount(&path, 1_000_000).unwrap(), 1_000_000);
+}
+
+#[test]
+fn mixed_paths_fall_back_to_numeric_search() {
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH), whirlpool_pool(m[0], m[1], 1.05, DEPTH as u128)]);
+    let path = two_hop([1, 0]).to_arbitrage_path(&graph).unwrap();
+
+    assert_eq!(closed_form_optimal_amount(&path, MAX_AMOUNT), None);
+    assert_eq!(
+        find_optimal_amount(&path, MAX_AMOUNT, &PathCosts::default()).unwrap(),
+        ternary_search_optimal_amount(&path, MAX_AMOUNT).unwrap()
+    );
+}
+
+#[test]
+fn tick_crossings_split_the_search_and_bound_the_feasible_size() {
+    let m = mints(2);
+    let tick = tick_from_sqrt_price(sqrt_price_x64(1.05));
+    let liquidity = DEPTH as u128;
+    // 1 % sous le prix courant, la liquidité chute de 90 % ; 3 % plus bas, il n'y en a plus.
+    let ticks = vec![
+        TickLiquidity { tick_index: tick - 300, liquidity_net: (liquidity / 10) as i128 },
+        TickLiquidity { tick_index: tick - 100, liquidity_net: (liquidity * 9 / 10) as i128 },
+    ];
+    let graph = graph_of(vec![
+        whirlpool_pool_with_ticks(m[0], m[1], 1.05, liquidity, ticks),
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+    ]);
+    let path = two_hop([0, 1]).to_arbitrage_path(&graph).unwrap();
+
+    let report = optimize_path(&path, MAX_AMOUNT, &PathCosts::default()).unwrap();
+    assert!(!report.breakpoints.is_empty());
+    assert!(report.max_feasible_amount > 0 && report.max_feasible_amount < MAX_AMOUNT);
+    assert!(simulate_path_profit(report.max_feasible_amount + 1, &path).is_err());
+    assert!(report.samples.windows(2).all(|pair| pair[0].amount_in < pair[1].amount_in));
+
+    // Aucun point d'une grille fine ne fait mieux que l'optimum trouvé.
+    let (amount_in, profit) = report.profitable().unwrap();
+    assert!(amount_in <= report.max_feasible_amount);
+    let steps = 400;
+    let grid_best = (1..=steps)
+        .map(|i| simulate_path_profit(report.max_feasible_amount / steps * i, &path).unwrap())
+        .max()
+        .unwrap();
+    assert!(profit >= grid_best, "optimizer {} < grid {}", profit, grid_best);
+}
+
+#[test]
+fn losses_are_reported_and_tiny_ranges_do_not_underflow() {
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH), whirlpool_pool(m[0], m[1], 1.0, DEPTH as u128)]);
+    let path = two_hop([1, 0]).to_arbitrage_path(&graph).unwrap();
+
+    for max_amount in 0..5 {
+        let (_, profit) = ternary_search_optimal_amount(&path, max_amount).unwrap();
+        assert!(profit <= 0);
+    }
+
+    let report = optimize_path(&path, MAX_AMOUNT, &PathCosts::default()).unwrap();
+    match report.outcome {
+        OptimizationOutcome::NoProfitableSize { best_amount, best_profit } => {
+            assert!(best_amount > 0 && best_profit < 0);
+            assert!(report.samples.iter().all(|sample| sample.net_profit <= best_profit));
+        }
+        other => panic!("unexpected outcome {:?}", other),
+    }
+    assert_eq!(find_optimal_amount(&path, MAX_AMOUNT, &PathCosts::default()).unwrap(), (0, 0));
+}
@@ -0,0 +1,164 @@
+// tests/simulation.rs
+
+mod common;
+
+use common::{clmm_whirlpool_opportunity, mints, token_account, ui_account};
+use mev_scalpel::execution::bundle_builder::build_arbitrage_transaction;
+use mev_scalpel::execution::bundle_sender::JITO_TIP_ACCOUNTS;
+use mev_scalpel::execution::costs::{CostModel, LAMPORTS_PER_SIGNATURE, SOL_MINT, TOKEN_ACCOUNT_RENT_LAMPORTS};
+use mev_scalpel::execution::lookup_tables::LookupTableManager;
+use mev_scalpel::execution::optimizer::simulate_path_profit;
+use mev_scalpel::execution::simulation::{
+    retain_simulated, simulate_candidate, transaction_fee, SimulationPolicy, SimulationVerdict,
+};
+use mev_scalpel::strategies::opportunity::OwnedOpportunity;
+use serde_json::{json, Value};
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_request::RpcRequest;
+use solana_sdk::hash::Hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_rpc_client::mock_sender::MocksMap;
+
+const SOL: u64 = 1_000_000_000;
+
+fn transaction_for(opportunity: &OwnedOpportunity, wallet: &Keypair) -> VersionedTransaction {
+    let tables = LookupTableManager::default();
+    build_arbitrage_transaction(opportunity, wallet, &CostModel::free(), None, None, &tables, Hash::new_unique()).unwrap()
+}
+
+fn system_account(lamports: u64) -> Value {
+    ui_account(lamports, &Pubkey::default(), &[])
+}
+
+/// Les réponses du RPC pour une simulation : comptes avant, puis résultat de la simulation,
+/// aux slots donnés.
+fn simulation_mocks_at(slots: (u64, u64), pre: Vec<Value>, post: Vec<Value>, err: Value) -> Vec<(RpcRequest, Value)> {
+    vec![
+        (RpcRequest::GetMultipleAccounts, json!({ "context": { "slot": slots.0 }, "value": pre })),
+        (
+            RpcRequest::SimulateTransaction,
+            json!({
+                "context": { "slot": slots.1 },
+                "value": { "err": err, "logs": ["Program log: swap"], "accounts": post, "unitsConsumed": 150_000 },
+            }),
+        ),
+    ]
+}
+
+fn simulation_mocks(pre: Vec<Value>, post: Vec<Value>, err: Value) -> Vec<(RpcRequest, Value)> {
+    simulation_mocks_at((1, 1), pre, post, err)
+}
+
+fn mock_rpc(mocks: Vec<(RpcRequest, Value)>) -> RpcClient {
+    RpcClient::new_mock_with_mocks_map("succeeds", mocks.into_iter().collect::<MocksMap>())
+}
+
+#[test]
+fn token_cycle_profit_is_read_from_the_start_account() {
+    let m = mints(2);
+    let opportunity = clmm_whirlpool_opportunity(m[0], m[1]);
+    let wallet = Keypair::new();
+    let transaction = transaction_for(&opportunity, &wallet);
+    // Le profit de référence est celui coté sur le chemin de l'opportunité.
+    let expected = simulate_path_profit(opportunity.amount_in, &opportunity.arbitrage_path()).unwrap();
+    assert!(expected > 100);
+    let before = 50 * SOL;
+    let simulate = |after: u64, err: Value, policy: SimulationPolicy| {
+        let rpc = mock_rpc(simulation_mocks(vec![token_account(before)], vec![token_account(after)], err));
+        simulate_candidate(&rpc, &transaction, &opportunity, &wallet.pubkey(), &policy).unwrap()
+    };
+    let policy = SimulationPolicy::default();
+
+    let report = simulate(before + expected as u64, Value::Null, policy);
+    assert_eq!(report.verdict, SimulationVerdict::Accepted);
+    assert_eq!((report.expected_profit, report.realized_profit), (expected, Some(expected)));
+    assert_eq!((report.units_consumed, report.logs.len()), (Some(150_000), 1));
+
+    // Les pools ont bougé : la transaction rapporte moitié moins que prévu.
+    let report = simulate(before + expected as u64 / 2, Value::Null, policy);
+    assert_eq!(report.verdict, SimulationVerdict::Diverged);
+    // Dans la tolérance, mais sous le seuil de profit.
+    let report = simulate(before + expected as u64, Value::Null, policy.with_min_profit(expected + 1));
+    assert_eq!(report.verdict, SimulationVerdict::BelowThreshold);
+    // La garde de sortie minimale a fait échouer la transaction.
+    let err = json!({ "InstructionError": [3, { "Custom": 6022 }] });
+    let report = simulate(before, err, policy);
+    assert!(matches!(report.verdict, SimulationVerdict::Failed(_)));
+    assert_eq!(report.realized_profit, None);
+}
+
+#[test]
+fn sol_cycle_profit_counts_lamports_and_adds_back_the_fee_and_tip() {
+    let other = mints(1)[0];
+    let opportunity = clmm_whirlpool_opportunity(SOL_MINT, other);
+    let wallet = Keypair::new();
+    let costs = CostModel::default();
+    let tables = LookupTableManager::default();
+    let tip_account = JITO_TIP_ACCOUNTS[0];
+    let transaction =
+        build_arbitrage_transaction(&opportunity, &wallet, &costs, None, Some(&tip_account), &tables, Hash::new_unique()).unwrap();
+    let expected = opportunity.expected_gross_profit();
+    let tip = costs.tip_lamports(&opportunity.arbitrage_path(), expected, None).unwrap();
+    // Les frais lus dans le message sont ceux du modèle : une signature et la limite de compute units à son prix.
+    let fee = transaction_fee(&transaction.message);
+    assert_eq!(fee, costs.fixed_cost_lamports(&opportunity.arbitrage_path()));
+    assert!(fee > LAMPORTS_PER_SIGNATURE);
+
+    // Le wallet, son ATA WSOL (fermé à la fin) et l'ATA intermédiaire (créé, son loyer reste au wallet).
+    let before = 500 * SOL;
+    let after = before + expected as u64 - fee - tip - TOKEN_ACCOUNT_RENT_LAMPORTS;
+    let pre = vec![system_account(before), Value::Null, Value::Null];
+    let post = vec![system_account(after), Value::Null, token_account(0)];
+    let rpc = mock_rpc(simulation_mocks(pre, post, Value::Null));
+    let report = simulate_candidate(&rpc, &transaction, &opportunity, &wallet.pubkey(), &SimulationPolicy::default()).unwrap();
+    assert_eq!(report.realized_profit, Some(expected));
+    assert!(report.is_accepted());
+}
+
+#[test]
+fn only_accepted_candidates_are_kept() {
+    let m = mints(2);
+    let wallet = Keypair::new();
+    let winner = clmm_whirlpool_opportunity(m[0], m[1]);
+    let loser = clmm_whirlpool_opportunity(m[0], m[1]);
+    let expected = winner.expected_gross_profit() as u64;
+
+    let mut mocks = simulation_mocks(vec![token_account(SOL)], vec![token_account(SOL + expected)], Value::Null);
+    mocks.extend(simulation_mocks(vec![token_account(SOL)], vec![token_account(SOL)], json!("AccountNotFound")));
+    let rpc = mock_rpc(mocks);
+    let candidates = vec![
+        (winner.clone(), transaction_for(&winner, &wallet)),
+        (loser.clone(), transaction_for(&loser, &wallet)),
+    ];
+    let kept = retain_simulated(&rpc, candidates, &wallet.pubkey(), &SimulationPolicy::default());
+    assert_eq!(kept.len(), 1);
+    assert_eq!(kept[0].0.pool_ids(), winner.pool_ids());
+    assert_eq!(kept[0].2.realized_profit, Some(expected as i64));
+}
+
+#[test]
+fn simulation_may_run_a_few_slots_after_the_balances() {
+    let m = mints(2);
+    let opportunity = clmm_whirlpool_opportunity(m[0], m[1]);
+    let wallet = Keypair::new();
+    let transaction = transaction_for(&opportunity, &wallet);
+    let expected = opportunity.expected_gross_profit() as u64;
+    let (pre, post) = (vec![token_account(SOL)], vec![token_account(SOL + expected)]);
+    let simulate = |mocks| simulate_candidate(&mock_rpc(mocks), &transaction, &opportunity, &wallet.pubkey(), &SimulationPolicy::default());
+
+    // Le RPC a avancé de quelques slots entre les deux appels : le gain est mesuré tel quel.
+    let report = simulate(simulation_mocks_at((10, 14), pre.clone(), post.clone(), Value::Null)).unwrap();
+    assert_eq!(report.realized_profit, Some(expected as i64));
+
+    // Trop loin : on relit les soldes et on resimule.
+    let mut mocks = simulation_mocks_at((10, 30), pre.clone(), post.clone(), Value::Null);
+    mocks.extend(simulation_mocks_at((31, 32), pre.clone(), post.clone(), Value::Null));
+    assert_eq!(simulate(mocks).unwrap().realized_profit, Some(expected as i64));
+
+    // Toujours trop loin : pas de verdict.
+    let mocks = (0..3).flat_map(|i| simulation_mocks_at((i * 100, i * 100 + 50), pre.clone(), post.clone(), Value::Null)).collect();
+    assert!(simulate(mocks).is_err());
+}
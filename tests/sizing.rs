@@ -0,0 +1,81 @@
+// tests/sizing.rs
+
+mod common;
+
+use common::{amm_pool, graph_of, mints};
+use mev_scalpel::execution::costs::CostModel;
+use mev_scalpel::strategies::sizing::{reference_sizes, rescore_at_optimal_size, EdgeSizing};
+use mev_scalpel::strategies::spfa_arb::find_negative_cycle_sized;
+use mev_scalpel::strategies::Cycle;
+use mev_scalpel::token_registry::{TokenInfo, TokenRegistry};
+use solana_sdk::pubkey::Pubkey;
+
+const DEPTH: u64 = 1_000_000_000_000;
+
+/// Un annuaire où `mint` est un stablecoin à 6 décimales.
+fn registry_with_stable(mint: Pubkey) -> TokenRegistry {
+    let mut registry = TokenRegistry::default();
+    registry.insert(TokenInfo { mint, symbol: "STABLE".to_string(), decimals: 6, usd_price: Some(1.0) });
+    registry
+}
+
+#[test]
+fn reference_sizes_propagate_from_priced_tokens() {
+    let m = mints(4);
+    // 1 token 0 = 2 token 1 = 8 token 2 ; le token 3 n'est relié à rien.
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, 2 * DEPTH),
+        amm_pool(m[1], m[2], DEPTH, 4 * DEPTH),
+        amm_pool(m[3], Pubkey::new_unique(), DEPTH, DEPTH),
+    ]);
+    let registry = registry_with_stable(m[0]);
+
+    let sizes = reference_sizes(&graph, &registry, 1_000.0);
+    assert_eq!(sizes[0], Some(1_000_000_000));
+    // Les conversions passent par des pools à 0.25 % de frais.
+    let close = |size: Option<u64>, expected: f64| (size.unwrap() as f64 / expected - 1.0).abs() < 0.01;
+    assert!(close(sizes[1], 2e9));
+    assert!(close(sizes[2], 8e9));
+    assert_eq!(sizes[graph.token_map[&m[3]]], None);
+
+    assert_eq!(EdgeSizing::PerToken { sizes, fallback: 42 }.amount_for(graph.token_map[&m[3]]), 42);
+}
+
+#[test]
+fn shallow_pools_stop_looking_profitable_at_reference_size() {
+    let m = mints(2);
+    // Un pool profond au prix du marché, et un pool minuscule où le token 1 est 10 % moins cher.
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[0], m[1], 1_000_000, 1_100_000),
+    ]);
+    let start = graph.token_map[&m[0]];
+
+    let marginal = find_negative_cycle_sized(&graph, start, &EdgeSizing::Fixed(1_000));
+    assert_eq!(marginal.expect("cheap at the margin").pools, vec![1, 0]);
+
+    let registry = registry_with_stable(m[0]);
+    let sized = EdgeSizing::PerToken { sizes: reference_sizes(&graph, &registry, 1_000.0), fallback: 1_000 };
+    assert_eq!(find_negative_cycle_sized(&graph, start, &sized), None);
+}
+
+#[test]
+fn rescoring_sizes_cycles_and_drops_the_unprofitable_ones() {
+    let m = mints(2);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[0], m[1], DEPTH, DEPTH * 105 / 100),
+        amm_pool(m[0], m[1], DEPTH, DEPTH * 102 / 100),
+    ]);
+    let cycle = |pools: Vec<usize>| Cycle { tokens: vec![0, 1, 0], pools };
+
+    let opportunities = rescore_at_optimal_size(
+        &graph,
+        vec![cycle(vec![2, 0]), cycle(vec![0, 1]), cycle(vec![1, 0])],
+        100_000_000_000,
+        &CostModel::free(),
+    );
+    let pools: Vec<Vec<usize>> = opportunities.iter().map(|o| o.cycle.pools.clone()).collect();
+    assert_eq!(pools, vec![vec![1, 0], vec![2, 0]]);
+    assert!(opportunities[0].expected_profit > opportunities[1].expected_profit);
+}
@@ -0,0 +1,170 @@
+// tests/snapshot.rs
+
+mod common;
+
+use common::{amm_pool, clmm_pool, graph_of, mints, whirlpool_pool_with_ticks};
+use mev_scalpel::decoders::clmm_math::TickLiquidity;
+use mev_scalpel::decoders::raydium_amm::OpenBookMarketKeys;
+use mev_scalpel::decoders::{Pool, PoolOperations};
+use mev_scalpel::execution::costs::PathCosts;
+use mev_scalpel::execution::optimizer::optimize_path;
+use mev_scalpel::snapshot::{load_graph, save_graph, SnapshotFormat};
+use mev_scalpel::state::MarketGraph;
+use mev_scalpel::strategies::sizing::EdgeSizing;
+use mev_scalpel::strategies::spfa_arb::{find_negative_cycles, TEST_SWAP_AMOUNT};
+use solana_sdk::pubkey::Pubkey;
+use std::path::Path;
+
+const DEPTH: u64 = 1_000_000_000_000;
+const LIQUIDITY: u128 = 1_000_000_000_000;
+
+/// Un graphe qui contient les trois variantes de pool, ticks CLMM et marché OpenBook compris,
+/// et au moins un cycle rentable.
+fn market() -> (MarketGraph, Vec<Pubkey>) {
+    let m = mints(3);
+    let ticks = vec![
+        TickLiquidity { tick_index: -6400, liquidity_net: 500_000_000 },
+        TickLiquidity { tick_index: 6400, liquidity_net: -500_000_000 },
+    ];
+    let mut clmm = clmm_pool(m[1], m[2], 1.0, LIQUIDITY);
+    if let Pool::RaydiumClmm(pool) = &mut clmm {
+        pool.ticks = vec![
+            TickLiquidity { tick_index: -100, liquidity_net: 1_000_000 },
+            TickLiquidity { tick_index: 100, liquidity_net: -1_000_000 },
+        ];
+    }
+    let mut amm = amm_pool(m[0], m[1], DEPTH, DEPTH);
+    if let Pool::RaydiumAmm(pool) = &mut amm {
+        pool.market_id = Pubkey::new_unique();
+        pool.market = Some(OpenBookMarketKeys {
+            bids: Pubkey::new_unique(),
+            asks: Pubkey::new_unique(),
+            event_queue: Pubkey::new_unique(),
+            base_vault: Pubkey::new_unique(),
+            quote_vault: Pubkey::new_unique(),
+            vault_signer: Pubkey::new_unique(),
+        });
+    }
+    let graph = graph_of(vec![
+        amm,
+        clmm,
+        // Le token 2 vaut 10 % de plus en token 0 ici qu'ailleurs.
+        whirlpool_pool_with_ticks(m[2], m[0], 1.1, LIQUIDITY, ticks),
+    ]);
+    (graph, m)
+}
+
+/// Les pools n'implémentent pas `PartialEq` : on compare leur encodage binaire.
+fn pool_bytes(graph: &MarketGraph) -> Vec<Vec<u8>> {
+    graph.pools.iter().map(|pool| bincode::serialize(pool).unwrap()).collect()
+}
+
+fn assert_same_graph(original: &MarketGraph, reloaded: &MarketGraph) {
+    assert_eq!(reloaded.tokens, original.tokens);
+    assert_eq!(reloaded.token_map, original.token_map);
+    assert_eq!(reloaded.pool_map, original.pool_map);
+    assert_eq!(pool_bytes(reloaded), pool_bytes(original));
+    let edges = |graph: &MarketGraph| -> Vec<Vec<(usize, usize)>> {
+        graph.nodes.iter().map(|edges| edges.iter().map(|edge| (edge.destination, edge.pool_index)).collect()).collect()
+    };
+    assert_eq!(edges(reloaded), edges(original));
+}
+
+/// Les cycles trouvés et leur optimisation, sous une forme comparable.
+fn analysis(graph: &MarketGraph, base: &[Pubkey]) -> Vec<String> {
+    let cycles = find_negative_cycles(graph, base, &EdgeSizing::Fixed(TEST_SWAP_AMOUNT), 1);
+    assert!(!cycles.is_empty());
+    cycles
+        .iter()
+        .map(|cycle| {
+            let path = cycle.to_arbitrage_path(graph).unwrap();
+            let report = optimize_path(&path, 100_000_000_000, &PathCosts::default()).unwrap();
+            format!("{:?} {:?}", cycle, report)
+        })
+        .collect()
+}
+
+#[test]
+fn binary_and_json_round_trips_keep_every_pool_variant() {
+    let (graph, _) = market();
+    assert!(graph.pools.iter().any(|pool| matches!(pool, Pool::RaydiumAmm(amm) if amm.market.is_some())));
+    assert!(graph.pools.iter().any(|pool| matches!(pool, Pool::RaydiumClmm(clmm) if !clmm.ticks.is_empty())));
+    assert!(graph.pools.iter().any(|pool| matches!(pool, Pool::OrcaWhirlpool(whirlpool) if !whirlpool.ticks.is_empty())));
+
+    let from_bytes = MarketGraph::from_bytes(&graph.to_bytes().unwrap()).unwrap();
+    assert_same_graph(&graph, &from_bytes);
+    let json = graph.to_json().unwrap();
+    let from_json = MarketGraph::from_json(&json).unwrap();
+    assert_same_graph(&graph, &from_json);
+    // Le JSON est lisible : les clés y sont en base58.
+    assert!(json.contains(&graph.pools[0].get_id().to_string()));
+}
+
+#[test]
+fn reloaded_graph_gives_the_same_cycles_and_sizes() {
+    let (graph, m) = market();
+    let expected = analysis(&graph, &m[..1]);
+
+    let dir = std::env::temp_dir().join(format!("mev_scalpel_snapshot_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    for name in ["graph.bin", "graph.json"] {
+        let path = dir.join(name);
+        save_graph(&graph, &path).unwrap();
+        let reloaded = load_graph(&path).unwrap();
+        assert_same_graph(&graph, &reloaded);
+        assert_eq!(analysis(&reloaded, &m[..1]), expected, "{}", name);
+    }
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn format_follows_the_extension_and_corrupt_snapshots_are_rejected() {
+    assert_eq!(SnapshotFormat::from_path(Path::new("dump.json")), SnapshotFormat::Json);
+    assert_eq!(SnapshotFormat::from_path(Path::new("dump.bin")), SnapshotFormat::Binary);
+
+    let (graph, _) = market();
+    let mut json: serde_json::Value = serde_json::from_str(&graph.to_json().unwrap()).unwrap();
+    // Une arête qui pointe vers un pool inexistant.
+    json["nodes"][0][0]["pool_index"] = serde_json::json!(99);
+    assert!(MarketGraph::from_json(&json.to_string()).is_err());
+    let bytes = graph.to_bytes().unwrap();
+    assert!(MarketGraph::from_bytes(&bytes[..bytes.len() / 2]).is_err());
+}
+
+#[test]
+fn inconsistent_snapshots_are_rejected() {
+    let (graph, _) = market();
+    // La sérialisation ne vérifie rien : on corrompt le graphe, puis on le recharge.
+    let reload = |graph: &MarketGraph| {
+        let from_json = MarketGraph::from_json(&graph.to_json().unwrap()).map(|_| ()).map_err(|e| e.to_string());
+        let from_bytes = MarketGraph::from_bytes(&graph.to_bytes().unwrap()).map(|_| ()).map_err(|e| e.to_string());
+        assert_eq!(from_json.is_ok(), from_bytes.is_ok());
+        from_json
+    };
+    assert!(reload(&graph).is_ok());
+
+    // Le même mint à deux index : l'annuaire écraserait l'un par l'autre.
+    let mut duplicate_token = graph.clone();
+    duplicate_token.tokens[1] = duplicate_token.tokens[0];
+    assert!(reload(&duplicate_token).unwrap_err().contains("token mint twice"));
+
+    // Deux pools avec le même identifiant.
+    let mut duplicate_pool = graph.clone();
+    let first_id = graph.pools[0].get_id();
+    if let Pool::RaydiumClmm(pool) = &mut duplicate_pool.pools[1] {
+        pool.id = first_id;
+    }
+    assert!(reload(&duplicate_pool).unwrap_err().contains("pool id twice"));
+
+    // Une arête rattachée à un pool qui ne relie pas ses deux tokens.
+    let mut wrong_pool = graph.clone();
+    let ends = [graph.tokens[0], graph.tokens[graph.nodes[0][0].destination]];
+    let other_pool = (0..graph.pools.len())
+        .find(|&idx| {
+            let (mint_a, mint_b) = graph.pools[idx].get_mints();
+            !(ends.contains(&mint_a) && ends.contains(&mint_b))
+        })
+        .unwrap();
+    wrong_pool.nodes[0][0].pool_index = other_pool;
+    assert!(reload(&wrong_pool).unwrap_err().contains("does not match the mints"));
+}
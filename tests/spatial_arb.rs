@@ -0,0 +1,87 @@
+// tests/spatial_arb.rs
+
+mod common;
+
+use common::{amm_pool, clmm_pool, graph_of, mints, whirlpool_pool};
+use mev_scalpel::decoders::PoolOperations;
+use mev_scalpel::execution::costs::CostModel;
+use mev_scalpel::state::GraphUpdate;
+use mev_scalpel::strategies::spatial_arb::{find_spatial_opportunities, pools_by_pair, SpatialStrategy};
+use mev_scalpel::strategies::Strategy;
+use solana_sdk::pubkey::Pubkey;
+
+const DEPTH: u64 = 1_000_000_000_000;
+const MAX_AMOUNT: u64 = 100_000_000_000;
+
+#[test]
+fn price_gap_between_amm_and_whirlpool_is_arbitraged() {
+    let m = mints(2);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        whirlpool_pool(m[0], m[1], 1.05, DEPTH as u128),
+    ]);
+
+    let opportunities = find_spatial_opportunities(&graph, &[m[0]], MAX_AMOUNT, &CostModel::free());
+    assert_eq!(opportunities.len(), 1);
+    let opportunity = &opportunities[0];
+    // On achète le token 1 là où il est le moins cher (le Whirlpool), puis on le revend sur l'AMM.
+    assert_eq!(opportunity.cycle.pools, vec![1, 0]);
+    assert!(opportunity.expected_profit > 0);
+    assert!(opportunity.amount_in > 0 && opportunity.amount_in <= MAX_AMOUNT);
+}
+
+#[test]
+fn aligned_prices_yield_no_opportunity() {
+    let m = mints(2);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        clmm_pool(m[0], m[1], 1.0, DEPTH as u128),
+        whirlpool_pool(m[0], m[1], 1.0, DEPTH as u128),
+    ]);
+
+    assert_eq!(pools_by_pair(&graph).len(), 1);
+    assert!(find_spatial_opportunities(&graph, &[m[0]], MAX_AMOUNT, &CostModel::free()).is_empty());
+}
+
+#[test]
+fn pairs_without_base_token_are_ignored() {
+    let m = mints(3);
+    let graph = graph_of(vec![
+        amm_pool(m[1], m[2], DEPTH, DEPTH),
+        whirlpool_pool(m[1], m[2], 1.05, DEPTH as u128),
+    ]);
+    assert!(find_spatial_opportunities(&graph, &[m[0]], MAX_AMOUNT, &CostModel::free()).is_empty());
+}
+
+#[test]
+fn strategy_only_sizes_the_pairs_of_changed_pools() {
+    let m = mints(3);
+    // Deux paires décalées, chacune cotée par deux pools.
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        whirlpool_pool(m[0], m[1], 1.05, DEPTH as u128),
+        amm_pool(m[0], m[2], DEPTH, DEPTH),
+        whirlpool_pool(m[0], m[2], 1.05, DEPTH as u128),
+    ]);
+    assert_eq!(find_spatial_opportunities(&graph, &[m[0]], MAX_AMOUNT, &CostModel::free()).len(), 2);
+    let mut strategy = SpatialStrategy::new(&[m[0]], MAX_AMOUNT);
+    let update = |changed_pools| GraphUpdate { version: 1, slot: 1, changed_pools };
+
+    let opportunities = strategy.on_update(&graph, &update(vec![graph.pools[3].get_id()]));
+    assert_eq!(opportunities.len(), 1);
+    assert_eq!(opportunities[0].cycle.pools, vec![3, 2]);
+
+    // Un pool inconnu du graphe, ou aucun pool modifié : rien à dimensionner.
+    assert!(strategy.on_update(&graph, &update(vec![Pubkey::new_unique()])).is_empty());
+    assert!(strategy.on_update(&graph, &update(Vec::new())).is_empty());
+}
+
+#[test]
+fn two_pools_of_the_same_dex_are_arbitraged() {
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH), amm_pool(m[0], m[1], DEPTH, DEPTH * 21 / 20)]);
+    let opportunities = find_spatial_opportunities(&graph, &[m[0]], MAX_AMOUNT, &CostModel::free());
+    assert_eq!(opportunities.len(), 1);
+    // Le token 1 est moins cher dans le second pool, qui en a davantage.
+    assert_eq!(opportunities[0].cycle.pools, vec![1, 0]);
+}
@@ -0,0 +1,183 @@
+// tests/spfa_arb.rs
+
+mod common;
+
+use common::{amm_pool, clmm_pool, graph_of, mints, whirlpool_pool};
+use mev_scalpel::decoders::{Dex, Pool, PoolOperations};
+use mev_scalpel::execution::optimizer::simulate_path_profit;
+use mev_scalpel::state::MarketGraph;
+use mev_scalpel::strategies::sizing::EdgeSizing;
+use mev_scalpel::strategies::spfa_arb::{find_negative_cycle, find_negative_cycles, TEST_SWAP_AMOUNT};
+use mev_scalpel::strategies::Cycle;
+use solana_sdk::pubkey::Pubkey;
+
+const DEPTH: u64 = 1_000_000_000_000;
+
+/// Vérifie qu'un cycle est fermé, cohérent avec le graphe et réellement rentable.
+fn assert_profitable_cycle(graph: &MarketGraph, cycle: &Cycle) {
+    assert_eq!(cycle.tokens.first(), cycle.tokens.last());
+    assert_eq!(cycle.tokens.len(), cycle.pools.len() + 1);
+    let path = cycle.to_arbitrage_path(graph).expect("cycle should map onto the graph");
+    let profit = simulate_path_profit(1_000_000, &path).unwrap();
+    assert!(profit > 0, "cycle should be profitable, got {}", profit);
+}
+
+#[test]
+fn empty_graph_has_no_cycle() {
+    let graph = MarketGraph::default();
+    assert_eq!(find_negative_cycle(&graph, 0), None);
+}
+
+#[test]
+fn fairly_priced_triangle_has_no_cycle() {
+    let m = mints(3);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[1], m[2], DEPTH, 2 * DEPTH),
+        amm_pool(m[2], m[0], 2 * DEPTH, DEPTH),
+    ]);
+    assert_eq!(find_negative_cycle(&graph, 0), None);
+}
+
+#[test]
+fn mispriced_triangle_is_found_from_start() {
+    let m = mints(3);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[1], m[2], DEPTH, DEPTH),
+        // Le token 2 vaut 10 % de plus en token 0 ici qu'ailleurs.
+        amm_pool(m[2], m[0], DEPTH, DEPTH * 11 / 10),
+    ]);
+    let start = graph.token_map[&m[0]];
+
+    let cycle = find_negative_cycle(&graph, start).expect("a negative cycle exists");
+    assert_eq!(cycle.hops(), 3);
+    assert_eq!(cycle.tokens[0], start);
+    assert_profitable_cycle(&graph, &cycle);
+}
+
+#[test]
+fn two_pools_on_the_same_pair_form_a_two_hop_cycle() {
+    let m = mints(2);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[0], m[1], DEPTH, DEPTH * 105 / 100),
+    ]);
+
+    let cycle = find_negative_cycle(&graph, 0).expect("a negative cycle exists");
+    assert_eq!(cycle.hops(), 2);
+    assert_ne!(cycle.pools[0], cycle.pools[1], "the cycle must use both pools");
+    assert_profitable_cycle(&graph, &cycle);
+}
+
+#[test]
+fn unreachable_cycle_is_not_reported() {
+    let m = mints(5);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        // Triangle mal pricé, mais dans une autre composante.
+        amm_pool(m[2], m[3], DEPTH, DEPTH),
+        amm_pool(m[3], m[4], DEPTH, DEPTH),
+        amm_pool(m[4], m[2], DEPTH, DEPTH * 11 / 10),
+    ]);
+    assert_eq!(find_negative_cycle(&graph, graph.token_map[&m[0]]), None);
+}
+
+#[test]
+fn reachable_cycle_not_through_start_is_extracted() {
+    let m = mints(4);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[1], m[2], DEPTH, DEPTH),
+        amm_pool(m[2], m[3], DEPTH, DEPTH),
+        amm_pool(m[3], m[1], DEPTH, DEPTH * 11 / 10),
+    ]);
+    let start = graph.token_map[&m[0]];
+
+    let cycle = find_negative_cycle(&graph, start).expect("a negative cycle is reachable");
+    assert!(!cycle.tokens.contains(&start));
+    assert_eq!(cycle.hops(), 3);
+    assert_profitable_cycle(&graph, &cycle);
+}
+
+#[test]
+fn pools_without_liquidity_are_ignored() {
+    let m = mints(2);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[0], m[1], 0, 0),
+    ]);
+    assert_eq!(find_negative_cycle(&graph, 0), None);
+}
+
+/// Un aller-retour entre un pool AMM au prix du marché et un pool d'un autre type,
+/// où le token 1 est 5 % moins cher.
+fn assert_two_hop_cycle_through(cheap_pool: Pool, dex: Dex) {
+    let (mint_a, mint_b) = cheap_pool.get_mints();
+    let graph = graph_of(vec![amm_pool(mint_a, mint_b, DEPTH, DEPTH), cheap_pool]);
+    let start = graph.token_map[&mint_a];
+
+    let cycle = find_negative_cycle(&graph, start).expect("a negative cycle exists");
+    assert_eq!(cycle.pools, vec![1, 0]);
+    assert_eq!(graph.pools[cycle.pools[0]].dex(), dex);
+    assert_profitable_cycle(&graph, &cycle);
+}
+
+#[test]
+fn raydium_clmm_pools_take_part_in_the_search() {
+    let m = mints(2);
+    assert_two_hop_cycle_through(clmm_pool(m[0], m[1], 1.05, DEPTH as u128), Dex::RaydiumClmm);
+}
+
+#[test]
+fn orca_whirlpools_take_part_in_the_search() {
+    let m = mints(2);
+    assert_two_hop_cycle_through(whirlpool_pool(m[0], m[1], 1.05, DEPTH as u128), Dex::OrcaWhirlpool);
+}
+
+#[test]
+fn cycle_through_mixed_pool_types_is_found() {
+    let m = mints(3);
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        clmm_pool(m[1], m[2], 2.0, DEPTH as u128),
+        // 1 token 2 vaut 0.5 token 0 ailleurs, mais 0.55 ici.
+        whirlpool_pool(m[2], m[0], 0.55, DEPTH as u128),
+    ]);
+    let start = graph.token_map[&m[0]];
+
+    let cycle = find_negative_cycle(&graph, start).expect("a negative cycle exists");
+    assert_eq!(cycle.hops(), 3);
+    let mut dexes: Vec<String> = cycle.pools.iter().map(|&p| graph.pools[p].dex().to_string()).collect();
+    dexes.sort();
+    assert_eq!(dexes, vec!["Orca Whirlpool", "Raydium AMM v4", "Raydium CLMM"]);
+    assert_profitable_cycle(&graph, &cycle);
+}
+
+#[test]
+fn parallel_search_merges_cycles_from_every_base_token() {
+    let m = mints(4);
+    // Deux composantes indépendantes, chacune avec son propre aller-retour rentable.
+    let graph = graph_of(vec![
+        amm_pool(m[0], m[1], DEPTH, DEPTH),
+        amm_pool(m[0], m[1], DEPTH, DEPTH * 105 / 100),
+        amm_pool(m[2], m[3], DEPTH, DEPTH),
+        amm_pool(m[2], m[3], DEPTH, DEPTH * 105 / 100),
+    ]);
+    let sizing = EdgeSizing::Fixed(TEST_SWAP_AMOUNT);
+
+    // Le même cycle est trouvé depuis m[0] et m[1] : il n'est gardé qu'une fois.
+    let bases = [m[0], m[1], m[2], m[3]];
+    for workers in [1, 4] {
+        let cycles = find_negative_cycles(&graph, &bases, &sizing, workers);
+        assert_eq!(cycles.len(), 2, "workers = {}", workers);
+        assert_eq!(cycles[0].tokens[0], graph.token_map[&m[0]]);
+        assert_eq!(cycles[1].tokens[0], graph.token_map[&m[2]]);
+        for cycle in &cycles {
+            assert_profitable_cycle(&graph, cycle);
+        }
+    }
+
+    // Les tokens de base absents du graphe sont ignorés.
+    assert!(find_negative_cycles(&graph, &[Pubkey::new_unique()], &sizing, 2).is_empty());
+}
@@ -0,0 +1,135 @@
+// tests/state.rs
+
+mod common;
+
+use common::{amm_pool, graph_of, mints};
+use mev_scalpel::decoders::{Pool, PoolOperations, RaydiumAmmPool};
+use mev_scalpel::state::{AppState, GraphUpdate};
+use solana_sdk::pubkey::Pubkey;
+
+const DEPTH: u64 = 1_000_000_000_000;
+
+fn update(version: u64) -> GraphUpdate {
+    GraphUpdate { version, ..Default::default() }
+}
+
+#[test]
+fn versions_increase_and_the_slot_never_goes_back() {
+    let state = AppState::new();
+    assert_eq!((state.version(), state.slot()), (0, 0));
+
+    assert_eq!(state.publish(graph_of(Vec::new()), 100, Vec::new()).unwrap(), 1);
+    assert_eq!(state.publish(graph_of(Vec::new()), 105, Vec::new()).unwrap(), 2);
+    // Deux versions observées au même slot sont toutes deux publiées.
+    assert_eq!(state.publish(graph_of(Vec::new()), 105, Vec::new()).unwrap(), 3);
+    assert_eq!((state.version(), state.slot()), (3, 105));
+}
+
+#[tokio::test]
+async fn out_of_order_publish_is_rejected() {
+    let state = AppState::new();
+    let mut updates = state.subscribe();
+    let m = mints(2);
+    let newer = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH)]);
+    let newer_id = newer.pools[0].get_id();
+    state.publish(newer, 105, vec![newer_id]).unwrap();
+    updates.borrow_and_update();
+
+    // Un graphe observé plus tôt arrive en retard : il ne remplace pas le plus récent.
+    let late = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH), amm_pool(m[0], m[1], DEPTH, DEPTH)]);
+    assert!(state.publish(late, 102, Vec::new()).is_err());
+    assert_eq!((state.version(), state.slot()), (1, 105));
+    assert_eq!(state.graph.load().pools.len(), 1);
+    assert_eq!(state.graph.load().pools[0].get_id(), newer_id);
+    // Les abonnés ne sont pas réveillés pour rien.
+    assert!(!updates.has_changed().unwrap());
+}
+
+#[test]
+fn replacing_a_pool_rebuilds_its_edges_only_when_its_mints_change() {
+    let m = mints(3);
+    let mut graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH)]);
+    let Pool::RaydiumAmm(original) = graph.pools[0].clone() else { unreachable!() };
+
+    // Mêmes mints : seul l'état change, les arêtes restent telles quelles.
+    let refreshed = Pool::RaydiumAmm(RaydiumAmmPool { mint_a_reserve: DEPTH / 2, ..original.clone() });
+    assert_eq!(graph.add_pool(refreshed), 0);
+    assert_eq!((graph.nodes[0].len(), graph.nodes[1].len()), (1, 1));
+
+    // Le même id réapparaît sur d'autres mints : les arêtes suivent, sans doublon ni reste.
+    let moved = Pool::RaydiumAmm(RaydiumAmmPool { mint_b: m[2], ..original });
+    assert_eq!(graph.add_pool(moved), 0);
+    assert_eq!(graph.pools.len(), 1);
+    let (m0, m1, m2) = (graph.token_map[&m[0]], graph.token_map[&m[1]], graph.token_map[&m[2]]);
+    assert!(graph.nodes[m1].is_empty());
+    let destinations = |token: usize| graph.nodes[token].iter().map(|edge| (edge.destination, edge.pool_index)).collect::<Vec<_>>();
+    assert_eq!(destinations(m0), vec![(m2, 0)]);
+    assert_eq!(destinations(m2), vec![(m0, 0)]);
+}
+
+#[test]
+fn concurrent_publishers_get_distinct_consecutive_versions() {
+    let state = AppState::new();
+    let handles: Vec<_> = (0..8)
+        .map(|_| {
+            let state = state.clone();
+            std::thread::spawn(move || {
+                (0..50).map(|_| state.publish(graph_of(Vec::new()), 7, Vec::new()).unwrap()).collect::<Vec<_>>()
+            })
+        })
+        .collect();
+    let mut versions: Vec<u64> = handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect();
+    versions.sort();
+    assert_eq!(versions, (1..=400).collect::<Vec<_>>());
+    assert_eq!((state.version(), state.slot()), (400, 7));
+}
+
+#[tokio::test]
+async fn subscriber_receives_the_changed_pools_with_the_matching_graph() {
+    let state = AppState::new();
+    let mut updates = state.subscribe();
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH)]);
+    let pool_id = graph.pools[0].get_id();
+
+    let publisher = state.clone();
+    tokio::spawn(async move { publisher.publish(graph, 7, vec![pool_id]).unwrap() });
+    updates.changed().await.unwrap();
+
+    let (update, graph) = {
+        let update = updates.borrow_and_update();
+        (update.clone(), state.graph.load_full())
+    };
+    assert_eq!((update.version, update.slot), (1, 7));
+    assert_eq!(update.changed_pools, vec![pool_id]);
+    assert_eq!(graph.pools[0].get_id(), pool_id);
+    assert!(!update.missed_versions_since(0));
+}
+
+#[tokio::test]
+async fn slow_subscriber_only_sees_the_latest_version() {
+    let state = AppState::new();
+    let mut updates = state.subscribe();
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    state.publish(graph_of(Vec::new()), 1, vec![first]).unwrap();
+    state.publish(graph_of(Vec::new()), 2, vec![second]).unwrap();
+
+    // Le canal ne garde que la dernière valeur : les pools de la version 1 sont perdus.
+    updates.changed().await.unwrap();
+    let update = updates.borrow_and_update().clone();
+    assert_eq!(update.version, 2);
+    assert_eq!(update.changed_pools, vec![second]);
+    assert!(update.missed_versions_since(0));
+    assert!(!update.missed_versions_since(1));
+}
+
+#[test]
+fn missed_versions_are_counted_from_the_last_seen_one() {
+    assert!(!update(1).missed_versions_since(0));
+    assert!(update(2).missed_versions_since(0));
+    assert!(!update(5).missed_versions_since(4));
+    assert!(update(5).missed_versions_since(3));
+    // Une version déjà vue (ou plus ancienne) n'est pas un saut.
+    assert!(!update(5).missed_versions_since(5));
+    assert!(!update(5).missed_versions_since(9));
+}
@@ -0,0 +1,101 @@
+// tests/strategy_runner.rs
+
+mod common;
+
+use common::{amm_pool, graph_of, mints};
+use mev_scalpel::decoders::PoolOperations;
+use mev_scalpel::execution::costs::CostModel;
+use mev_scalpel::state::{AppState, GraphUpdate, MarketGraph};
+use mev_scalpel::strategies::runner::{build_strategies, StrategyRunner};
+use solana_sdk::pubkey::Pubkey;
+use tokio::sync::mpsc;
+
+const DEPTH: u64 = 1_000_000_000_000;
+const MAX_AMOUNT: u64 = 100_000_000_000;
+
+/// Deux pools alignés sur la même paire.
+fn aligned_graph() -> (MarketGraph, Vec<Pubkey>) {
+    let m = mints(2);
+    (graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH), amm_pool(m[0], m[1], DEPTH, DEPTH)]), m)
+}
+
+/// Le même graphe, après qu'un gros swap a déséquilibré le premier pool.
+fn skewed(graph: &MarketGraph, mint: &Pubkey) -> MarketGraph {
+    let mut skewed = graph.clone();
+    skewed.pools[0].apply_swap(mint, DEPTH / 10).unwrap();
+    skewed
+}
+
+fn update(version: u64, changed_pools: Vec<Pubkey>) -> GraphUpdate {
+    GraphUpdate { version, slot: 100 + version, changed_pools }
+}
+
+fn names(names: &[&str]) -> Vec<String> {
+    names.iter().map(|name| name.to_string()).collect()
+}
+
+#[test]
+fn each_enabled_strategy_reports_into_the_queue() {
+    let (graph, m) = aligned_graph();
+    let strategies = build_strategies(&names(&["spfa", "bounded_cycles", "spatial"]), &graph, &[m[0]], MAX_AMOUNT, &CostModel::free()).unwrap();
+    let mut runner = StrategyRunner::new(strategies);
+    assert_eq!(runner.strategy_names(), vec!["spfa", "bounded_cycles", "spatial"]);
+
+    let all_pools: Vec<Pubkey> = graph.pools.iter().map(|pool| pool.get_id()).collect();
+    assert!(runner.process(&graph, &update(1, all_pools)).is_empty());
+
+    let skewed = skewed(&graph, &m[1]);
+    let queued = runner.process(&skewed, &update(2, vec![graph.pools[0].get_id()]));
+    let mut strategies: Vec<&str> = queued.iter().map(|q| q.strategy).collect();
+    strategies.sort_unstable();
+    strategies.dedup();
+    assert_eq!(strategies, vec!["bounded_cycles", "spatial", "spfa"]);
+    assert!(queued.iter().all(|q| {
+        let opportunity = &q.opportunity;
+        opportunity.graph_version == 2 && opportunity.slot == 102 && opportunity.expected_profit > 0
+    }));
+}
+
+#[test]
+fn incremental_strategies_only_look_at_changed_pools() {
+    let (graph, m) = aligned_graph();
+    let strategies = build_strategies(&names(&["bounded_cycles", "spatial"]), &graph, &[m[0]], MAX_AMOUNT, &CostModel::free()).unwrap();
+    let mut runner = StrategyRunner::new(strategies);
+    runner.process(&graph, &update(1, Vec::new()));
+
+    // Le pool déséquilibré n'est pas annoncé comme modifié : rien n'est re-coté.
+    let skewed = skewed(&graph, &m[1]);
+    assert!(runner.process(&skewed, &update(2, vec![Pubkey::new_unique()])).is_empty());
+
+    // Une version manquée : les stratégies réévaluent tout, et trouvent l'opportunité.
+    assert!(!runner.process(&skewed, &update(4, vec![Pubkey::new_unique()])).is_empty());
+}
+
+#[test]
+fn unknown_strategy_names_are_rejected() {
+    let (graph, m) = aligned_graph();
+    assert!(build_strategies(&names(&["spatial", "sandwich"]), &graph, &[m[0]], MAX_AMOUNT, &CostModel::free()).is_err());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn runner_follows_app_state_updates() {
+    let (graph, m) = aligned_graph();
+    let strategies = build_strategies(&names(&["bounded_cycles"]), &graph, &[m[0]], MAX_AMOUNT, &CostModel::free()).unwrap();
+    let state = AppState::new();
+    state.publish(graph.clone(), 1, Vec::new()).unwrap();
+
+    let (queue_tx, mut queue_rx) = mpsc::channel(16);
+    let runner = tokio::spawn(StrategyRunner::new(strategies).run(state.clone(), queue_tx));
+
+    state.publish(skewed(&graph, &m[1]), 2, vec![graph.pools[0].get_id()]).unwrap();
+    let queued = tokio::time::timeout(std::time::Duration::from_secs(5), queue_rx.recv())
+        .await
+        .expect("no opportunity received")
+        .unwrap();
+    assert_eq!((queued.strategy, queued.opportunity.graph_version), ("bounded_cycles", 2));
+
+    // Fermer la file arrête le runner.
+    drop(queue_rx);
+    state.publish(skewed(&graph, &m[1]), 3, vec![graph.pools[0].get_id()]).unwrap();
+    tokio::time::timeout(std::time::Duration::from_secs(5), runner).await.unwrap().unwrap();
+}
@@ -0,0 +1,269 @@
+// tests/swap_instructions.rs
+
+mod common;
+
+use base64::Engine;
+use common::{amm_pool, graph_of, mints, whirlpool_pool};
+use mev_scalpel::decoders::swap_instructions::{
+    decode_swaps, observed_swaps, SwapDirection, TransactionView,
+};
+use mev_scalpel::decoders::{
+    associated_token_address, instruction_discriminator, Dex, PoolOperations, ORCA_WHIRLPOOL_PROGRAM_ID, RAYDIUM_AMM_V4_PROGRAM_ID,
+    RAYDIUM_CLMM_PROGRAM_ID, RAYDIUM_CPMM_PROGRAM_ID,
+};
+use serde_json::json;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::{Transaction, VersionedTransaction};
+use solana_transaction_status_client_types::EncodedConfirmedTransactionWithStatusMeta;
+
+const DEPTH: u64 = 1_000_000_000_000;
+
+fn instruction(program_id: Pubkey, accounts: &[Pubkey], data: Vec<u8>) -> Instruction {
+    let metas = accounts.iter().map(|&key| AccountMeta::new(key, false)).collect();
+    Instruction { program_id, accounts: metas, data }
+}
+
+fn unique_keys(n: usize) -> Vec<Pubkey> {
+    (0..n).map(|_| Pubkey::new_unique()).collect()
+}
+
+/// Les arguments communs aux swaps Anchor (CLMM, Whirlpool) :
+/// amount, other_amount_threshold, sqrt_price_limit, puis les drapeaux.
+fn anchor_swap_data(name: &str, amount: u64, threshold: u64, flags: &[bool]) -> Vec<u8> {
+    let mut data = instruction_discriminator(name).to_vec();
+    data.extend(amount.to_le_bytes());
+    data.extend(threshold.to_le_bytes());
+    data.extend(0u128.to_le_bytes());
+    data.extend(flags.iter().map(|&flag| flag as u8));
+    data
+}
+
+/// Une transaction exécutée, au format de `getTransaction` (encodage base64).
+/// Les soldes sont donnés en (compte, mint, avant, après).
+fn encoded_transaction(
+    instructions: &[Instruction],
+    inner: &[(usize, Instruction)],
+    balances: &[(Pubkey, Pubkey, u64, u64)],
+) -> EncodedConfirmedTransactionWithStatusMeta {
+    let message = Message::new(instructions, Some(&Pubkey::new_unique()));
+    let index_of = |key: &Pubkey| message.account_keys.iter().position(|k| k == key).unwrap();
+
+    let inner_json: Vec<_> = inner
+        .iter()
+        .map(|(outer, ix)| {
+            json!({
+                "index": outer,
+                "instructions": [{
+                    "programIdIndex": index_of(&ix.program_id),
+                    "accounts": ix.accounts.iter().map(|meta| index_of(&meta.pubkey)).collect::<Vec<_>>(),
+                    "data": bs58::encode(&ix.data).into_string(),
+                    "stackHeight": 2,
+                }],
+            })
+        })
+        .collect();
+    let token_balance = |account: &Pubkey, mint: &Pubkey, amount: u64| {
+        json!({
+            "accountIndex": index_of(account),
+            "mint": mint.to_string(),
+            "uiTokenAmount": { "uiAmount": null, "decimals": 6, "amount": amount.to_string(), "uiAmountString": "" },
+        })
+    };
+    let pre: Vec<_> = balances.iter().map(|(account, mint, pre, _)| token_balance(account, mint, *pre)).collect();
+    let post: Vec<_> = balances.iter().map(|(account, mint, _, post)| token_balance(account, mint, *post)).collect();
+
+    let transaction = VersionedTransaction::from(Transaction::new_unsigned(message));
+    let blob = base64::engine::general_purpose::STANDARD.encode(bincode::serialize(&transaction).unwrap());
+    serde_json::from_value(json!({
+        "slot": 1,
+        "transaction": [blob, "base64"],
+        "meta": {
+            "err": null,
+            "status": { "Ok": null },
+            "fee": 5000,
+            "preBalances": [],
+            "postBalances": [],
+            "innerInstructions": inner_json,
+            "preTokenBalances": pre,
+            "postTokenBalances": post,
+            "loadedAddresses": { "writable": [], "readonly": [] },
+        },
+        "blockTime": null,
+    }))
+    .unwrap()
+}
+
+#[test]
+fn raydium_amm_swap_is_resolved_from_token_balances() {
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH)]);
+    let pool_id = graph.pools[0].get_id();
+    let (vault_a, vault_b) = graph.pools[0].vaults();
+
+    // 18 comptes : ..., amm (1), ..., user_source (15), user_dest (16), user_owner (17).
+    let mut accounts = unique_keys(18);
+    accounts[1] = pool_id;
+    accounts[5] = vault_a;
+    accounts[6] = vault_b;
+    let (user_source, user_dest, user_owner) = (accounts[15], accounts[16], accounts[17]);
+    let mut data = vec![9];
+    data.extend(5_000u64.to_le_bytes());
+    data.extend(4_900u64.to_le_bytes());
+
+    let encoded = encoded_transaction(
+        &[instruction(RAYDIUM_AMM_V4_PROGRAM_ID, &accounts, data)],
+        &[],
+        &[
+            (user_source, m[1], 10_000, 5_000),
+            (user_dest, m[0], 0, 4_980),
+            (vault_a, m[0], DEPTH, DEPTH - 4_980),
+            (vault_b, m[1], DEPTH, DEPTH + 5_000),
+        ],
+    );
+    let view = TransactionView::from_encoded(&encoded).unwrap();
+
+    let swaps = decode_swaps(&view);
+    assert_eq!(swaps.len(), 1);
+    assert_eq!(swaps[0].swap.dex, Dex::RaydiumAmmV4);
+    assert_eq!(swaps[0].swap.direction, SwapDirection::UserSource { account: user_source, owner: user_owner });
+    assert_eq!((swaps[0].swap.amount, swaps[0].swap.other_amount_threshold), (5_000, 4_900));
+
+    let resolved = swaps[0].resolve(&graph, &view).unwrap();
+    assert_eq!((resolved.input_mint, resolved.output_mint), (m[1], m[0]));
+    assert_eq!((resolved.amount_in, resolved.amount_out), (5_000, Some(4_980)));
+}
+
+#[test]
+fn clmm_swap_emitted_by_an_aggregator_is_found_in_inner_instructions() {
+    let vault_in = Pubkey::new_unique();
+    // payer, amm_config, pool_state, input_account, output_account, input_vault, output_vault, observation, token_program
+    let mut accounts = unique_keys(9);
+    accounts[5] = vault_in;
+    let clmm_swap = instruction(RAYDIUM_CLMM_PROGRAM_ID, &accounts, anchor_swap_data("swap_v2", 7_000, 6_500, &[true]));
+
+    // L'agrégateur reçoit tous les comptes du swap (et le programme CLMM) pour pouvoir faire le CPI.
+    let mut router_accounts = accounts.clone();
+    router_accounts.push(RAYDIUM_CLMM_PROGRAM_ID);
+    let router = instruction(Pubkey::new_unique(), &router_accounts, vec![1, 2, 3]);
+
+    let encoded = encoded_transaction(&[router], &[(0, clmm_swap)], &[]);
+    let swaps = decode_swaps(&TransactionView::from_encoded(&encoded).unwrap());
+
+    assert_eq!(swaps.len(), 1);
+    assert_eq!((swaps[0].instruction_index, swaps[0].inner_index), (0, Some(0)));
+    let swap = swaps[0].swap;
+    assert_eq!(swap.dex, Dex::RaydiumClmm);
+    assert_eq!(swap.pool_id, accounts[2]);
+    assert_eq!(swap.direction, SwapDirection::InputVault(vault_in));
+    assert!(swap.exact_in);
+    assert_eq!((swap.amount, swap.other_amount_threshold), (7_000, 6_500));
+}
+
+#[test]
+fn exact_out_whirlpool_swap_reads_the_input_from_vault_balances() {
+    let m = mints(2);
+    let graph = graph_of(vec![whirlpool_pool(m[0], m[1], 1.0, DEPTH as u128)]);
+    let (vault_a, vault_b) = graph.pools[0].vaults();
+
+    // token_program, authority, whirlpool, owner_a, vault_a, owner_b, vault_b, tick_arrays (3), oracle
+    let mut accounts = unique_keys(11);
+    accounts[2] = graph.pools[0].get_id();
+    accounts[4] = vault_a;
+    accounts[6] = vault_b;
+    // On veut exactement 3 000 de token A, en payant au plus 3 100 de token B.
+    let data = anchor_swap_data("swap", 3_000, 3_100, &[false, false]);
+
+    let encoded = encoded_transaction(
+        &[instruction(ORCA_WHIRLPOOL_PROGRAM_ID, &accounts, data)],
+        &[],
+        &[(vault_a, m[0], DEPTH, DEPTH - 3_000), (vault_b, m[1], DEPTH, DEPTH + 3_010)],
+    );
+    let view = TransactionView::from_encoded(&encoded).unwrap();
+
+    let resolved = decode_swaps(&view)[0].resolve(&graph, &view).unwrap();
+    assert_eq!((resolved.input_mint, resolved.output_mint), (m[1], m[0]));
+    assert_eq!((resolved.amount_in, resolved.amount_out), (3_010, Some(3_000)));
+    assert_eq!(observed_swaps(&view, &graph)[0].amount_in, 3_010);
+}
+
+#[test]
+fn pending_transactions_and_unknown_pools() {
+    let m = mints(2);
+    let graph = graph_of(vec![whirlpool_pool(m[0], m[1], 1.0, DEPTH as u128)]);
+
+    // Un swap Whirlpool sur un pool absent du graphe : décodé, mais pas rattaché.
+    let unknown_pool_accounts = unique_keys(11);
+    let unknown_pool_data = anchor_swap_data("swap", 1_000, 900, &[true, true]);
+
+    // Un swap CPMM : aucun pool CPMM n'est coté, l'instruction est ignorée.
+    let mut cpmm_accounts = unique_keys(13);
+    cpmm_accounts[10] = m[0];
+    let mut cpmm_data = instruction_discriminator("swap_base_input").to_vec();
+    cpmm_data.extend(1_000u64.to_le_bytes());
+    cpmm_data.extend(900u64.to_le_bytes());
+
+    // Un swap Whirlpool A -> B à entrée exacte, résolu sans métadonnées.
+    let mut whirlpool_accounts = unique_keys(11);
+    whirlpool_accounts[2] = graph.pools[0].get_id();
+    let whirlpool_data = anchor_swap_data("swap", 2_000, 1_900, &[true, true]);
+
+    // Une instruction d'un autre programme, et un discriminateur inconnu : ignorés.
+    let unrelated = instruction(Pubkey::new_unique(), &unique_keys(3), vec![9; 17]);
+    let unknown = instruction(ORCA_WHIRLPOOL_PROGRAM_ID, &whirlpool_accounts, anchor_swap_data("two_hop_swap", 1, 1, &[true, true]));
+
+    let message = Message::new(
+        &[
+            instruction(ORCA_WHIRLPOOL_PROGRAM_ID, &unknown_pool_accounts, unknown_pool_data),
+            instruction(RAYDIUM_CPMM_PROGRAM_ID, &cpmm_accounts, cpmm_data),
+            unrelated,
+            unknown,
+            instruction(ORCA_WHIRLPOOL_PROGRAM_ID, &whirlpool_accounts, whirlpool_data),
+        ],
+        Some(&Pubkey::new_unique()),
+    );
+    let transaction = VersionedTransaction::from(Transaction::new_unsigned(message));
+    let view = TransactionView::from_transaction(&transaction, None);
+
+    let swaps = decode_swaps(&view);
+    assert_eq!(swaps.iter().map(|s| s.instruction_index).collect::<Vec<_>>(), vec![0, 4]);
+    assert_eq!(swaps[0].swap.pool_id, unknown_pool_accounts[2]);
+    assert!(swaps[0].resolve(&graph, &view).is_none());
+
+    let resolved = swaps[1].resolve(&graph, &view).unwrap();
+    assert_eq!((resolved.input_mint, resolved.amount_in, resolved.amount_out), (m[0], 2_000, None));
+    assert_eq!(observed_swaps(&view, &graph).len(), 1);
+}
+
+#[test]
+fn pending_amm_swap_is_resolved_only_from_the_owner_ata() {
+    let m = mints(2);
+    let graph = graph_of(vec![amm_pool(m[0], m[1], DEPTH, DEPTH)]);
+    let owner = Pubkey::new_unique();
+    let mut data = vec![9];
+    data.extend(5_000u64.to_le_bytes());
+    data.extend(4_900u64.to_le_bytes());
+
+    let pending = |source: Pubkey| {
+        let mut accounts = unique_keys(18);
+        accounts[1] = graph.pools[0].get_id();
+        accounts[15] = source;
+        accounts[17] = owner;
+        let message = Message::new(&[instruction(RAYDIUM_AMM_V4_PROGRAM_ID, &accounts, data.clone())], Some(&owner));
+        TransactionView::from_transaction(&VersionedTransaction::from(Transaction::new_unsigned(message)), None)
+    };
+
+    // Sans soldes, l'ATA du propriétaire suffit à retrouver le mint d'entrée.
+    let view = pending(associated_token_address(&owner, &m[1]));
+    let resolved = decode_swaps(&view)[0].resolve(&graph, &view).unwrap();
+    assert_eq!((resolved.input_mint, resolved.output_mint), (m[1], m[0]));
+    assert_eq!((resolved.amount_in, resolved.amount_out), (5_000, None));
+    assert_eq!(observed_swaps(&view, &graph).len(), 1);
+
+    // Un compte source quelconque (WSOL temporaire…) ne peut pas être rattaché : le swap est écarté.
+    let view = pending(Pubkey::new_unique());
+    assert_eq!(decode_swaps(&view).len(), 1);
+    assert!(decode_swaps(&view)[0].resolve(&graph, &view).is_none());
+    assert!(observed_swaps(&view, &graph).is_empty());
+}